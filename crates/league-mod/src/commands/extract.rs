@@ -1,17 +1,21 @@
+use std::collections::HashSet;
 use std::fs::File;
 
 use crate::errors::CliError;
 use crate::println_pad;
-use crate::utils::config::load_config;
+use crate::utils::config::{default_hashtable_cache_dir, load_config};
+use crate::utils::disk_space::ensure_free_space;
 use camino::{Utf8Path, Utf8PathBuf};
 use colored::Colorize;
-use ltk_fantome::{FantomeExtractError, FantomeExtractor, WadHashtable};
+use ltk_fantome::{format_chunk_path_hash, FantomeExtractError, FantomeExtractor, WadHashtable};
 use ltk_modpkg::{Modpkg, ModpkgExtractor};
 use miette::{IntoDiagnostic, Result};
 
 pub struct ExtractModPackageArgs {
     pub file_path: String,
     pub output_dir: Option<String>,
+    /// Only load hashtable entries whose path contains this text.
+    pub hash_filter: Option<String>,
 }
 
 /// Compute the default output directory: parent folder + file stem
@@ -62,6 +66,7 @@ pub fn extract_mod_package(args: ExtractModPackageArgs) -> Result<()> {
         "📁 Extracting to:".bright_yellow(),
         output_dir.as_str().bright_white().bold()
     );
+    ensure_free_space(&output_dir, extractor.required_space())?;
     extractor.extract_all(output_dir).into_diagnostic()?;
 
     println_pad!("{}", "✅ Extraction complete!".bright_green().bold());
@@ -80,17 +85,38 @@ fn extract_fantome_package(args: ExtractModPackageArgs) -> Result<()> {
         file_path.as_str().bright_cyan().bold()
     );
 
+    let mut extractor = FantomeExtractor::new(file).map_err(map_fantome_error)?;
+
     // Load hashtable from config if available
     let config = load_config();
-    let hashtable = config.hashtable_dir.and_then(|dir| {
-        if dir.exists() {
+    let hashtable_dir = config.hashtable_dir.filter(|dir| dir.exists());
+    // Only the paths of chunks in packed WADs are looked up, WAD folders already have theirs
+    let chunk_path_hashes = match &hashtable_dir {
+        Some(_) => extractor.chunk_path_hashes().map_err(map_fantome_error)?,
+        None => HashSet::new(),
+    };
+    let hash_filter = args.hash_filter;
+    let hashtable = hashtable_dir.and_then(|dir| {
+        if chunk_path_hashes.is_empty() {
+            None
+        } else {
             println_pad!(
                 "{} {}",
                 "📖 Loading WAD hashtable from:".bright_cyan(),
                 dir.as_str().bright_white()
             );
-            match WadHashtable::from_directory(&dir) {
-                Ok(ht) => Some(ht),
+            let mut hashtable = WadHashtable::new().with_hash_filter(chunk_path_hashes);
+            if let Some(cache_dir) = default_hashtable_cache_dir() {
+                hashtable = hashtable.with_cache_dir(cache_dir);
+            }
+            if let Some(filter) = hash_filter {
+                hashtable = hashtable.with_path_filter(filter);
+            }
+            match hashtable.add_from_dir(&dir) {
+                Ok(()) => {
+                    report_hashtable_conflicts(&hashtable);
+                    Some(hashtable)
+                }
                 Err(e) => {
                     println_pad!(
                         "{} {}",
@@ -100,8 +126,6 @@ fn extract_fantome_package(args: ExtractModPackageArgs) -> Result<()> {
                     None
                 }
             }
-        } else {
-            None
         }
     });
 
@@ -116,9 +140,9 @@ fn extract_fantome_package(args: ExtractModPackageArgs) -> Result<()> {
         output_dir.as_str().bright_white().bold()
     );
 
-    let mut extractor = FantomeExtractor::new(file)
-        .map_err(map_fantome_error)?
-        .with_hashtable_opt(hashtable);
+    let mut extractor = extractor.with_hashtable_opt(hashtable);
+    let required_space = extractor.uncompressed_size().map_err(map_fantome_error)?;
+    ensure_free_space(&output_dir, required_space)?;
     extractor
         .extract_to(output_dir.as_std_path())
         .map_err(map_fantome_error)?;
@@ -126,6 +150,36 @@ fn extract_fantome_package(args: ExtractModPackageArgs) -> Result<()> {
     Ok(())
 }
 
+/// Warn about hashtable entries that disagree on the path of a hash.
+fn report_hashtable_conflicts(hashtable: &WadHashtable) {
+    const MAX_LISTED_CONFLICTS: usize = 10;
+
+    let conflicts = hashtable.conflicts();
+    if conflicts.is_empty() {
+        return;
+    }
+
+    println_pad!(
+        "{} {}",
+        "   Warning: Conflicting hashtable entries:".bright_yellow(),
+        conflicts.len().to_string().bright_red()
+    );
+    for conflict in conflicts.iter().take(MAX_LISTED_CONFLICTS) {
+        println_pad!(
+            "     {} {} -> {}",
+            format_chunk_path_hash(conflict.path_hash).bright_white(),
+            conflict.previous_path.bright_black(),
+            conflict.path.bright_white()
+        );
+    }
+    if conflicts.len() > MAX_LISTED_CONFLICTS {
+        println_pad!(
+            "     {}",
+            format!("... and {} more", conflicts.len() - MAX_LISTED_CONFLICTS).bright_black()
+        );
+    }
+}
+
 /// Map FantomeExtractError to CliError for user-friendly error messages.
 fn map_fantome_error(err: FantomeExtractError) -> CliError {
     match err {
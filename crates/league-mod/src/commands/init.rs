@@ -102,6 +102,7 @@ fn create_default_mod_project(name: Option<String>, display_name: Option<String>
         transformers: vec![],
         layers: ltk_mod_project::default_layers(),
         thumbnail: None,
+        wad_ignore: vec![],
     }
 }
 
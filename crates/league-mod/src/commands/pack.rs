@@ -1,7 +1,10 @@
 use crate::println_pad;
 use crate::{
     errors::CliError,
-    utils::{validate_mod_name, validate_version_format},
+    utils::{
+        disk_space::{dir_size, ensure_free_space},
+        validate_mod_name, validate_version_format,
+    },
 };
 use camino::{Utf8Path, Utf8PathBuf};
 use colored::Colorize;
@@ -76,6 +79,8 @@ fn pack_to_modpkg(
         }
     }
 
+    ensure_free_space(&output_dir, packed_size_estimate(project_root))?;
+
     let modpkg_file_name = modpkg_project::create_file_name(&mod_project, args.file_name);
     let output_path = output_dir.join(&modpkg_file_name);
 
@@ -106,6 +111,18 @@ fn convert_pack_error(err: PackError, project_root: &Utf8Path) -> miette::Report
         PackError::ConfigNotFound(_) => {
             CliError::config_not_found(project_root.as_std_path().to_owned()).into()
         }
+        PackError::ChunkPathCollision {
+            layer,
+            path_hash,
+            first,
+            second,
+        } => CliError::chunk_path_collision(
+            layer,
+            path_hash,
+            first.into_std_path_buf(),
+            second.into_std_path_buf(),
+        )
+        .into(),
         other => miette!("Failed to pack mod: {}", other),
     }
 }
@@ -141,6 +158,8 @@ fn pack_to_fantome_format(
         std::fs::create_dir_all(&output_dir).into_diagnostic()?;
     }
 
+    ensure_free_space(&output_dir, packed_size_estimate(project_root))?;
+
     let fantome_file_name = ltk_fantome::create_file_name(&mod_project, args.file_name);
     let output_path = output_dir.join(&fantome_file_name);
 
@@ -197,6 +216,11 @@ fn warn_about_unsupported_layers(mod_project: &ModProject) {
     }
 }
 
+/// Upper bound for the size of a package built from the project: its uncompressed content.
+fn packed_size_estimate(project_root: &Utf8Path) -> u64 {
+    dir_size(project_root.join("content").as_std_path())
+}
+
 // Config utils
 
 fn resolve_config_path(config_path: Option<String>) -> Result<PathBuf> {
@@ -1,5 +1,6 @@
 #![allow(unused_assignments)]
 
+use crate::utils::disk_space::format_bytes;
 use miette::{Diagnostic, SourceSpan};
 use std::path::PathBuf;
 use thiserror::Error;
@@ -113,6 +114,33 @@ pub enum CliError {
         help("The packed WAD file could not be extracted. The file may be corrupted or in an unsupported format.")
     )]
     WadExtractionFailed { message: String },
+
+    #[error("Files {first} and {second} both map to chunk path hash {path_hash:016x} in layer '{layer_name}'")]
+    #[diagnostic(
+        code(layer::chunk_path_collision),
+        help("Chunk paths are case-insensitive. Rename or remove one of the files so every path in a layer is unique.")
+    )]
+    ChunkPathCollision {
+        layer_name: String,
+        path_hash: u64,
+        first: PathBuf,
+        second: PathBuf,
+    },
+
+    #[error(
+        "Not enough free disk space for {path}: {} needed, {} available",
+        format_bytes(*required),
+        format_bytes(*available)
+    )]
+    #[diagnostic(
+        code(fs::insufficient_space),
+        help("Free up disk space or choose an output directory on another drive")
+    )]
+    InsufficientDiskSpace {
+        path: PathBuf,
+        required: u64,
+        available: u64,
+    },
 }
 
 impl CliError {
@@ -157,6 +185,28 @@ impl CliError {
         Self::DirectoryCreationFailed { path, source }
     }
 
+    pub fn chunk_path_collision(
+        layer_name: String,
+        path_hash: u64,
+        first: PathBuf,
+        second: PathBuf,
+    ) -> Self {
+        Self::ChunkPathCollision {
+            layer_name,
+            path_hash,
+            first,
+            second,
+        }
+    }
+
+    pub fn insufficient_disk_space(path: PathBuf, required: u64, available: u64) -> Self {
+        Self::InsufficientDiskSpace {
+            path,
+            required,
+            available,
+        }
+    }
+
     #[allow(unused)]
     pub fn invalid_base_layer_priority(provided: i32) -> Self {
         Self::InvalidBaseLayerPriority { provided }
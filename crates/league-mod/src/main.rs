@@ -65,6 +65,11 @@ pub enum Commands {
         /// The directory to extract the mod package to (defaults to file name without extension)
         #[arg(short, long)]
         output_dir: Option<String>,
+
+        /// Only load hashtable entries whose path contains this text, e.g. "characters/ahri".
+        /// Lowers memory use when only part of the game is needed
+        #[arg(long)]
+        hash_filter: Option<String>,
     },
     /// Manage application configuration
     Config {
@@ -138,9 +143,11 @@ fn main() -> Result<()> {
         Commands::Extract {
             file_path,
             output_dir,
+            hash_filter,
         } => extract_mod_package(ExtractModPackageArgs {
             file_path,
             output_dir,
+            hash_filter,
         }),
         Commands::Config { action } => match action {
             ConfigAction::Show => config_cmd::show_config(),
@@ -134,3 +134,13 @@ pub fn default_hashtable_dir() -> Option<Utf8PathBuf> {
     path.push("wad_hashtables");
     Utf8PathBuf::from_path_buf(path).ok()
 }
+
+/// Returns the directory where compiled copies of the wad hashtables are kept.
+/// Uses the user's cache folder: LeagueToolkit/hashtable_cache
+pub fn default_hashtable_cache_dir() -> Option<Utf8PathBuf> {
+    let base_dirs = directories_next::BaseDirs::new()?;
+    let mut path = base_dirs.cache_dir().to_path_buf();
+    path.push("LeagueToolkit");
+    path.push("hashtable_cache");
+    Utf8PathBuf::from_path_buf(path).ok()
+}
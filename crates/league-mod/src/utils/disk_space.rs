@@ -0,0 +1,84 @@
+//! Free disk space checks before extracting or packing.
+
+use crate::errors::CliError;
+use camino::Utf8Path;
+use miette::Result;
+use std::path::Path;
+use sysinfo::Disks;
+
+/// Fail with a clear error if the disk `path` is on has less than `required` bytes free.
+///
+/// `path` doesn't need to exist yet. The check passes if the disk can't be determined.
+pub fn ensure_free_space(path: &Utf8Path, required: u64) -> Result<()> {
+    let Some(available) = available_space(path.as_std_path()) else {
+        return Ok(());
+    };
+
+    if available < required {
+        return Err(CliError::insufficient_disk_space(
+            path.as_std_path().to_owned(),
+            required,
+            available,
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Free bytes on the disk with the longest mount point containing `path`.
+fn available_space(path: &Path) -> Option<u64> {
+    let path = std::path::absolute(path).ok()?;
+    let disks = Disks::new_with_refreshed_list();
+
+    disks
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// Total size of the files under `dir`, 0 if it doesn't exist.
+pub fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Format a byte count for messages, e.g. `1.5 GB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", value, UNITS[unit]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_bytes_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+}
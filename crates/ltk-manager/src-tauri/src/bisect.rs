@@ -0,0 +1,207 @@
+//! Finding the mod that crashes the game by bisecting a mod set.
+//!
+//! Each round runs an overlay with half of the remaining candidates for one game. When the
+//! game crashes (reported by the crash watchdog, or by the user through
+//! `report_bisect_result`), the crashing mod is in that half, otherwise it is in the other
+//! one. Once a single mod is left it is recorded as a known crash. This assumes exactly one
+//! mod of the set causes the crash.
+
+use crate::commands::mod_skin::{begin_overlay_run, run_staged_overlay, stop_all_mods};
+use crate::commands::{entry_dir, link_tree, record_crash_inner, sanitize_name, ModListEntry};
+use crate::error::{AppError, AppResult};
+use crate::events::{emit, AppEvent};
+use crate::jobs::{run_job_with_token, JobKind};
+use crate::workspace::WorkspaceLayout;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BisectStatus {
+    /// Mods that may still cause the crash.
+    pub candidates: Vec<ModListEntry>,
+    /// Mods of the overlay for the current round, empty once finished.
+    pub testing: Vec<ModListEntry>,
+    pub round: u32,
+    /// The crashing mod, once isolated.
+    pub culprit: Option<ModListEntry>,
+}
+
+#[derive(Default)]
+pub struct BisectState(Mutex<Option<BisectStatus>>);
+
+impl BisectState {
+    fn lock(&self) -> AppResult<std::sync::MutexGuard<'_, Option<BisectStatus>>> {
+        self.0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))
+    }
+}
+
+pub fn status(app_handle: &AppHandle) -> AppResult<Option<BisectStatus>> {
+    Ok(app_handle.state::<BisectState>().lock()?.clone())
+}
+
+/// Whether a bisect is waiting for the outcome of a game.
+pub fn is_running(app_handle: &AppHandle) -> bool {
+    let state = app_handle.state::<BisectState>();
+    let guard = state.lock();
+    guard.is_ok_and(|session| session.as_ref().is_some_and(|s| s.culprit.is_none()))
+}
+
+/// Start bisecting `mods` and run the overlay for the first round.
+pub async fn start(app_handle: &AppHandle, mods: Vec<ModListEntry>) -> AppResult<BisectStatus> {
+    if mods.len() < 2 {
+        return Err(AppError::ValidationFailed(
+            "Bisecting needs at least two mods".to_string(),
+        ));
+    }
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    if let Some(missing) = mods
+        .iter()
+        .find(|m| !entry_dir(&layout, m).is_some_and(|d| d.is_dir()))
+    {
+        return Err(AppError::ModNotFound(missing.id.clone()));
+    }
+
+    let status = next_round(mods, 0);
+    *app_handle.state::<BisectState>().lock()? = Some(status.clone());
+    tracing::info!("Started bisecting {} mods", status.candidates.len());
+
+    run_round(app_handle, &status).await?;
+    Ok(status)
+}
+
+pub async fn cancel(app_handle: &AppHandle) -> AppResult<()> {
+    let was_running = app_handle.state::<BisectState>().lock()?.take().is_some();
+    if was_running {
+        stop_overlay(app_handle).await;
+        tracing::info!("Bisecting cancelled");
+    }
+    Ok(())
+}
+
+/// Narrow the candidates down with the outcome of the game the current round ran.
+///
+/// Does nothing if no bisect is running.
+pub async fn game_finished(app_handle: &AppHandle, crashed: bool) -> AppResult<()> {
+    let status = {
+        let state = app_handle.state::<BisectState>();
+        let mut session = state.lock()?;
+        let Some(current) = session.as_ref().filter(|s| s.culprit.is_none()) else {
+            return Ok(());
+        };
+
+        let candidates = if crashed {
+            current.testing.clone()
+        } else {
+            current
+                .candidates
+                .iter()
+                .filter(|m| !current.testing.iter().any(|t| same_mod(t, m)))
+                .cloned()
+                .collect()
+        };
+        tracing::info!(
+            "Bisect round {} {}, {} candidate(s) left",
+            current.round,
+            if crashed { "crashed" } else { "passed" },
+            candidates.len()
+        );
+
+        let status = next_round(candidates, current.round);
+        *session = Some(status.clone());
+        status
+    };
+
+    match &status.culprit {
+        Some(culprit) => {
+            tracing::info!("Bisecting isolated mod {}", culprit.id);
+            stop_overlay(app_handle).await;
+            record_crash_inner(
+                app_handle,
+                culprit.id.clone(),
+                culprit.champion_id,
+                Some("Isolated by bisecting a crashing mod set".to_string()),
+            )
+            .await?;
+        }
+        None => run_round(app_handle, &status).await?,
+    }
+
+    emit(app_handle, AppEvent::Bisect { status });
+    Ok(())
+}
+
+/// The status after `round` narrowed the candidates down to `candidates`.
+fn next_round(candidates: Vec<ModListEntry>, round: u32) -> BisectStatus {
+    match candidates.as_slice() {
+        [culprit] => BisectStatus {
+            culprit: Some(culprit.clone()),
+            candidates,
+            testing: Vec::new(),
+            round,
+        },
+        _ => BisectStatus {
+            testing: candidates[..candidates.len() / 2].to_vec(),
+            candidates,
+            round: round + 1,
+            culprit: None,
+        },
+    }
+}
+
+fn same_mod(a: &ModListEntry, b: &ModListEntry) -> bool {
+    a.kind == b.kind && a.id == b.id
+}
+
+/// Stage the mods of the current round and run them as the overlay for the next game.
+async fn run_round(app_handle: &AppHandle, status: &BisectStatus) -> AppResult<()> {
+    let cancel_token = begin_overlay_run(app_handle)
+        .await
+        .map_err(|e| AppError::Other(e.message))?;
+
+    let testing = status.testing.clone();
+    run_job_with_token(
+        app_handle,
+        JobKind::Overlay,
+        format!("Bisect round {}", status.round),
+        None,
+        cancel_token.clone(),
+        |_| async move {
+            let layout = WorkspaceLayout::from_app(app_handle)?;
+            let staging_dir = layout.bisect_staging_dir();
+            let names = stage_mods(&layout, &testing)?;
+            run_staged_overlay(app_handle, &staging_dir, &names, cancel_token).await
+        },
+    )
+    .await
+    .map_err(|e| AppError::Other(format!("{:#}", e)))
+}
+
+/// Link `mods` into the bisect staging directory, returning their folder names there.
+fn stage_mods(layout: &WorkspaceLayout, mods: &[ModListEntry]) -> anyhow::Result<Vec<String>> {
+    let staging_dir = layout.bisect_staging_dir();
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(&staging_dir)?;
+    }
+
+    let mut names = Vec::new();
+    for (index, entry) in mods.iter().enumerate() {
+        let source = entry_dir(layout, entry)
+            .ok_or_else(|| anyhow::anyhow!("Invalid mod id {:?}", entry.id))?;
+        // Ids of different kinds can collide, the index keeps the names unique
+        let name = format!("{}-{}", index, sanitize_name(&entry.id));
+        link_tree(&source, &staging_dir.join(&name), &|_| false)?;
+        names.push(name);
+    }
+
+    Ok(names)
+}
+
+async fn stop_overlay(app_handle: &AppHandle) {
+    if let crate::error::IpcResult::Err { error } = stop_all_mods(app_handle.clone()).await {
+        tracing::warn!("Failed to stop the bisect overlay: {}", error.message);
+    }
+}
@@ -0,0 +1,145 @@
+//! In-memory cache of the skin catalog, champion metadata and mod annotations.
+//!
+//! Browsing asks for the same champions over and over while the user scrolls. The parsed
+//! files are kept until a database refresh or metadata prune rewrites them, or for
+//! annotations until they are saved, and dropped when the workspace changes.
+
+use crate::commands::{
+    load_annotations, load_catalog, load_champion_metadata, AnnotationStore, ChampionMetadata,
+    SkinCatalog,
+};
+use crate::error::AppResult;
+use crate::workspace::WorkspaceLayout;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+#[derive(Default)]
+struct DataCacheInner {
+    /// Bumped whenever the cache is emptied, so reads started before don't store stale data.
+    generation: u64,
+    /// Workspace the cached data was read from.
+    workspace: Option<PathBuf>,
+    catalog: Option<Arc<SkinCatalog>>,
+    /// Metadata by champion id, `None` for champions without a metadata file.
+    metadata: HashMap<i32, Option<Arc<ChampionMetadata>>>,
+    annotations: Option<Arc<AnnotationStore>>,
+}
+
+#[derive(Default)]
+pub struct DataCache(Mutex<DataCacheInner>);
+
+/// Run `f` on the cache of `layout`'s workspace, emptying it first if it holds another one.
+///
+/// A poisoned lock skips the cache, callers then read the files.
+fn with_cache<T>(
+    app_handle: &AppHandle,
+    layout: &WorkspaceLayout,
+    f: impl FnOnce(&mut DataCacheInner) -> Option<T>,
+) -> Option<T> {
+    let state = app_handle.state::<DataCache>();
+    let mut inner = state.0.lock().ok()?;
+    if inner.workspace.as_deref() != Some(layout.root()) {
+        *inner = DataCacheInner {
+            generation: inner.generation + 1,
+            workspace: Some(layout.root().to_path_buf()),
+            ..Default::default()
+        };
+    }
+    f(&mut inner)
+}
+
+fn generation(app_handle: &AppHandle, layout: &WorkspaceLayout) -> Option<u64> {
+    with_cache(app_handle, layout, |inner| Some(inner.generation))
+}
+
+/// The skin catalog, read from disk on first use.
+pub async fn cached_catalog(
+    app_handle: &AppHandle,
+    layout: &WorkspaceLayout,
+) -> AppResult<Arc<SkinCatalog>> {
+    if let Some(catalog) = with_cache(app_handle, layout, |inner| inner.catalog.clone()) {
+        return Ok(catalog);
+    }
+
+    let generation = generation(app_handle, layout);
+    let catalog = Arc::new(load_catalog(layout).await?);
+    with_cache(app_handle, layout, |inner| {
+        (Some(inner.generation) == generation).then(|| inner.catalog = Some(catalog.clone()))
+    });
+    Ok(catalog)
+}
+
+/// The metadata of `champion_id`, read from disk on first use.
+pub async fn cached_champion_metadata(
+    app_handle: &AppHandle,
+    layout: &WorkspaceLayout,
+    champion_id: i32,
+) -> AppResult<Option<Arc<ChampionMetadata>>> {
+    if let Some(metadata) = with_cache(app_handle, layout, |inner| {
+        inner.metadata.get(&champion_id).cloned()
+    }) {
+        return Ok(metadata);
+    }
+
+    let generation = generation(app_handle, layout);
+    let metadata = load_champion_metadata(layout, champion_id)
+        .await?
+        .map(Arc::new);
+    with_cache(app_handle, layout, |inner| {
+        (Some(inner.generation) == generation)
+            .then(|| inner.metadata.insert(champion_id, metadata.clone()))
+    });
+    Ok(metadata)
+}
+
+/// The mod annotations, read from disk on first use.
+///
+/// Annotations only decorate the skin list, so a file that can't be read counts as no
+/// annotations rather than failing the listing. The failure isn't cached.
+pub async fn cached_annotations(
+    app_handle: &AppHandle,
+    layout: &WorkspaceLayout,
+) -> Arc<AnnotationStore> {
+    if let Some(annotations) = with_cache(app_handle, layout, |inner| inner.annotations.clone()) {
+        return annotations;
+    }
+
+    let generation = generation(app_handle, layout);
+    let annotations = match load_annotations(app_handle).await {
+        Ok(annotations) => Arc::new(annotations),
+        Err(e) => {
+            tracing::warn!("Ignoring mod annotations: {}", e);
+            return Arc::default();
+        }
+    };
+    with_cache(app_handle, layout, |inner| {
+        (Some(inner.generation) == generation)
+            .then(|| inner.annotations = Some(annotations.clone()))
+    });
+    annotations
+}
+
+/// Drop the cached annotations, after the annotations file was rewritten.
+pub fn invalidate_annotations(app_handle: &AppHandle) {
+    let state = app_handle.state::<DataCache>();
+    let guard = state.0.lock();
+    if let Ok(mut inner) = guard {
+        // Also keeps a read that started before the write from storing the old file
+        inner.generation += 1;
+        inner.annotations = None;
+    }
+}
+
+/// Drop everything cached, after the files were rewritten.
+pub fn invalidate(app_handle: &AppHandle) {
+    let state = app_handle.state::<DataCache>();
+    let guard = state.0.lock();
+    if let Ok(mut inner) = guard {
+        *inner = DataCacheInner {
+            generation: inner.generation + 1,
+            ..Default::default()
+        };
+    }
+}
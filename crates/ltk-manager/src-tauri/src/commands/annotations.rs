@@ -0,0 +1,211 @@
+use crate::cache::invalidate_annotations;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::workspace::WorkspaceLayout;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+const MAX_RATING: u8 = 5;
+
+/// Serializes read-modify-write cycles on the annotations file.
+static ANNOTATIONS_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// The user's own notes about a mod.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModAnnotation {
+    /// 1 to 5 stars.
+    #[serde(default)]
+    pub rating: Option<u8>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+/// Annotations keyed by mod id (the skin id for repository skins, the index id for browser mods).
+pub(crate) type AnnotationStore = BTreeMap<String, ModAnnotation>;
+
+fn get_annotations_path(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    Ok(WorkspaceLayout::from_app(app_handle)?.annotations_file())
+}
+
+pub(crate) async fn load_annotations(app_handle: &AppHandle) -> AppResult<AnnotationStore> {
+    let path = get_annotations_path(app_handle)?;
+    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Ok(AnnotationStore::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to read annotations file: {}", e)))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::Other(format!("Failed to parse annotations file: {}", e)))
+}
+
+async fn save_annotations(app_handle: &AppHandle, store: &AnnotationStore) -> AppResult<()> {
+    let path = get_annotations_path(app_handle)?;
+    let json = serde_json::to_string_pretty(store)
+        .map_err(|e| AppError::Other(format!("Failed to serialize annotations: {}", e)))?;
+
+    fs::write(&path, json)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to write annotations file: {}", e)))?;
+    invalidate_annotations(app_handle);
+    Ok(())
+}
+
+fn validate_annotation(mod_id: &str, annotation: &ModAnnotation) -> AppResult<()> {
+    if mod_id.trim().is_empty() {
+        return Err(AppError::ValidationFailed("Mod id is empty".to_string()));
+    }
+    if let Some(rating) = annotation.rating {
+        if !(1..=MAX_RATING).contains(&rating) {
+            return Err(AppError::ValidationFailed(format!(
+                "Rating must be between 1 and {}, got {}",
+                MAX_RATING, rating
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Trim the tags and drop empty and duplicate ones, leaving them sorted.
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    // dedup only removes consecutive duplicates
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+#[tauri::command]
+pub async fn get_mod_annotations(
+    app_handle: AppHandle,
+) -> IpcResult<BTreeMap<String, ModAnnotation>> {
+    load_annotations(&app_handle).await.into()
+}
+
+#[tauri::command]
+pub async fn get_mod_annotation(
+    app_handle: AppHandle,
+    mod_id: String,
+) -> IpcResult<Option<ModAnnotation>> {
+    get_mod_annotation_inner(&app_handle, &mod_id).await.into()
+}
+
+async fn get_mod_annotation_inner(
+    app_handle: &AppHandle,
+    mod_id: &str,
+) -> AppResult<Option<ModAnnotation>> {
+    Ok(load_annotations(app_handle).await?.remove(mod_id))
+}
+
+/// Create or replace the annotation of a mod.
+#[tauri::command]
+pub async fn set_mod_annotation(
+    app_handle: AppHandle,
+    mod_id: String,
+    annotation: ModAnnotation,
+) -> IpcResult<ModAnnotation> {
+    set_mod_annotation_inner(&app_handle, mod_id, annotation)
+        .await
+        .into()
+}
+
+async fn set_mod_annotation_inner(
+    app_handle: &AppHandle,
+    mod_id: String,
+    mut annotation: ModAnnotation,
+) -> AppResult<ModAnnotation> {
+    validate_annotation(&mod_id, &annotation)?;
+
+    annotation.tags = normalize_tags(annotation.tags);
+    annotation.updated_at = chrono::Utc::now().to_rfc3339();
+
+    let _guard = ANNOTATIONS_LOCK.lock().await;
+    let mut store = load_annotations(app_handle).await?;
+    store.insert(mod_id, annotation.clone());
+    save_annotations(app_handle, &store).await?;
+
+    Ok(annotation)
+}
+
+#[tauri::command]
+pub async fn delete_mod_annotation(app_handle: AppHandle, mod_id: String) -> IpcResult<()> {
+    delete_mod_annotation_inner(&app_handle, &mod_id)
+        .await
+        .into()
+}
+
+async fn delete_mod_annotation_inner(app_handle: &AppHandle, mod_id: &str) -> AppResult<()> {
+    let _guard = ANNOTATIONS_LOCK.lock().await;
+    let mut store = load_annotations(app_handle).await?;
+    if store.remove(mod_id).is_some() {
+        save_annotations(app_handle, &store).await?;
+    }
+
+    Ok(())
+}
+
+/// Add `incoming` annotations that are missing or newer than the local ones.
+///
+/// Returns the number of annotations taken from `incoming`.
+pub(crate) async fn merge_annotations(
+    app_handle: &AppHandle,
+    incoming: AnnotationStore,
+) -> AppResult<usize> {
+    let _guard = ANNOTATIONS_LOCK.lock().await;
+    let mut store = load_annotations(app_handle).await?;
+
+    let mut merged = 0;
+    for (mod_id, annotation) in incoming {
+        // Timestamps are all RFC 3339 in UTC, so they compare as strings
+        let is_newer = store
+            .get(&mod_id)
+            .is_none_or(|local| annotation.updated_at > local.updated_at);
+        if is_newer {
+            store.insert(mod_id, annotation);
+            merged += 1;
+        }
+    }
+
+    if merged > 0 {
+        save_annotations(app_handle, &store).await?;
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_tags_removes_non_adjacent_duplicates() {
+        let normalized = normalize_tags(tags(&["funny", "chroma", " funny ", "", "  ", "chroma"]));
+        assert_eq!(normalized, tags(&["chroma", "funny"]));
+    }
+
+    #[test]
+    fn test_normalize_tags_keeps_distinct_tags() {
+        assert_eq!(
+            normalize_tags(tags(&["b", "a", "c"])),
+            tags(&["a", "b", "c"])
+        );
+        assert!(normalize_tags(Vec::new()).is_empty());
+    }
+}
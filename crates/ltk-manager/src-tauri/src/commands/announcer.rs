@@ -0,0 +1,567 @@
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::state::{save_settings_to_disk, SettingsState};
+use crate::workspace::WorkspaceLayout;
+use league_toolkit::wad::Wad;
+use ltk_modpkg::utils::hash_chunk_name;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Folder name the active announcer pack gets in the staged overlay mods directory.
+const ANNOUNCER_MOD_NAME: &str = "announcer";
+const AUDIO_EXTENSIONS: &[&str] = &["bnk", "wpk"];
+/// Wwise HIRC object type of an event.
+const HIRC_EVENT_TYPE: u8 = 4;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnouncerPackInfo {
+    pub name: String,
+    pub file_count: usize,
+    /// Game WADs the pack overrides files in.
+    pub wads: Vec<String>,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnouncerImportReport {
+    pub pack: AnnouncerPackInfo,
+    /// Non-fatal validation findings, e.g. banks that are missing events of the original.
+    pub warnings: Vec<String>,
+}
+
+fn validate_pack_name(name: &str) -> AppResult<()> {
+    if name.is_empty()
+        || name == ANNOUNCER_MOD_NAME
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(AppError::ValidationFailed(format!(
+            "Invalid announcer pack name: {}",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn is_audio_bank(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Recursively collect all files below `dir`.
+pub(crate) fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Game path of `file` relative to `root`, lowercased with forward slashes.
+fn game_path(root: &Path, file: &Path) -> String {
+    file.strip_prefix(root)
+        .unwrap_or(file)
+        .to_string_lossy()
+        .replace('\\', "/")
+        .to_lowercase()
+}
+
+/// Read the ids of all events declared in the HIRC section of a Wwise soundbank.
+///
+/// Returns `None` if the data is not a soundbank.
+fn read_bnk_event_ids(data: &[u8]) -> Option<BTreeSet<u32>> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(
+            data.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+
+    if data.get(0..4)? != b"BKHD" {
+        return None;
+    }
+
+    let mut events = BTreeSet::new();
+    let mut offset = 0;
+    while offset + 8 <= data.len() {
+        let tag = &data[offset..offset + 4];
+        let size = read_u32(offset + 4)? as usize;
+        let section = offset + 8;
+
+        if tag == b"HIRC" {
+            let count = read_u32(section)?;
+            let mut object = section + 4;
+            for _ in 0..count {
+                let kind = *data.get(object)?;
+                let object_size = read_u32(object + 1)? as usize;
+                if kind == HIRC_EVENT_TYPE {
+                    events.insert(read_u32(object + 5)?);
+                }
+                object += 5 + object_size;
+            }
+        }
+
+        offset = section + size;
+    }
+
+    Some(events)
+}
+
+/// Find the game WAD below `final_dir` that contains each of `hashes`.
+fn locate_in_game_wads(
+    final_dir: &Path,
+    hashes: &HashSet<u64>,
+) -> AppResult<HashMap<u64, PathBuf>> {
+    let mut wads = Vec::new();
+    crate::commands::patch_history::collect_wad_files(final_dir, &mut wads)?;
+    wads.sort();
+
+    let mut located = HashMap::new();
+    for wad_path in wads {
+        if located.len() == hashes.len() {
+            break;
+        }
+
+        let wad = match File::open(&wad_path).map(Wad::mount) {
+            Ok(Ok(wad)) => wad,
+            _ => {
+                tracing::warn!("Skipping unreadable WAD {:?}", wad_path);
+                continue;
+            }
+        };
+
+        for hash in hashes {
+            if wad.chunks().contains_key(hash) {
+                located.entry(*hash).or_insert_with(|| wad_path.clone());
+            }
+        }
+    }
+
+    Ok(located)
+}
+
+fn read_game_chunk(wad_path: &Path, path_hash: u64) -> AppResult<Vec<u8>> {
+    let file = File::open(wad_path)?;
+    let mut wad = Wad::mount(file).map_err(|e| AppError::Other(e.to_string()))?;
+    let (mut decoder, chunks) = wad.decode();
+    let chunk = chunks
+        .get(&path_hash)
+        .ok_or_else(|| AppError::Other(format!("Chunk {:016x} not found", path_hash)))?;
+
+    decoder
+        .load_chunk_decompressed(chunk)
+        .map(|data| data.into_vec())
+        .map_err(|e| AppError::Other(e.to_string()))
+}
+
+/// Validate the audio banks in `source_dir` against the game and lay them out as a mod in `target_dir`.
+fn import_pack_blocking(
+    final_dir: &Path,
+    source_dir: &Path,
+    target_dir: &Path,
+    name: &str,
+) -> AppResult<(usize, BTreeSet<String>, Vec<String>)> {
+    let mut files = Vec::new();
+    collect_files(source_dir, &mut files)?;
+    files.sort();
+
+    if files.is_empty() {
+        return Err(AppError::ValidationFailed(
+            "Announcer pack contains no files".to_string(),
+        ));
+    }
+    if let Some(file) = files.iter().find(|f| !is_audio_bank(f)) {
+        return Err(AppError::ValidationFailed(format!(
+            "Announcer packs may only contain .bnk and .wpk files: {}",
+            game_path(source_dir, file)
+        )));
+    }
+
+    let hashes: HashSet<u64> = files
+        .iter()
+        .map(|f| hash_chunk_name(&game_path(source_dir, f)))
+        .collect();
+    let located = locate_in_game_wads(final_dir, &hashes)?;
+
+    let mut wads = BTreeSet::new();
+    let mut warnings = Vec::new();
+    let mut copies = Vec::new();
+    for file in &files {
+        let path = game_path(source_dir, file);
+        let hash = hash_chunk_name(&path);
+        let wad_path = located.get(&hash).ok_or_else(|| {
+            AppError::ValidationFailed(format!(
+                "{} does not replace an audio bank of the game",
+                path
+            ))
+        })?;
+
+        if path.ends_with(".bnk") {
+            let data = std::fs::read(file)?;
+            let events = read_bnk_event_ids(&data).ok_or_else(|| {
+                AppError::ValidationFailed(format!("{} is not a Wwise soundbank", path))
+            })?;
+            let original =
+                read_bnk_event_ids(&read_game_chunk(wad_path, hash)?).unwrap_or_default();
+
+            let missing: Vec<String> = original
+                .difference(&events)
+                .map(|id| id.to_string())
+                .collect();
+            if !missing.is_empty() {
+                warnings.push(format!(
+                    "{} is missing {} event(s) of the original bank: {}",
+                    path,
+                    missing.len(),
+                    missing.join(", ")
+                ));
+            }
+        }
+
+        let wad_name = wad_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        copies.push((file, target_dir.join("WAD").join(&wad_name).join(&path)));
+        wads.insert(wad_name);
+    }
+
+    for (source, target) in copies {
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(source, target)?;
+    }
+
+    let info = serde_json::json!({
+        "Name": name,
+        "Author": "",
+        "Version": "1.0.0",
+        "Description": "Announcer pack",
+    });
+    std::fs::create_dir_all(target_dir.join("META"))?;
+    std::fs::write(
+        target_dir.join("META").join("info.json"),
+        serde_json::to_string_pretty(&info)?,
+    )?;
+
+    Ok((files.len(), wads, warnings))
+}
+
+fn read_pack_info(pack_dir: &Path, active: Option<&str>) -> AppResult<AnnouncerPackInfo> {
+    let name = pack_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut wads = Vec::new();
+    let mut file_count = 0;
+    let wad_root = pack_dir.join("WAD");
+    if wad_root.exists() {
+        for entry in std::fs::read_dir(&wad_root)? {
+            let path = entry?.path();
+            let mut files = Vec::new();
+            collect_files(&path, &mut files)?;
+            file_count += files.len();
+            wads.push(
+                path.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            );
+        }
+    }
+    wads.sort();
+
+    Ok(AnnouncerPackInfo {
+        active: active == Some(name.as_str()),
+        name,
+        file_count,
+        wads,
+    })
+}
+
+/// Import a folder of `.bnk`/`.wpk` files laid out by game path (e.g. `assets/sounds/...`).
+#[tauri::command]
+pub async fn import_announcer_pack(
+    app_handle: AppHandle,
+    source_dir: PathBuf,
+    name: String,
+) -> IpcResult<AnnouncerImportReport> {
+    import_announcer_pack_inner(&app_handle, source_dir, name)
+        .await
+        .into()
+}
+
+async fn import_announcer_pack_inner(
+    app_handle: &AppHandle,
+    source_dir: PathBuf,
+    name: String,
+) -> AppResult<AnnouncerImportReport> {
+    validate_pack_name(&name)?;
+
+    if !source_dir.is_dir() {
+        return Err(AppError::InvalidPath(source_dir.display().to_string()));
+    }
+
+    let league_path = {
+        let settings_state = app_handle.state::<SettingsState>();
+        let settings = settings_state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        settings
+            .league_path
+            .clone()
+            .ok_or(AppError::LeagueNotFound)?
+    };
+    let final_dir = league_path.join("Game").join("DATA").join("FINAL");
+    if !final_dir.exists() {
+        return Err(AppError::LeagueNotFound);
+    }
+
+    let target_dir = WorkspaceLayout::from_app(app_handle)?.announcer_dir(&name)?;
+    if target_dir.exists() {
+        return Err(AppError::ValidationFailed(format!(
+            "Announcer pack {} already exists",
+            name
+        )));
+    }
+
+    let task_target_dir = target_dir.clone();
+    let task_name = name.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        import_pack_blocking(&final_dir, &source_dir, &task_target_dir, &task_name)
+    })
+    .await
+    .map_err(|e| AppError::Other(format!("Announcer import task failed: {}", e)))?;
+
+    let (file_count, wads, warnings) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            // Don't leave a half-imported pack behind
+            let _ = tokio::fs::remove_dir_all(&target_dir).await;
+            return Err(e);
+        }
+    };
+
+    for warning in &warnings {
+        tracing::warn!("Announcer pack {}: {}", name, warning);
+    }
+
+    Ok(AnnouncerImportReport {
+        pack: AnnouncerPackInfo {
+            name,
+            file_count,
+            wads: wads.into_iter().collect(),
+            active: false,
+        },
+        warnings,
+    })
+}
+
+#[tauri::command]
+pub async fn list_announcer_packs(app_handle: AppHandle) -> IpcResult<Vec<AnnouncerPackInfo>> {
+    list_announcer_packs_inner(&app_handle).into()
+}
+
+fn list_announcer_packs_inner(app_handle: &AppHandle) -> AppResult<Vec<AnnouncerPackInfo>> {
+    let dir = WorkspaceLayout::from_app(app_handle)?.announcers_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let active = {
+        let settings_state = app_handle.state::<SettingsState>();
+        let settings = settings_state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        settings.active_announcer.clone()
+    };
+
+    let mut packs = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            packs.push(read_pack_info(&path, active.as_deref())?);
+        }
+    }
+    packs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(packs)
+}
+
+#[tauri::command]
+pub async fn delete_announcer_pack(app_handle: AppHandle, name: String) -> IpcResult<()> {
+    delete_announcer_pack_inner(&app_handle, name).await.into()
+}
+
+async fn delete_announcer_pack_inner(app_handle: &AppHandle, name: String) -> AppResult<()> {
+    validate_pack_name(&name)?;
+
+    let dir = WorkspaceLayout::from_app(app_handle)?.announcer_dir(&name)?;
+    if !dir.exists() {
+        return Err(AppError::ModNotFound(name));
+    }
+    tokio::fs::remove_dir_all(&dir).await?;
+
+    let state = app_handle.state::<SettingsState>();
+    let mut settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+    if settings.active_announcer.as_deref() == Some(name.as_str()) {
+        settings.active_announcer = None;
+        save_settings_to_disk(app_handle, &settings)?;
+    }
+
+    Ok(())
+}
+
+/// Select the announcer pack that is added to every overlay, or clear it with `None`.
+#[tauri::command]
+pub fn set_active_announcer(app_handle: AppHandle, name: Option<String>) -> IpcResult<()> {
+    set_active_announcer_inner(&app_handle, name).into()
+}
+
+pub(crate) fn set_active_announcer_inner(
+    app_handle: &AppHandle,
+    name: Option<String>,
+) -> AppResult<()> {
+    if let Some(name) = &name {
+        validate_pack_name(name)?;
+        if !WorkspaceLayout::from_app(app_handle)?
+            .announcer_dir(name)?
+            .exists()
+        {
+            return Err(AppError::ModNotFound(name.clone()));
+        }
+    }
+
+    let state = app_handle.state::<SettingsState>();
+    let mut settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+    settings.active_announcer = name;
+    save_settings_to_disk(app_handle, &settings)?;
+
+    Ok(())
+}
+
+/// Path hashes of every file a mod overrides, for both WAD folders and packed WAD files.
+fn collect_mod_hashes(mod_dir: &Path) -> anyhow::Result<HashSet<u64>> {
+    let mut hashes = HashSet::new();
+    let wad_root = mod_dir.join("WAD");
+    if !wad_root.exists() {
+        return Ok(hashes);
+    }
+
+    for entry in std::fs::read_dir(&wad_root)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            let mut files = Vec::new();
+            collect_files(&path, &mut files)?;
+            hashes.extend(files.iter().map(|f| hash_chunk_name(&game_path(&path, f))));
+        } else {
+            let wad = Wad::mount(File::open(&path)?)?;
+            hashes.extend(wad.chunks().keys().copied());
+        }
+    }
+
+    Ok(hashes)
+}
+
+/// Hard link (or copy, if linking fails) the tree at `source` to `target`, skipping `skip`.
+pub(crate) fn link_tree(
+    source: &Path,
+    target: &Path,
+    skip: &dyn Fn(&Path) -> bool,
+) -> std::io::Result<()> {
+    let mut files = Vec::new();
+    collect_files(source, &mut files)?;
+
+    for file in files {
+        if skip(&file) {
+            continue;
+        }
+        let destination = target.join(file.strip_prefix(source).unwrap_or(&file));
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if std::fs::hard_link(&file, &destination).is_err() {
+            std::fs::copy(&file, &destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Stage `mod_name` from `mods_base_dir` together with the active announcer pack.
+///
+/// Audio banks that the mod itself already overrides are left out of the announcer so the
+/// mod's version wins; their game paths are returned as conflicts. Returns the mods
+/// directory and the `--mods:` value to pass to mkoverlay.
+pub(crate) fn stage_with_announcer(
+    layout: &WorkspaceLayout,
+    mods_base_dir: &Path,
+    mod_name: &str,
+    announcer: &str,
+) -> anyhow::Result<(PathBuf, String, Vec<String>)> {
+    let announcer_dir = layout.announcer_dir(announcer)?;
+    if !announcer_dir.exists() {
+        return Err(anyhow::anyhow!(
+            "Active announcer pack not found: {:?}",
+            announcer_dir
+        ));
+    }
+
+    let staging_dir = layout.overlay_staging_dir();
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(&staging_dir)?;
+    }
+
+    let mod_dir = mods_base_dir.join(mod_name);
+    link_tree(&mod_dir, &staging_dir.join(mod_name), &|_| false)?;
+
+    // Announcer files the mod also overrides, keyed by their path in the pack
+    let mod_hashes = collect_mod_hashes(&mod_dir)?;
+    let announcer_wad_root = announcer_dir.join("WAD");
+    let mut conflicts = Vec::new();
+    let mut skipped = HashSet::new();
+    if announcer_wad_root.exists() {
+        for entry in std::fs::read_dir(&announcer_wad_root)? {
+            let wad_dir = entry?.path();
+            let mut files = Vec::new();
+            collect_files(&wad_dir, &mut files)?;
+            for file in files {
+                let path = game_path(&wad_dir, &file);
+                if mod_hashes.contains(&hash_chunk_name(&path)) {
+                    conflicts.push(path);
+                    skipped.insert(file);
+                }
+            }
+        }
+    }
+
+    link_tree(
+        &announcer_dir,
+        &staging_dir.join(ANNOUNCER_MOD_NAME),
+        &|file| skipped.contains(file),
+    )?;
+
+    Ok((
+        staging_dir,
+        format!("{}/{}", mod_name, ANNOUNCER_MOD_NAME),
+        conflicts,
+    ))
+}
@@ -0,0 +1,150 @@
+//! Checking for and installing new app versions from the backend.
+//!
+//! Stable releases come from the latest GitHub release. Beta builds come from the release
+//! tagged `beta`, which the release workflow gives the `latest.json` of every pre-release
+//! (versions like `0.2.0-beta.1`). The update found by the last check is kept until it is
+//! installed, so `install_app_update` installs exactly what the user was shown.
+
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::events::{emit, AppEvent, EventSource};
+use crate::state::{SettingsState, UpdateChannel};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/long113112113/league-mod/releases/latest/download/latest.json";
+const BETA_ENDPOINT: &str =
+    "https://github.com/long113112113/league-mod/releases/download/beta/latest.json";
+
+#[derive(Default)]
+pub struct AppUpdateState(Mutex<Option<Update>>);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUpdateInfo {
+    pub channel: UpdateChannel,
+    pub current_version: String,
+    pub version: String,
+    /// Release notes.
+    pub body: Option<String>,
+    pub date: Option<String>,
+}
+
+fn endpoint(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    }
+}
+
+fn update_channel(app_handle: &AppHandle) -> AppResult<UpdateChannel> {
+    let state = app_handle.state::<SettingsState>();
+    let settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+    Ok(settings.update_channel)
+}
+
+/// Look for a newer version on the configured channel, `None` if the app is up to date.
+#[tauri::command]
+pub async fn check_app_update(app_handle: AppHandle) -> IpcResult<Option<AppUpdateInfo>> {
+    check_app_update_inner(&app_handle).await.into()
+}
+
+async fn check_app_update_inner(app_handle: &AppHandle) -> AppResult<Option<AppUpdateInfo>> {
+    let channel = update_channel(app_handle)?;
+    let url = Url::parse(endpoint(channel))
+        .map_err(|e| AppError::Other(format!("Invalid update endpoint: {}", e)))?;
+
+    let update = app_handle
+        .updater_builder()
+        .endpoints(vec![url])
+        .and_then(|builder| builder.build())
+        .map_err(|e| AppError::Other(format!("Failed to create updater: {}", e)))?
+        .check()
+        .await
+        .map_err(|e| AppError::Other(format!("Update check failed: {}", e)))?;
+
+    let info = update.as_ref().map(|update| AppUpdateInfo {
+        channel,
+        current_version: update.current_version.clone(),
+        version: update.version.clone(),
+        body: update.body.clone(),
+        date: update.date.as_ref().map(ToString::to_string),
+    });
+    match &info {
+        Some(info) => tracing::info!("Update to {} available on {:?}", info.version, channel),
+        None => tracing::info!("No update available on {:?}", channel),
+    }
+
+    *app_handle
+        .state::<AppUpdateState>()
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))? = update;
+    Ok(info)
+}
+
+/// Download and install the update found by the last `check_app_update`.
+///
+/// Download progress is sent as `progress` events. The new version runs after the app is
+/// restarted, on Windows the installer closes the app itself.
+#[tauri::command]
+pub async fn install_app_update(app_handle: AppHandle) -> IpcResult<()> {
+    let result = install_app_update_inner(&app_handle).await;
+    match &result {
+        Ok(()) => emit(
+            &app_handle,
+            AppEvent::completed(EventSource::AppUpdate, "Update installed"),
+        ),
+        Err(e) => emit(
+            &app_handle,
+            AppEvent::error(EventSource::AppUpdate, e.to_string()),
+        ),
+    }
+    result.into()
+}
+
+async fn install_app_update_inner(app_handle: &AppHandle) -> AppResult<()> {
+    let update = app_handle
+        .state::<AppUpdateState>()
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?
+        .take()
+        .ok_or_else(|| {
+            AppError::ValidationFailed("No update available, check for updates first".to_string())
+        })?;
+
+    tracing::info!("Installing update {}", update.version);
+    let mut downloaded = 0;
+    let mut reported_percent = None;
+    update
+        .download_and_install(
+            |chunk_length, content_length| {
+                downloaded += chunk_length;
+                let total = content_length.unwrap_or(0) as usize;
+                // Chunks are small, only report whole percents
+                let percent = (total > 0).then(|| downloaded * 100 / total);
+                if percent.is_some() && percent == reported_percent {
+                    return;
+                }
+                reported_percent = percent;
+                emit(
+                    app_handle,
+                    AppEvent::progress(
+                        EventSource::AppUpdate,
+                        downloaded,
+                        total,
+                        "Downloading update...",
+                    ),
+                );
+            },
+            || tracing::info!("Update downloaded, installing"),
+        )
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to install update: {}", e)))
+}
@@ -0,0 +1,372 @@
+//! Backups of the user's configuration to a folder of their choice, e.g. a synced cloud folder.
+//!
+//! A backup is a zip archive holding the settings, the mod annotations and a mod list
+//! manifest. Downloaded mods and game data are not included; restoring reinstalls the mods of
+//! the manifest instead.
+
+use crate::commands::annotations::{load_annotations, merge_annotations, AnnotationStore};
+use crate::commands::mod_list::{
+    collect_mod_list, install_mod_list, ApplyModListReport, ModListManifest,
+};
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::state::{get_app_data_dir, save_settings_to_disk, Settings, SettingsState};
+use crate::workspace::WorkspaceLayout;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const BACKUP_FORMAT_VERSION: u32 = 1;
+const BACKUP_FILE_PREFIX: &str = "ltk-manager-backup-";
+const BACKUP_FILE_EXTENSION: &str = "zip";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+/// Length of a timestamp in `BACKUP_TIMESTAMP_FORMAT`.
+const TIMESTAMP_LEN: usize = 15;
+/// Backups of the local state taken right before a restore, in the app data directory.
+const SAFETY_BACKUP_DIRNAME: &str = "backups";
+
+const INFO_ENTRY: &str = "backup.json";
+const SETTINGS_ENTRY: &str = "settings.json";
+const ANNOTATIONS_ENTRY: &str = "annotations.json";
+const MOD_LIST_ENTRY: &str = "mod-list.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupInfo {
+    format_version: u32,
+    created_at: String,
+    app_version: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    pub path: PathBuf,
+    pub created_at: String,
+    /// Number of mods in the backed up mod list.
+    pub mod_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub restored_from: PathBuf,
+    /// Backup of the state that was replaced, to undo the restore with `restore_from`.
+    pub safety_backup: PathBuf,
+    /// Annotations taken from the backup because they were missing or newer locally.
+    pub annotations_merged: usize,
+    pub mod_list: Option<ApplyModListReport>,
+}
+
+/// Everything a backup holds. Annotations and the mod list need a configured workspace.
+struct BackupContents {
+    settings: Settings,
+    annotations: Option<AnnotationStore>,
+    mod_list: Option<ModListManifest>,
+}
+
+/// Write a new backup into the `path` directory.
+#[tauri::command]
+pub async fn backup_to(app_handle: AppHandle, path: String) -> IpcResult<BackupSummary> {
+    backup_to_inner(&app_handle, Path::new(&path)).await.into()
+}
+
+async fn backup_to_inner(app_handle: &AppHandle, dir: &Path) -> AppResult<BackupSummary> {
+    let contents = collect_backup(app_handle).await?;
+    let summary = write_backup(dir, &contents)?;
+    tracing::info!("Wrote backup to {}", summary.path.display());
+    Ok(summary)
+}
+
+/// Restore a backup file, or the newest backup when `path` is a directory.
+///
+/// Nothing is changed unless the whole backup can be read. The current state is saved to a
+/// safety backup first. Paths that belong to this machine (League, workspace and workshop)
+/// are kept if they are set, annotations are merged by recency, and the mods of the backed up
+/// mod list that are missing are installed.
+#[tauri::command]
+pub async fn restore_from(app_handle: AppHandle, path: String) -> IpcResult<RestoreReport> {
+    restore_from_inner(&app_handle, Path::new(&path))
+        .await
+        .into()
+}
+
+async fn restore_from_inner(app_handle: &AppHandle, path: &Path) -> AppResult<RestoreReport> {
+    let backup_path = if path.is_dir() {
+        newest_backup_in(path)?
+    } else {
+        path.to_path_buf()
+    };
+    let backup = read_backup(&backup_path)?;
+
+    let safety_dir = get_app_data_dir(app_handle)
+        .ok_or_else(|| AppError::Other("Could not determine app data directory".to_string()))?
+        .join(SAFETY_BACKUP_DIRNAME);
+    let safety_backup = write_backup(&safety_dir, &collect_backup(app_handle).await?)?.path;
+    tracing::info!(
+        "Restoring {}, previous state saved to {}",
+        backup_path.display(),
+        safety_backup.display()
+    );
+
+    restore_settings(app_handle, backup.settings)?;
+
+    let annotations_merged = match backup.annotations {
+        Some(annotations) => merge_annotations(app_handle, annotations).await?,
+        None => 0,
+    };
+
+    let mod_list = match backup.mod_list {
+        Some(manifest) if !manifest.mods.is_empty() => {
+            Some(install_mod_list(app_handle, manifest).await?)
+        }
+        _ => None,
+    };
+
+    Ok(RestoreReport {
+        restored_from: backup_path,
+        safety_backup,
+        annotations_merged,
+        mod_list,
+    })
+}
+
+async fn collect_backup(app_handle: &AppHandle) -> AppResult<BackupContents> {
+    let settings = {
+        let state = app_handle.state::<SettingsState>();
+        let settings = state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        settings.clone()
+    };
+
+    // Settings can be backed up before a workspace is chosen
+    let (annotations, mod_list) = if WorkspaceLayout::from_app(app_handle).is_ok() {
+        (
+            Some(load_annotations(app_handle).await?),
+            Some(collect_mod_list(app_handle).await?),
+        )
+    } else {
+        (None, None)
+    };
+
+    Ok(BackupContents {
+        settings,
+        annotations,
+        mod_list,
+    })
+}
+
+fn restore_settings(app_handle: &AppHandle, mut restored: Settings) -> AppResult<()> {
+    let state = app_handle.state::<SettingsState>();
+    let mut settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+    // A backup from another machine can point at paths that don't exist here
+    restored.league_path = settings.league_path.take().or(restored.league_path);
+    restored.workspace_path = settings.workspace_path.take().or(restored.workspace_path);
+    restored.workshop_path = settings.workshop_path.take().or(restored.workshop_path);
+    restored.first_run_complete |= settings.first_run_complete;
+
+    *settings = restored;
+    save_settings_to_disk(app_handle, &settings)?;
+    Ok(())
+}
+
+/// Write `contents` to a new timestamped archive in `dir`.
+///
+/// The archive is written under a temporary name first, so sync clients never upload a
+/// partial backup.
+fn write_backup(dir: &Path, contents: &BackupContents) -> AppResult<BackupSummary> {
+    std::fs::create_dir_all(dir)?;
+
+    let now = chrono::Utc::now();
+    let info = BackupInfo {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: now.to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+
+    let stem = format!(
+        "{}{}",
+        BACKUP_FILE_PREFIX,
+        now.format(BACKUP_TIMESTAMP_FORMAT)
+    );
+    let mut path = dir.join(format!("{}.{}", stem, BACKUP_FILE_EXTENSION));
+    let mut suffix = 1;
+    while path.exists() {
+        suffix += 1;
+        path = dir.join(format!("{}-{}.{}", stem, suffix, BACKUP_FILE_EXTENSION));
+    }
+    let temp_path = path.with_extension("tmp");
+
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&temp_path)?);
+    write_entry(&mut writer, INFO_ENTRY, &info)?;
+    write_entry(&mut writer, SETTINGS_ENTRY, &contents.settings)?;
+    if let Some(annotations) = &contents.annotations {
+        write_entry(&mut writer, ANNOTATIONS_ENTRY, annotations)?;
+    }
+    if let Some(mod_list) = &contents.mod_list {
+        write_entry(&mut writer, MOD_LIST_ENTRY, mod_list)?;
+    }
+    writer
+        .finish()
+        .map_err(|e| AppError::Other(format!("Failed to write backup: {}", e)))?;
+    std::fs::rename(&temp_path, &path)?;
+
+    Ok(BackupSummary {
+        path,
+        created_at: info.created_at,
+        mod_count: contents.mod_list.as_ref().map_or(0, |m| m.mods.len()),
+    })
+}
+
+fn write_entry<W: Write + std::io::Seek, T: Serialize>(
+    writer: &mut zip::ZipWriter<W>,
+    name: &str,
+    value: &T,
+) -> AppResult<()> {
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    writer
+        .start_file(name, options)
+        .map_err(|e| AppError::Other(format!("Failed to write backup: {}", e)))?;
+    writer.write_all(&serde_json::to_vec_pretty(value)?)?;
+    Ok(())
+}
+
+fn read_backup(path: &Path) -> AppResult<BackupContents> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| {
+        AppError::ValidationFailed(format!("{} is not a backup: {}", path.display(), e))
+    })?;
+
+    let info: BackupInfo = read_entry(&mut archive, INFO_ENTRY)?
+        .ok_or_else(|| AppError::ValidationFailed(format!("{} is not a backup", path.display())))?;
+    if info.format_version > BACKUP_FORMAT_VERSION {
+        return Err(AppError::ValidationFailed(format!(
+            "Backup format version {} needs a newer version of LTK Manager",
+            info.format_version
+        )));
+    }
+
+    let settings = read_entry(&mut archive, SETTINGS_ENTRY)?
+        .ok_or_else(|| AppError::ValidationFailed(format!("{} has no settings", path.display())))?;
+
+    Ok(BackupContents {
+        settings,
+        annotations: read_entry(&mut archive, ANNOTATIONS_ENTRY)?,
+        mod_list: read_entry(&mut archive, MOD_LIST_ENTRY)?,
+    })
+}
+
+fn read_entry<R: Read + std::io::Seek, T: DeserializeOwned>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> AppResult<Option<T>> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(AppError::Other(format!("Failed to read backup: {}", e))),
+    };
+
+    let mut content = Vec::new();
+    entry.read_to_end(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// The newest backup in `dir`, by the timestamp and counter in its file name.
+fn newest_backup_in(dir: &Path) -> AppResult<PathBuf> {
+    std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let order = backup_order(path.file_name()?.to_str()?)?;
+            Some((order, path))
+        })
+        .max()
+        .map(|(_, path)| path)
+        .ok_or_else(|| AppError::ValidationFailed(format!("No backups found in {}", dir.display())))
+}
+
+/// Creation time and counter of a backup named by `write_backup`, `None` for other files.
+///
+/// Backups made within the same second get a counter suffix starting at 2, which doesn't sort
+/// as text past 9.
+fn backup_order(file_name: &str) -> Option<(chrono::NaiveDateTime, u32)> {
+    let stem = file_name
+        .strip_prefix(BACKUP_FILE_PREFIX)?
+        .strip_suffix(BACKUP_FILE_EXTENSION)?
+        .strip_suffix('.')?;
+    let (timestamp, counter) = match stem.get(TIMESTAMP_LEN..) {
+        Some("") => (stem, 1),
+        Some(rest) => (
+            &stem[..TIMESTAMP_LEN],
+            rest.strip_prefix('-')?.parse().ok()?,
+        ),
+        None => return None,
+    };
+    let timestamp =
+        chrono::NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).ok()?;
+    Some((timestamp, counter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn newest(names: &[&str]) -> Option<String> {
+        names
+            .iter()
+            .filter_map(|name| Some((backup_order(name)?, name.to_string())))
+            .max()
+            .map(|(_, name)| name)
+    }
+
+    #[test]
+    fn test_backup_order_parses_timestamp_and_counter() {
+        let order = backup_order("ltk-manager-backup-20260102-030405-12.zip").unwrap();
+        assert_eq!(order.0.to_string(), "2026-01-02 03:04:05");
+        assert_eq!(order.1, 12);
+        assert_eq!(
+            backup_order("ltk-manager-backup-20260102-030405.zip").map(|o| o.1),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_backup_order_ignores_other_files() {
+        assert!(backup_order("ltk-manager-backup-20260102-030405.tmp").is_none());
+        assert!(backup_order("ltk-manager-backup-notes.zip").is_none());
+        assert!(backup_order("ltk-manager-backup-20260102-030405-x.zip").is_none());
+        assert!(backup_order("other-20260102-030405.zip").is_none());
+    }
+
+    #[test]
+    fn test_newest_backup_compares_counters_numerically() {
+        let names = [
+            "ltk-manager-backup-20260102-030405.zip",
+            "ltk-manager-backup-20260102-030405-2.zip",
+            "ltk-manager-backup-20260102-030405-10.zip",
+            "ltk-manager-backup-20260102-030405-9.zip",
+        ];
+        assert_eq!(
+            newest(&names).as_deref(),
+            Some("ltk-manager-backup-20260102-030405-10.zip")
+        );
+    }
+
+    #[test]
+    fn test_newest_backup_prefers_later_timestamp() {
+        let names = [
+            "ltk-manager-backup-20260102-030405-3.zip",
+            "ltk-manager-backup-20260102-030406.zip",
+        ];
+        assert_eq!(
+            newest(&names).as_deref(),
+            Some("ltk-manager-backup-20260102-030406.zip")
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use crate::bisect::{self, BisectStatus};
+use crate::commands::{collect_mod_list, ModListEntry};
+use crate::error::{AppResult, IpcResult};
+use tauri::AppHandle;
+
+/// Start isolating the mod of `mods` (every installed mod if empty) that crashes the game.
+///
+/// Each following game runs with half of the remaining mods; the crash watchdog reports
+/// whether it crashed and the next round starts on its own.
+#[tauri::command]
+pub async fn start_bisect(
+    app_handle: AppHandle,
+    mods: Vec<ModListEntry>,
+) -> IpcResult<BisectStatus> {
+    start_bisect_inner(&app_handle, mods).await.into()
+}
+
+async fn start_bisect_inner(
+    app_handle: &AppHandle,
+    mods: Vec<ModListEntry>,
+) -> AppResult<BisectStatus> {
+    let mods = if mods.is_empty() {
+        collect_mod_list(app_handle).await?.mods
+    } else {
+        mods
+    };
+    bisect::start(app_handle, mods).await
+}
+
+#[tauri::command]
+pub fn get_bisect_status(app_handle: AppHandle) -> IpcResult<Option<BisectStatus>> {
+    bisect::status(&app_handle).into()
+}
+
+/// Report the outcome of the current round by hand, e.g. when the game froze instead of exiting.
+#[tauri::command]
+pub async fn report_bisect_result(app_handle: AppHandle, crashed: bool) -> IpcResult<()> {
+    bisect::game_finished(&app_handle, crashed).await.into()
+}
+
+#[tauri::command]
+pub async fn cancel_bisect(app_handle: AppHandle) -> IpcResult<()> {
+    bisect::cancel(&app_handle).await.into()
+}
@@ -0,0 +1,420 @@
+use crate::commands::mod_skin::download_and_extract;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::events::{emit, AppEvent, EventSource};
+use crate::jobs::{run_job, run_resumable_job, JobKind, JobResume};
+use crate::workspace::WorkspaceLayout;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{command, AppHandle, Manager};
+use tokio::fs;
+
+const INDEX_CACHE_FILENAME: &str = "index.json";
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 200;
+
+/// A mod listed in the community index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModIndexEntry {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    /// Free-form category such as "skin", "ward", "announcer" or "ui".
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub champion_id: Option<i32>,
+    /// Set for skin mods, the mod is then installed like a skin from the skins repository.
+    #[serde(default)]
+    pub skin_id: Option<i32>,
+    #[serde(default)]
+    pub description: String,
+    pub download_url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteModIndex {
+    mods: Vec<ModIndexEntry>,
+}
+
+/// Locally cached copy of the remote index.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedModIndex {
+    source_url: String,
+    fetched_at: String,
+    mods: Vec<ModIndexEntry>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSearchQuery {
+    /// Matched case-insensitively against name, description and tags.
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub champion_id: Option<i32>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Zero-based page number.
+    #[serde(default)]
+    pub page: usize,
+    #[serde(default)]
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSearchPage {
+    pub items: Vec<ModIndexEntry>,
+    /// Number of entries matching the query across all pages.
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub fetched_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModIndexStatus {
+    pub source_url: String,
+    pub fetched_at: String,
+    pub count: usize,
+}
+
+fn get_browser_dir(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    Ok(WorkspaceLayout::from_app(app_handle)?.browser_dir())
+}
+
+fn get_mod_index_url(app_handle: &AppHandle) -> AppResult<String> {
+    let settings_state = app_handle.state::<crate::state::SettingsState>();
+    let settings = settings_state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+    settings.mod_index_url.clone().ok_or_else(|| {
+        AppError::ValidationFailed(
+            "Mod index URL not configured. Please set it in Settings.".to_string(),
+        )
+    })
+}
+
+fn is_valid_mod_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !id.starts_with('.')
+}
+
+/// Whether the skin of a skin mod belongs to its champion, skin ids are `champion_id * 1000 + n`.
+fn skin_matches_champion(entry: &ModIndexEntry) -> bool {
+    match (entry.champion_id, entry.skin_id) {
+        (Some(champion_id), Some(skin_id)) => skin_id / 1000 == champion_id,
+        _ => true,
+    }
+}
+
+async fn fetch_mod_index(app_handle: &AppHandle) -> AppResult<CachedModIndex> {
+    let url = get_mod_index_url(app_handle)?;
+    tracing::info!("Fetching mod index from {}", url);
+
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to fetch mod index: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(AppError::Other(format!(
+            "Failed to fetch mod index: HTTP {}",
+            response.status()
+        )));
+    }
+
+    let remote: RemoteModIndex = response
+        .json()
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to parse mod index: {}", e)))?;
+
+    // Entry ids become directory names, drop anything that can't safely be one. A skin of
+    // another champion would install the mod into that champion's folder.
+    let total = remote.mods.len();
+    let mods: Vec<ModIndexEntry> = remote
+        .mods
+        .into_iter()
+        .filter(|m| is_valid_mod_id(&m.id) && skin_matches_champion(m))
+        .collect();
+    if mods.len() != total {
+        tracing::warn!(
+            "Ignored {} mod index entries with invalid ids or skins of another champion",
+            total - mods.len()
+        );
+    }
+
+    let index = CachedModIndex {
+        source_url: url,
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+        mods,
+    };
+
+    let browser_dir = get_browser_dir(app_handle)?;
+    fs::create_dir_all(&browser_dir)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to create browser dir: {}", e)))?;
+    let json = serde_json::to_string(&index)
+        .map_err(|e| AppError::Other(format!("Failed to serialize mod index: {}", e)))?;
+    fs::write(browser_dir.join(INDEX_CACHE_FILENAME), json)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to write mod index cache: {}", e)))?;
+
+    Ok(index)
+}
+
+/// Load the cached index, fetching it first if there is no cache for the configured URL.
+async fn load_mod_index(app_handle: &AppHandle) -> AppResult<CachedModIndex> {
+    let cache_path = get_browser_dir(app_handle)?.join(INDEX_CACHE_FILENAME);
+
+    if tokio::fs::try_exists(&cache_path).await.unwrap_or(false) {
+        let content = fs::read_to_string(&cache_path)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to read mod index cache: {}", e)))?;
+
+        match serde_json::from_str::<CachedModIndex>(&content) {
+            Ok(index) if get_mod_index_url(app_handle)? == index.source_url => return Ok(index),
+            Ok(_) => tracing::info!("Mod index URL changed, refetching"),
+            Err(e) => tracing::warn!("Ignoring unreadable mod index cache: {}", e),
+        }
+    }
+
+    fetch_mod_index(app_handle).await
+}
+
+fn matches_query(entry: &ModIndexEntry, query: &ModSearchQuery) -> bool {
+    if query.champion_id.is_some() && entry.champion_id != query.champion_id {
+        return false;
+    }
+    if let Some(category) = &query.category {
+        if !entry.category.eq_ignore_ascii_case(category) {
+            return false;
+        }
+    }
+    if let Some(author) = &query.author {
+        if !entry.author.eq_ignore_ascii_case(author) {
+            return false;
+        }
+    }
+    if let Some(text) = query
+        .text
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        let text = text.to_lowercase();
+        let found = entry.name.to_lowercase().contains(&text)
+            || entry.description.to_lowercase().contains(&text)
+            || entry.tags.iter().any(|t| t.to_lowercase().contains(&text));
+        if !found {
+            return false;
+        }
+    }
+    true
+}
+
+/// Fetch the mod index from the configured URL and replace the local cache.
+#[tauri::command]
+pub async fn refresh_mod_index(app_handle: AppHandle) -> IpcResult<ModIndexStatus> {
+    run_job(&app_handle, JobKind::ModIndex, "Refresh mod index", |_| {
+        refresh_mod_index_inner(&app_handle)
+    })
+    .await
+    .into()
+}
+
+async fn refresh_mod_index_inner(app_handle: &AppHandle) -> AppResult<ModIndexStatus> {
+    let index = fetch_mod_index(app_handle).await?;
+    emit(
+        app_handle,
+        AppEvent::completed(
+            EventSource::ModIndex,
+            format!("Fetched {} mods", index.mods.len()),
+        ),
+    );
+    Ok(ModIndexStatus {
+        source_url: index.source_url,
+        fetched_at: index.fetched_at,
+        count: index.mods.len(),
+    })
+}
+
+#[tauri::command]
+pub async fn search_mods(app_handle: AppHandle, query: ModSearchQuery) -> IpcResult<ModSearchPage> {
+    search_mods_inner(&app_handle, query).await.into()
+}
+
+async fn search_mods_inner(
+    app_handle: &AppHandle,
+    query: ModSearchQuery,
+) -> AppResult<ModSearchPage> {
+    let index = load_mod_index(app_handle).await?;
+    let page_size = query
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let matching: Vec<&ModIndexEntry> = index
+        .mods
+        .iter()
+        .filter(|entry| matches_query(entry, &query))
+        .collect();
+
+    Ok(ModSearchPage {
+        total: matching.len(),
+        items: matching
+            .into_iter()
+            .skip(query.page * page_size)
+            .take(page_size)
+            .cloned()
+            .collect(),
+        page: query.page,
+        page_size,
+        fetched_at: index.fetched_at,
+    })
+}
+
+/// Where a browser mod is installed: skin mods go next to the skins they replace so
+/// `run_skin` picks them up, everything else gets its own folder.
+fn install_dir(layout: &WorkspaceLayout, entry: &ModIndexEntry) -> AppResult<PathBuf> {
+    // Indexes cached before mismatched entries were dropped may still contain them
+    if !skin_matches_champion(entry) {
+        return Err(AppError::ValidationFailed(format!(
+            "Mod {} lists skin {:?} under champion {:?}",
+            entry.id, entry.skin_id, entry.champion_id
+        )));
+    }
+
+    match (entry.champion_id, entry.skin_id) {
+        (Some(champion_id), Some(skin_id)) if champion_id > 0 && skin_id > 0 => {
+            layout.skin_dir(champion_id, skin_id)
+        }
+        _ => layout.browser_mod_dir(&entry.id),
+    }
+}
+
+#[command]
+pub async fn download_browser_mod(app_handle: AppHandle, mod_id: String) -> IpcResult<String> {
+    let download = run_resumable_job(
+        &app_handle,
+        JobKind::Download,
+        format!("Download mod {}", mod_id),
+        JobResume::BrowserMod {
+            mod_id: mod_id.clone(),
+        },
+        |_| download_browser_mod_inner(app_handle.clone(), mod_id.clone()),
+    );
+    match download.await {
+        Ok(msg) => IpcResult::Ok { value: msg },
+        Err(e) => IpcResult::Err {
+            error: crate::error::AppErrorResponse::new(
+                crate::error::ErrorCode::Unknown,
+                format!("{:#}", e),
+            ),
+        },
+    }
+}
+
+pub(crate) async fn download_browser_mod_inner(
+    app_handle: AppHandle,
+    mod_id: String,
+) -> anyhow::Result<String> {
+    let index = load_mod_index(&app_handle)
+        .await
+        .context("Failed to load mod index")?;
+    let entry = index
+        .mods
+        .iter()
+        .find(|m| m.id == mod_id)
+        .ok_or_else(|| anyhow::anyhow!("Mod {} not found in the index", mod_id))?;
+
+    let layout = WorkspaceLayout::from_app(&app_handle).context("Failed to get data directory")?;
+    let extract_to = install_dir(&layout, entry)?;
+    if extract_to.exists() {
+        return Ok(format!(
+            "{} already downloaded at {:?}",
+            entry.name, extract_to
+        ));
+    }
+
+    let archive_dir = extract_to
+        .parent()
+        .context("Install directory has no parent")?;
+    tokio::fs::create_dir_all(archive_dir)
+        .await
+        .with_context(|| format!("Failed to create {:?}", archive_dir))?;
+
+    download_and_extract(
+        std::slice::from_ref(&entry.download_url),
+        archive_dir,
+        &extract_to,
+    )
+    .await
+    .with_context(|| format!("Failed to download mod {}", entry.name))?;
+
+    Ok(format!(
+        "Downloaded and extracted {} to {:?}",
+        entry.name, extract_to
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(champion_id: Option<i32>, skin_id: Option<i32>) -> ModIndexEntry {
+        ModIndexEntry {
+            id: "test-mod".to_string(),
+            name: "Test mod".to_string(),
+            author: String::new(),
+            category: "skin".to_string(),
+            champion_id,
+            skin_id,
+            description: String::new(),
+            download_url: "https://example.com/test-mod.zip".to_string(),
+            tags: Vec::new(),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_install_dir_places_skin_mods_with_their_champion() {
+        let layout = WorkspaceLayout::new("workspace");
+        assert_eq!(
+            install_dir(&layout, &entry(Some(103), Some(103005))).unwrap(),
+            layout.skin_dir(103, 103005).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_install_dir_rejects_skin_of_another_champion() {
+        let layout = WorkspaceLayout::new("workspace");
+        assert!(install_dir(&layout, &entry(Some(103), Some(1005))).is_err());
+        assert!(!skin_matches_champion(&entry(Some(1), Some(103005))));
+    }
+
+    #[test]
+    fn test_install_dir_uses_mod_folder_without_skin() {
+        let layout = WorkspaceLayout::new("workspace");
+        assert_eq!(
+            install_dir(&layout, &entry(Some(103), None)).unwrap(),
+            layout.browser_mod_dir("test-mod").unwrap()
+        );
+        assert!(skin_matches_champion(&entry(None, Some(103005))));
+    }
+}
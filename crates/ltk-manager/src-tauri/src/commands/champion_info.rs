@@ -0,0 +1,194 @@
+//! Champion roles and the free champion rotation.
+//!
+//! Roles come from the champion summary of Community Dragon, downloaded with the skin database.
+//! The free rotation is read from the League client while it runs and kept in the workspace,
+//! so it is still known after the client closes. A rotation read from the client is reused for
+//! [`FREE_ROTATION_TTL`] before the client is asked again.
+
+use crate::cache::cached_catalog;
+use crate::commands::validate_champion_id;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::lcu::{LcuClient, LcuCredentials};
+use crate::state::SettingsState;
+use crate::workspace::{validate_id, WorkspaceLayout};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::fs;
+
+/// How long a rotation read from the client is used without asking it again.
+const FREE_ROTATION_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Last rotation read from the client, and when.
+static FREE_ROTATION: Mutex<Option<(Instant, FreeRotation)>> = Mutex::new(None);
+
+const CHAMPION_SUMMARY_URL: &str =
+    "https://raw.communitydragon.org/latest/plugins/rcp-be-lol-game-data/global/vi_vn/v1/champion-summary.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChampionSummary {
+    id: i32,
+    #[serde(default)]
+    alias: Option<String>,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreeRotation {
+    pub champion_ids: Vec<i32>,
+    /// When the rotation was read from the client.
+    pub fetched_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampionInfo {
+    pub id: i32,
+    pub name: Option<String>,
+    /// Internal name, e.g. `MonkeyKing` for Wukong.
+    pub alias: Option<String>,
+    /// E.g. `fighter` or `support`, empty until the database was refreshed.
+    pub roles: Vec<String>,
+    /// Whether the champion is in the free rotation, `None` while the rotation is unknown.
+    pub free_to_play: Option<bool>,
+}
+
+/// Download the roles of every champion, returning the number of champions.
+pub(crate) async fn download_champion_summary(layout: &WorkspaceLayout) -> AppResult<usize> {
+    tracing::info!("Downloading champion summary from {}", CHAMPION_SUMMARY_URL);
+    let summaries: Vec<ChampionSummary> = reqwest::get(CHAMPION_SUMMARY_URL)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::Other(format!("Failed to fetch champion summary: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to parse champion summary: {}", e)))?;
+
+    // Id -1 is the "None" placeholder champion
+    let summaries: Vec<ChampionSummary> = summaries.into_iter().filter(|c| c.id > 0).collect();
+    fs::write(
+        layout.champion_summary_file(),
+        serde_json::to_string_pretty(&summaries)?,
+    )
+    .await
+    .map_err(|e| AppError::Other(format!("Failed to write champion summary: {}", e)))?;
+
+    Ok(summaries.len())
+}
+
+async fn load_json<T: serde::de::DeserializeOwned>(
+    path: &std::path::Path,
+    what: &str,
+) -> AppResult<Option<T>> {
+    if !fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to read {}: {}", what, e)))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| AppError::Other(format!("Failed to parse {}: {}", what, e)))
+}
+
+/// The free rotation from the running client, or the last one read if it isn't running.
+async fn free_rotation(
+    app_handle: &AppHandle,
+    layout: &WorkspaceLayout,
+) -> AppResult<Option<FreeRotation>> {
+    let cached = FREE_ROTATION
+        .lock()
+        .ok()
+        .and_then(|cached| cached.clone())
+        .filter(|(read_at, _)| read_at.elapsed() < FREE_ROTATION_TTL);
+    if let Some((_, rotation)) = cached {
+        return Ok(Some(rotation));
+    }
+
+    let league_path = {
+        let state = app_handle.state::<SettingsState>();
+        let settings = state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        settings.league_path.clone()
+    };
+
+    let credentials = league_path.and_then(|path| LcuCredentials::from_install_dir(&path));
+    if let Some(credentials) = credentials {
+        match LcuClient::new(credentials)?.free_champion_ids().await {
+            Ok(champion_ids) if !champion_ids.is_empty() => {
+                let rotation = FreeRotation {
+                    champion_ids,
+                    fetched_at: chrono::Utc::now().to_rfc3339(),
+                };
+                fs::write(
+                    layout.free_rotation_file(),
+                    serde_json::to_string_pretty(&rotation)?,
+                )
+                .await
+                .map_err(|e| AppError::Other(format!("Failed to write free rotation: {}", e)))?;
+                if let Ok(mut cached) = FREE_ROTATION.lock() {
+                    *cached = Some((Instant::now(), rotation.clone()));
+                }
+                return Ok(Some(rotation));
+            }
+            Ok(_) => tracing::debug!("Client returned no free champions"),
+            Err(e) => tracing::debug!("Failed to read the free rotation: {}", e),
+        }
+    }
+
+    load_json(&layout.free_rotation_file(), "free rotation").await
+}
+
+#[tauri::command]
+pub async fn get_champion_info(app_handle: AppHandle, champion_id: i32) -> IpcResult<ChampionInfo> {
+    get_champion_info_inner(&app_handle, champion_id)
+        .await
+        .into()
+}
+
+async fn get_champion_info_inner(
+    app_handle: &AppHandle,
+    champion_id: i32,
+) -> AppResult<ChampionInfo> {
+    validate_id(champion_id, "champion")?;
+    validate_champion_id(app_handle, champion_id).await?;
+
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let catalog = cached_catalog(app_handle, &layout).await?;
+    // The base skin of a champion has the id `champion_id * 1000`
+    let name = champion_id
+        .checked_mul(1000)
+        .and_then(|base_skin_id| catalog.skin(base_skin_id))
+        .map(|skin| skin.name);
+    let summary =
+        load_json::<Vec<ChampionSummary>>(&layout.champion_summary_file(), "champion summary")
+            .await?
+            .and_then(|summaries| summaries.into_iter().find(|c| c.id == champion_id));
+    let rotation = free_rotation(app_handle, &layout).await?;
+
+    Ok(ChampionInfo {
+        id: champion_id,
+        name,
+        alias: summary.as_ref().and_then(|s| s.alias.clone()),
+        roles: summary.map(|s| s.roles).unwrap_or_default(),
+        free_to_play: rotation.map(|r| r.champion_ids.contains(&champion_id)),
+    })
+}
+
+/// The current free champion rotation, `None` until the client was seen running.
+#[tauri::command]
+pub async fn get_free_rotation(app_handle: AppHandle) -> IpcResult<Option<FreeRotation>> {
+    get_free_rotation_inner(&app_handle).await.into()
+}
+
+async fn get_free_rotation_inner(app_handle: &AppHandle) -> AppResult<Option<FreeRotation>> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    free_rotation(app_handle, &layout).await
+}
@@ -0,0 +1,312 @@
+//! Known crashing mod and champion combinations.
+//!
+//! Entries come from three places: a list bundled with the app, a community list fetched
+//! from `compatListUrl`, and crashes the user recorded locally. Building an overlay for a
+//! skin with a matching entry only warns, the user decides whether to keep playing it.
+
+use crate::commands::data::load_saved_version;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::workspace::WorkspaceLayout;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+const BUNDLED_LIST: &str = include_str!("../../compat/known_crashes.json");
+
+/// Serializes read-modify-write cycles on the crash reports file.
+static CRASH_REPORTS_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// A mod known to crash the game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatEntry {
+    /// Skin id for repository skins, index id for browser mods.
+    pub mod_id: String,
+    /// Only crashes with this champion, any champion if unset.
+    #[serde(default)]
+    pub champion_id: Option<i32>,
+    /// Game version prefix the crash happens on, e.g. `14.3`. Every version if unset.
+    #[serde(default)]
+    pub game_version: Option<String>,
+    #[serde(default)]
+    pub reason: String,
+    #[serde(default)]
+    pub reported_at: Option<String>,
+}
+
+impl CompatEntry {
+    fn matches(&self, mod_id: &str, champion_id: Option<i32>, game_version: Option<&str>) -> bool {
+        if self.mod_id != mod_id {
+            return false;
+        }
+        if self.champion_id.is_some() && champion_id.is_some() && self.champion_id != champion_id {
+            return false;
+        }
+        // Without a known game version the entry can't be ruled out
+        match (&self.game_version, game_version) {
+            (Some(prefix), Some(version)) => version.starts_with(prefix.as_str()),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CompatList {
+    #[serde(default)]
+    entries: Vec<CompatEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedCompatList {
+    source_url: String,
+    fetched_at: String,
+    entries: Vec<CompatEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CompatSource {
+    Bundled,
+    Remote,
+    Local,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatWarning {
+    pub source: CompatSource,
+    pub entry: CompatEntry,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatListStatus {
+    pub source_url: String,
+    pub fetched_at: String,
+    pub count: usize,
+}
+
+fn bundled_entries() -> Vec<CompatEntry> {
+    match serde_json::from_str::<CompatList>(BUNDLED_LIST) {
+        Ok(list) => list.entries,
+        Err(e) => {
+            tracing::error!("Bundled known crashes list is invalid: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+async fn load_remote_entries(layout: &WorkspaceLayout) -> Vec<CompatEntry> {
+    let Ok(content) = fs::read_to_string(layout.compat_cache_file()).await else {
+        return Vec::new();
+    };
+    match serde_json::from_str::<CachedCompatList>(&content) {
+        Ok(cache) => cache.entries,
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable known crashes cache: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+async fn load_crash_reports(layout: &WorkspaceLayout) -> AppResult<Vec<CompatEntry>> {
+    let path = layout.crash_reports_file();
+    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to read crash reports: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::Other(format!("Failed to parse crash reports: {}", e)))
+}
+
+async fn save_crash_reports(layout: &WorkspaceLayout, reports: &[CompatEntry]) -> AppResult<()> {
+    let json = serde_json::to_string_pretty(reports)?;
+    fs::write(layout.crash_reports_file(), json)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to write crash reports: {}", e)))
+}
+
+/// Known crashes of `mod_id` (with `champion_id`, if given) on the current game version.
+pub(crate) async fn compat_warnings(
+    app_handle: &AppHandle,
+    mod_id: &str,
+    champion_id: Option<i32>,
+) -> AppResult<Vec<CompatWarning>> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let game_version = load_saved_version(app_handle).await?.map(|v| v.version);
+
+    let sources = [
+        (CompatSource::Bundled, bundled_entries()),
+        (CompatSource::Remote, load_remote_entries(&layout).await),
+        (CompatSource::Local, load_crash_reports(&layout).await?),
+    ];
+
+    Ok(sources
+        .into_iter()
+        .flat_map(|(source, entries)| {
+            entries
+                .into_iter()
+                .map(move |entry| CompatWarning { source, entry })
+        })
+        .filter(|warning| {
+            warning
+                .entry
+                .matches(mod_id, champion_id, game_version.as_deref())
+        })
+        .collect())
+}
+
+/// Fetch the community known crashes list from `compatListUrl` and cache it.
+#[tauri::command]
+pub async fn refresh_compat_list(app_handle: AppHandle) -> IpcResult<CompatListStatus> {
+    refresh_compat_list_inner(&app_handle).await.into()
+}
+
+async fn refresh_compat_list_inner(app_handle: &AppHandle) -> AppResult<CompatListStatus> {
+    let url = {
+        let settings_state = app_handle.state::<crate::state::SettingsState>();
+        let settings = settings_state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        settings.compat_list_url.clone().ok_or_else(|| {
+            AppError::ValidationFailed(
+                "Known crashes list URL not configured. Please set it in Settings.".to_string(),
+            )
+        })?
+    };
+    tracing::info!("Fetching known crashes list from {}", url);
+
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to fetch known crashes list: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::Other(format!(
+            "Failed to fetch known crashes list: HTTP {}",
+            response.status()
+        )));
+    }
+    let list: CompatList = response
+        .json()
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to parse known crashes list: {}", e)))?;
+
+    let cache = CachedCompatList {
+        source_url: url,
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+        entries: list.entries,
+    };
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    fs::write(layout.compat_cache_file(), serde_json::to_string(&cache)?)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to write known crashes cache: {}", e)))?;
+
+    Ok(CompatListStatus {
+        source_url: cache.source_url,
+        fetched_at: cache.fetched_at,
+        count: cache.entries.len(),
+    })
+}
+
+#[tauri::command]
+pub async fn check_mod_compat(
+    app_handle: AppHandle,
+    mod_id: String,
+    champion_id: Option<i32>,
+) -> IpcResult<Vec<CompatWarning>> {
+    compat_warnings(&app_handle, &mod_id, champion_id)
+        .await
+        .into()
+}
+
+/// Record that `mod_id` crashed the game on the current game version.
+#[tauri::command]
+pub async fn record_crash(
+    app_handle: AppHandle,
+    mod_id: String,
+    champion_id: Option<i32>,
+    reason: Option<String>,
+) -> IpcResult<CompatEntry> {
+    record_crash_inner(&app_handle, mod_id, champion_id, reason)
+        .await
+        .into()
+}
+
+pub(crate) async fn record_crash_inner(
+    app_handle: &AppHandle,
+    mod_id: String,
+    champion_id: Option<i32>,
+    reason: Option<String>,
+) -> AppResult<CompatEntry> {
+    if mod_id.trim().is_empty() {
+        return Err(AppError::ValidationFailed("Mod id is empty".to_string()));
+    }
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let entry = CompatEntry {
+        mod_id,
+        champion_id,
+        game_version: load_saved_version(app_handle).await?.map(|v| v.version),
+        reason: reason
+            .filter(|r| !r.trim().is_empty())
+            .unwrap_or_else(|| "Crashed the game".to_string()),
+        reported_at: Some(chrono::Utc::now().to_rfc3339()),
+    };
+
+    let _guard = CRASH_REPORTS_LOCK.lock().await;
+    let mut reports = load_crash_reports(&layout).await?;
+    reports.retain(|r| {
+        (&r.mod_id, r.champion_id, &r.game_version)
+            != (&entry.mod_id, entry.champion_id, &entry.game_version)
+    });
+    reports.push(entry.clone());
+    save_crash_reports(&layout, &reports).await?;
+
+    tracing::info!("Recorded a crash for mod {}", entry.mod_id);
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn list_crash_reports(app_handle: AppHandle) -> IpcResult<Vec<CompatEntry>> {
+    list_crash_reports_inner(&app_handle).await.into()
+}
+
+async fn list_crash_reports_inner(app_handle: &AppHandle) -> AppResult<Vec<CompatEntry>> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    load_crash_reports(&layout).await
+}
+
+/// Forget the recorded crashes of `mod_id`, only those with `champion_id` if given.
+#[tauri::command]
+pub async fn delete_crash_report(
+    app_handle: AppHandle,
+    mod_id: String,
+    champion_id: Option<i32>,
+) -> IpcResult<()> {
+    delete_crash_report_inner(&app_handle, &mod_id, champion_id)
+        .await
+        .into()
+}
+
+async fn delete_crash_report_inner(
+    app_handle: &AppHandle,
+    mod_id: &str,
+    champion_id: Option<i32>,
+) -> AppResult<()> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+
+    let _guard = CRASH_REPORTS_LOCK.lock().await;
+    let mut reports = load_crash_reports(&layout).await?;
+    let before = reports.len();
+    reports
+        .retain(|r| r.mod_id != mod_id || (champion_id.is_some() && r.champion_id != champion_id));
+    if reports.len() != before {
+        save_crash_reports(&layout, &reports).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+//! Exporting mods to a cslol-manager folder.
+//!
+//! cslol-manager keeps each mod in `installed/{name}` and the enabled mods of a profile as
+//! one mod name per line in `profiles/{profile}.profile`; `current.profile` names the
+//! selected profile. Workspace mods already use its `META/info.json` + `WAD/` layout.
+
+use crate::commands::announcer::link_tree;
+use crate::commands::import::{copy_tree, sanitize_name};
+use crate::commands::mod_list::{collect_mod_list, entry_dir, ModListEntry};
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::workspace::{validate_name, WorkspaceLayout};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const INSTALLED_DIRNAME: &str = "installed";
+const PROFILES_DIRNAME: &str = "profiles";
+const PROFILE_EXTENSION: &str = "profile";
+const CURRENT_PROFILE_FILENAME: &str = "current.profile";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CslolExportFailure {
+    pub entry: ModListEntry,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CslolExportReport {
+    /// Folder names the mods got in `installed/`.
+    pub exported: Vec<String>,
+    pub failed: Vec<CslolExportFailure>,
+    /// The written profile file, if a profile name was given.
+    pub profile: Option<PathBuf>,
+}
+
+/// Export `mods` (every installed mod if empty) to the cslol-manager folder `path`.
+///
+/// Mods that already exist in `installed/` under the same name are replaced. With
+/// `profile_name`, a profile enabling the exported mods is written and selected. With `link`,
+/// files are hard linked instead of copied.
+#[tauri::command]
+pub async fn export_to_cslol(
+    app_handle: AppHandle,
+    path: String,
+    mods: Vec<ModListEntry>,
+    profile_name: Option<String>,
+    link: bool,
+) -> IpcResult<CslolExportReport> {
+    export_to_cslol_inner(&app_handle, PathBuf::from(path), mods, profile_name, link)
+        .await
+        .into()
+}
+
+async fn export_to_cslol_inner(
+    app_handle: &AppHandle,
+    cslol_dir: PathBuf,
+    mods: Vec<ModListEntry>,
+    profile_name: Option<String>,
+    link: bool,
+) -> AppResult<CslolExportReport> {
+    if !cslol_dir.is_dir() {
+        return Err(AppError::InvalidPath(cslol_dir.display().to_string()));
+    }
+    if let Some(profile_name) = &profile_name {
+        validate_name(profile_name, "profile name")?;
+    }
+
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let mods = if mods.is_empty() {
+        collect_mod_list(app_handle).await?.mods
+    } else {
+        mods
+    };
+
+    tokio::task::spawn_blocking(move || {
+        export_blocking(&layout, &cslol_dir, mods, profile_name.as_deref(), link)
+    })
+    .await
+    .map_err(|e| AppError::Other(format!("cslol-manager export task failed: {}", e)))?
+}
+
+fn export_blocking(
+    layout: &WorkspaceLayout,
+    cslol_dir: &Path,
+    mods: Vec<ModListEntry>,
+    profile_name: Option<&str>,
+    link: bool,
+) -> AppResult<CslolExportReport> {
+    let installed_dir = cslol_dir.join(INSTALLED_DIRNAME);
+    std::fs::create_dir_all(&installed_dir)?;
+
+    let mut report = CslolExportReport::default();
+    let mut used_names = HashSet::new();
+    for entry in mods {
+        let Some(source) = entry_dir(layout, &entry).filter(|dir| dir.is_dir()) else {
+            report.failed.push(CslolExportFailure {
+                error: "Mod is not installed".to_string(),
+                entry,
+            });
+            continue;
+        };
+
+        let name = unique_name(&entry, &mut used_names);
+        let target = installed_dir.join(&name);
+        match export_mod(&source, &target, link) {
+            Ok(()) => report.exported.push(name),
+            Err(e) => {
+                tracing::warn!("Failed to export {} to cslol-manager: {}", name, e);
+                let _ = std::fs::remove_dir_all(&target);
+                report.failed.push(CslolExportFailure {
+                    error: e.to_string(),
+                    entry,
+                });
+            }
+        }
+    }
+
+    if let Some(profile_name) = profile_name {
+        let profiles_dir = cslol_dir.join(PROFILES_DIRNAME);
+        std::fs::create_dir_all(&profiles_dir)?;
+
+        let profile_path = profiles_dir.join(format!("{}.{}", profile_name, PROFILE_EXTENSION));
+        let mut content = report.exported.join("\n");
+        content.push('\n');
+        std::fs::write(&profile_path, content)?;
+        std::fs::write(profiles_dir.join(CURRENT_PROFILE_FILENAME), profile_name)?;
+        report.profile = Some(profile_path);
+    }
+
+    tracing::info!(
+        "Exported {} mod(s) to {:?}",
+        report.exported.len(),
+        cslol_dir
+    );
+    Ok(report)
+}
+
+fn export_mod(source: &Path, target: &Path, link: bool) -> std::io::Result<()> {
+    if target.exists() {
+        std::fs::remove_dir_all(target)?;
+    }
+    if link {
+        link_tree(source, target, &|_| false)
+    } else {
+        copy_tree(source, target)
+    }
+}
+
+/// Folder name for `entry` in `installed/`, made unique by appending the id on collisions.
+fn unique_name(entry: &ModListEntry, used_names: &mut HashSet<String>) -> String {
+    let base = Some(sanitize_name(&entry.name))
+        .filter(|name| validate_name(name, "mod name").is_ok())
+        .unwrap_or_else(|| sanitize_name(&entry.id));
+
+    let mut name = base.clone();
+    if used_names.contains(&name.to_lowercase()) {
+        name = format!("{} ({})", base, sanitize_name(&entry.id));
+    }
+    used_names.insert(name.to_lowercase());
+    name
+}
@@ -1,26 +1,29 @@
 use crate::error::{AppError, AppResult, IpcResult};
+use crate::events::{emit, AppEvent, EventSource};
+use crate::jobs::{run_job, JobHandle, JobKind};
+use crate::workspace::{validate_id, WorkspaceLayout};
 use crate::commands::merge_data::{prune_metadata, RawMetadata};
+use crate::cache::{cached_annotations, cached_catalog, cached_champion_metadata};
+use crate::commands::skin_catalog::{load_catalog, save_catalog, SkinCatalog};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 use tokio::fs;
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 
 const SKIN_IDS_URL: &str =
     "https://github.com/Alban1911/LeagueSkins/raw/main/resources/vi/skin_ids.json";
-const SKIN_IDS_FILENAME: &str = "skin_ids.json";
 const VERSION_API_URL: &str = "https://ddragon.leagueoflegends.com/api/versions.json";
-const VERSION_FILENAME: &str = "version.json";
 
 const METADATA_URL_TEMPLATE: &str = 
     "https://raw.communitydragon.org/latest/plugins/rcp-be-lol-game-data/global/vi_vn/v1/champions/{id}.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct VersionInfo {
-    version: String,
+pub(crate) struct VersionInfo {
+    pub(crate) version: String,
     last_updated: String,
     #[serde(default)]
     hash_1: String
@@ -43,31 +46,36 @@ pub struct ChampionWithSkins {
     pub skin_collection: BTreeMap<String, String>,
 }
 
-fn get_data_dir(app_handle: &AppHandle) -> AppResult<PathBuf> {
-    let settings_state = app_handle.state::<crate::state::SettingsState>();
-    let settings = settings_state
-        .0
-        .lock()
-        .map_err(|e| AppError::InternalState(e.to_string()))?;
-
-    match &settings.workspace_path {
-        Some(path) => Ok(path.clone()),
-        None => Err(AppError::Other(
-            "Workspace path not configured. Please set it in Settings.".to_string(),
-        )),
-    }
+pub(crate) fn get_data_dir(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    Ok(WorkspaceLayout::from_app(app_handle)?.root().to_path_buf())
 }
 
 #[tauri::command]
 pub async fn refresh_skin_database(app_handle: AppHandle) -> IpcResult<UpdateResult> {
-    refresh_skin_database_inner(&app_handle).await.into()
+    let app = &app_handle;
+    let result = run_job(app, JobKind::DatabaseRefresh, "Refresh skin database", |job| async move {
+        refresh_skin_database_inner(app, &job).await
+    })
+    .await;
+    match &result {
+        Ok(update) => emit(
+            &app_handle,
+            AppEvent::completed(EventSource::SkinDatabase, update.message.clone()),
+        ),
+        Err(e) => emit(&app_handle, AppEvent::error(EventSource::SkinDatabase, e.to_string())),
+    }
+    result.into()
 }
 
-async fn refresh_skin_database_inner(app_handle: &AppHandle) -> AppResult<UpdateResult> {
+async fn refresh_skin_database_inner(
+    app_handle: &AppHandle,
+    job: &JobHandle,
+) -> AppResult<UpdateResult> {
     tracing::info!("Fetching skin database and champion data...");
 
     // Ensure data directory exists
-    let data_dir = get_data_dir(app_handle)?;
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let data_dir = layout.root();
     if !data_dir.exists() {
         fs::create_dir_all(&data_dir)
             .await
@@ -98,57 +106,89 @@ async fn refresh_skin_database_inner(app_handle: &AppHandle) -> AppResult<Update
     let skins_count = skins.len();
     tracing::info!("Fetched {} skin entries", skins_count);
 
-    // Save skin_ids.json
-    let skins_file_path = data_dir.join(SKIN_IDS_FILENAME);
-    fs::write(&skins_file_path, skins_text)
-        .await
-        .map_err(|e| AppError::Other(format!("Failed to write skin data file: {}", e)))?;
+    // Only rewrite the stored skin data when the skins changed
+    let mut catalog = SkinCatalog::from_skin_ids(&skins);
+    let previous_catalog = load_catalog(&layout).await?;
+    match crate::commands::download_legacy_skin_ids().await {
+        Ok(legacy) => catalog.set_legacy_skin_ids(Some(legacy)),
+        Err(e) => {
+            tracing::warn!("Failed to download legacy skins, keeping the previous list: {:?}", e);
+            catalog.set_legacy_skin_ids(previous_catalog.legacy_skin_ids().cloned());
+        }
+    }
+    let changes = catalog.changes_from(&previous_catalog);
+    let organized_champions = catalog.champions_with_skins();
+    let organized_file_path = layout.champions_file();
 
-    // Organize và lưu champions_with_skins.json
-    tracing::info!("Organizing champions with skins...");
-    let organized_champions = organize_skins_by_champion(skins);
-    let organized_count = organized_champions.len();
-    
-    let organized_json = serde_json::to_string_pretty(&organized_champions)
-        .map_err(|e| AppError::Other(format!("Failed to serialize organized data: {}", e)))?;
-    
-    let organized_file_path = data_dir.join("champions_with_skins.json");
-    fs::write(&organized_file_path, organized_json)
-        .await
-        .map_err(|e| AppError::Other(format!("Failed to write organized data file: {}", e)))?;
+    if changes.is_empty() && organized_file_path.exists() {
+        tracing::info!("Skin data unchanged, keeping stored files");
+    } else {
+        tracing::info!(
+            "Skin data changed: {} added, {} removed, {} renamed, {} legacy changes",
+            changes.added, changes.removed, changes.renamed, changes.legacy_changed
+        );
+
+        // Save skin_ids.json
+        let skins_file_path = layout.skin_ids_file();
+        fs::write(&skins_file_path, skins_text)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to write skin data file: {}", e)))?;
+        save_catalog(&layout, &catalog).await?;
+
+        let organized_json = serde_json::to_string_pretty(&organized_champions)
+            .map_err(|e| AppError::Other(format!("Failed to serialize organized data: {}", e)))?;
+        fs::write(&organized_file_path, organized_json)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to write organized data file: {}", e)))?;
 
-    tracing::info!("Saved {} champions with skins to file", organized_count);
+        tracing::info!("Saved {} champions with skins to file", organized_champions.len());
+    }
 
     // Download champion icons
 
 
     // Initialize data folders and download metadata
-    let metadata_count = download_champion_metadata(app_handle, &data_dir, &organized_champions).await?;
+    let metadata_count = download_champion_metadata(app_handle, job, &layout, &organized_champions).await;
+    // Files may have been rewritten even if the download failed part way
+    crate::cache::invalidate(app_handle);
+    let metadata_count = metadata_count?;
+
+    job.status("Downloading ward skin and emote metadata...");
+    match crate::commands::ward_skin::download_cosmetic_metadata(&layout).await {
+        Ok(count) => tracing::info!("Saved {} ward skin and emote entries", count),
+        Err(e) => tracing::warn!("Failed to download ward skin and emote metadata: {:?}", e),
+    }
+
+    // Roles are extra information, the skins work without them
+    match crate::commands::download_champion_summary(&layout).await {
+        Ok(count) => tracing::info!("Saved roles of {} champions", count),
+        Err(e) => tracing::warn!("Failed to download champion summary: {:?}", e),
+    }
+
+    // TFT cosmetics are optional, a failed download shouldn't fail the champion refresh
+    if crate::commands::tft::is_tft_enabled(app_handle)? {
+        match crate::commands::tft::download_tft_metadata(&layout).await {
+            Ok(count) => tracing::info!("Saved {} TFT items", count),
+            Err(e) => tracing::warn!("Failed to download TFT metadata: {:?}", e),
+        }
+    }
 
     Ok(UpdateResult {
         success: true,
         message: format!(
-            "Updated {} skins, 0 champions (derived), {} metadata files checked",
-            skins_count, metadata_count
+            "Updated {} skins ({} added, {} removed, {} renamed), {} metadata files checked",
+            skins_count, changes.added, changes.removed, changes.renamed, metadata_count
         ),
         count: skins_count,
     })
 }
 
-async fn download_champion_metadata(
+pub(crate) async fn download_champion_metadata(
     app_handle: &AppHandle,
-    data_dir: &PathBuf,
+    job: &JobHandle,
+    layout: &WorkspaceLayout,
     champions: &[ChampionWithSkins],
 ) -> AppResult<usize> {
-    use tauri::Emitter;
-
-    #[derive(Clone, Serialize)]
-    struct ProgressPayload {
-        processed: usize,
-        total: usize,
-        message: String,
-    }
-
     let client = reqwest::Client::new();
     let mut join_set = JoinSet::new();
     let semaphore = Arc::new(Semaphore::new(50));
@@ -156,11 +196,10 @@ async fn download_champion_metadata(
 
     // Count total work
     let total_work = champions.iter().filter(|c| c.id > 0).count();
-    let _ = app_handle.emit("metadata-download-progress", ProgressPayload {
-        processed: 0,
-        total: total_work,
-        message: "Downloading metadata...".into(),
-    });
+    emit(
+        app_handle,
+        AppEvent::progress(EventSource::Metadata, 0, total_work, "Downloading metadata..."),
+    );
 
     // Build skin IDs map for pruning
     let skin_ids_map: HashMap<i32, HashSet<i32>> = champions
@@ -184,7 +223,8 @@ async fn download_champion_metadata(
         let client = client.clone();
         let champ_id = champion.id;
         let champ_name = champion.name.clone();
-        let data_dir = data_dir.clone();
+        let champion_dir = layout.champion_dir(champ_id)?;
+        let metadata_path = layout.champion_metadata_file(champ_id)?;
         let valid_skin_ids = skin_ids_map.get(&champ_id).cloned().unwrap_or_default();
         let permit = semaphore.clone().acquire_owned().await.map_err(|e| {
             AppError::Other(format!("Failed to acquire semaphore: {}", e))
@@ -195,8 +235,6 @@ async fn download_champion_metadata(
             let _permit = permit;
 
             // Create champion specific folder: data/{id}
-            let champion_dir = data_dir.join("data").join(champ_id.to_string());
-            
             // Check existence asynchronously
             if !tokio::fs::try_exists(&champion_dir).await.unwrap_or(false) {
                 if let Err(e) = fs::create_dir_all(&champion_dir).await {
@@ -208,8 +246,6 @@ async fn download_champion_metadata(
                 }
             }
 
-            let metadata_path = champion_dir.join("metadata.json");
-
             // Skip if metadata already exists
             if tokio::fs::try_exists(&metadata_path).await.unwrap_or(false) {
                 return 0;
@@ -289,11 +325,12 @@ async fn download_champion_metadata(
     let mut processed = 0;
     while let Some(result) = join_set.join_next().await {
         processed += 1;
-        let _ = app_handle.emit("metadata-download-progress", ProgressPayload {
-            processed,
-            total: total_work,
-            message: format!("Downloading metadata... {}/{}", processed, total_work),
-        });
+        let message = format!("Downloading metadata... {}/{}", processed, total_work);
+        job.progress(processed, total_work, &message);
+        emit(
+            app_handle,
+            AppEvent::progress(EventSource::Metadata, processed, total_work, message),
+        );
 
         match result {
             Ok(downloaded) => count += downloaded,
@@ -309,9 +346,8 @@ pub async fn get_skin_database(app_handle: AppHandle) -> IpcResult<HashMap<Strin
     get_skin_database_inner(&app_handle).await.into()
 }
 
-async fn get_skin_database_inner(app_handle: &AppHandle) -> AppResult<HashMap<String, String>> {
-    let data_dir = get_data_dir(app_handle)?;
-    let file_path = data_dir.join(SKIN_IDS_FILENAME);
+pub(crate) async fn get_skin_database_inner(app_handle: &AppHandle) -> AppResult<HashMap<String, String>> {
+    let file_path = WorkspaceLayout::from_app(app_handle)?.skin_ids_file();
 
     if !file_path.exists() {
         return Ok(HashMap::new());
@@ -327,44 +363,45 @@ async fn get_skin_database_inner(app_handle: &AppHandle) -> AppResult<HashMap<St
     Ok(skins)
 }
 
+/// Check that `champion_id` is a champion of the skin database.
+///
+/// Only the id range is checked while the database hasn't been downloaded yet.
+pub(crate) async fn validate_champion_id(app_handle: &AppHandle, champion_id: i32) -> AppResult<()> {
+    validate_id(champion_id, "champion")?;
 
-// Organize skins theo champion
-fn organize_skins_by_champion(
-    skins: HashMap<String, String>,
-) -> Vec<ChampionWithSkins> {
-    let mut champions_map: HashMap<i32, String> = HashMap::new();
-    let mut champion_skins: HashMap<i32, BTreeMap<String, String>> = HashMap::new();
-
-    for (skin_id_str, skin_name) in &skins {
-        if let Ok(id) = skin_id_str.parse::<i32>() {
-            let champion_id = id / 1000;
-            if id % 1000 == 0 {
-                champions_map.insert(champion_id, skin_name.clone());
-            }
-            champion_skins
-                .entry(champion_id)
-                .or_default()
-                .insert(skin_id_str.clone(), skin_name.clone());
-        }
+    let catalog = cached_catalog(app_handle, &WorkspaceLayout::from_app(app_handle)?).await?;
+    if !catalog.is_empty() && !catalog.has_champion(champion_id) {
+        return Err(AppError::ChampionNotFound(champion_id));
     }
+    Ok(())
+}
 
-    let mut result: Vec<ChampionWithSkins> = champions_map
-        .into_iter()
-        .map(|(champion_id, name)| {
-            let skin_collection = champion_skins.remove(&champion_id).unwrap_or_default();
-            ChampionWithSkins {
-                id: champion_id,
-                name,
-                skin_collection,
-            }
-        })
-        .collect();
-
-    result.sort_by_key(|c| c.id);
-
-    result
+/// Check that `skin_id` is in the skin database and belongs to `champion_id`.
+///
+/// Only the id ranges are checked while the database hasn't been downloaded yet.
+pub(crate) async fn validate_skin_id(
+    app_handle: &AppHandle,
+    champion_id: i32,
+    skin_id: i32,
+) -> AppResult<()> {
+    validate_id(champion_id, "champion")?;
+    validate_id(skin_id, "skin")?;
+
+    let catalog = cached_catalog(app_handle, &WorkspaceLayout::from_app(app_handle)?).await?;
+    if !catalog.is_empty() && !catalog.has_champion(champion_id) {
+        return Err(AppError::ChampionNotFound(champion_id));
+    }
+    // Skin and chroma ids are `champion_id * 1000 + n`
+    if skin_id / 1000 != champion_id || (!catalog.is_empty() && !catalog.has_skin(skin_id)) {
+        return Err(AppError::SkinNotFound {
+            champion_id,
+            skin_id,
+        });
+    }
+    Ok(())
 }
 
+
 #[tauri::command]
 pub async fn get_champions_with_skins(
     app_handle: AppHandle,
@@ -375,23 +412,16 @@ pub async fn get_champions_with_skins(
 async fn get_champions_with_skins_inner(
     app_handle: &AppHandle,
 ) -> AppResult<Vec<ChampionWithSkins>> {
-    let data_dir = get_data_dir(app_handle)?;
-    let file_path = data_dir.join("champions_with_skins.json");
+    let catalog = cached_catalog(app_handle, &WorkspaceLayout::from_app(app_handle)?).await?;
 
-    if !file_path.exists() {
+    if catalog.is_empty() {
         return Err(AppError::Other(
             "Champions data not found. Please refresh the database first.".to_string(),
         ));
     }
 
-    let content = fs::read_to_string(&file_path)
-        .await
-        .map_err(|e| AppError::Other(format!("Failed to read champions data file: {}", e)))?;
-
-    let champions: Vec<ChampionWithSkins> = serde_json::from_str(&content)
-        .map_err(|e| AppError::Other(format!("Failed to parse champions data file: {}", e)))?;
-
-    tracing::info!("Loaded {} champions with skins from file", champions.len());
+    let champions = catalog.champions_with_skins();
+    tracing::info!("Loaded {} champions with skins from the catalog", champions.len());
 
     Ok(champions)
 }
@@ -421,9 +451,8 @@ async fn fetch_latest_version() -> AppResult<String> {
         .ok_or_else(|| AppError::Other("Version list is empty".to_string()))
 }
 
-async fn load_saved_version(app_handle: &AppHandle) -> AppResult<Option<VersionInfo>> {
-    let data_dir = get_data_dir(app_handle)?;
-    let file_path = data_dir.join(VERSION_FILENAME);
+pub(crate) async fn load_saved_version(app_handle: &AppHandle) -> AppResult<Option<VersionInfo>> {
+    let file_path = WorkspaceLayout::from_app(app_handle)?.version_file();
 
     if !file_path.exists() {
         return Ok(None);
@@ -463,7 +492,7 @@ async fn save_version(app_handle: &AppHandle, version: &str) -> AppResult<()> {
     let json = serde_json::to_string_pretty(&version_info)
         .map_err(|e| AppError::Other(format!("Failed to serialize version: {}", e)))?;
 
-    let file_path = data_dir.join(VERSION_FILENAME);
+    let file_path = WorkspaceLayout::from_app(app_handle)?.version_file();
     fs::write(&file_path, json)
         .await
         .map_err(|e| AppError::Other(format!("Failed to write version file: {}", e)))?;
@@ -473,10 +502,18 @@ async fn save_version(app_handle: &AppHandle, version: &str) -> AppResult<()> {
 
 #[tauri::command]
 pub async fn check_and_update_database(app_handle: AppHandle) -> IpcResult<UpdateResult> {
-    check_and_update_database_inner(&app_handle).await.into()
+    let app = &app_handle;
+    run_job(app, JobKind::DatabaseRefresh, "Check for database updates", |job| async move {
+        check_and_update_database_inner(app, &job).await
+    })
+    .await
+    .into()
 }
 
-async fn check_and_update_database_inner(app_handle: &AppHandle) -> AppResult<UpdateResult> {
+async fn check_and_update_database_inner(
+    app_handle: &AppHandle,
+    job: &JobHandle,
+) -> AppResult<UpdateResult> {
     tracing::info!("Checking for database updates...");
 
     let latest_version = fetch_latest_version().await?;
@@ -490,8 +527,9 @@ async fn check_and_update_database_inner(app_handle: &AppHandle) -> AppResult<Up
             true
         }
         Some(saved) => {
-            let data_folder = get_data_dir(app_handle)?.join("data");
-            let champions_file = get_data_dir(app_handle)?.join("champions_with_skins.json");
+            let layout = WorkspaceLayout::from_app(app_handle)?;
+            let data_folder = layout.data_dir();
+            let champions_file = layout.champions_file();
             
             if !data_folder.exists() || !champions_file.exists() {
                 tracing::info!("Data directory or champions file missing, forcing update");
@@ -511,7 +549,7 @@ async fn check_and_update_database_inner(app_handle: &AppHandle) -> AppResult<Up
     };
 
     if should_update {
-        let result = refresh_skin_database_inner(app_handle).await?;
+        let result = refresh_skin_database_inner(app_handle, job).await?;
 
         save_version(app_handle, &latest_version).await?;
 
@@ -545,49 +583,111 @@ pub struct SkinData {
     pub tile_path: String,
     pub rarity: String,
     pub is_base: bool,
+    /// Vaulted skins that are only sold during special events.
+    #[serde(default)]
+    pub is_legacy: bool,
+    /// Availability as listed in the game data, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability: Option<String>,
     #[serde(default)]
     pub chromas: Vec<ChromaData>,
+    /// The user's rating and notes for this skin, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<crate::commands::annotations::ModAnnotation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct ChampionMetadata {
+pub(crate) struct ChampionMetadata {
     pub id: i32,
     pub name: String,
     pub skins: Vec<SkinData>,
 }
 
+/// Read `data/{id}/metadata.json`, `None` if it hasn't been downloaded.
+pub(crate) async fn load_champion_metadata(
+    layout: &WorkspaceLayout,
+    champion_id: i32,
+) -> AppResult<Option<ChampionMetadata>> {
+    let metadata_path = layout.champion_metadata_file(champion_id)?;
+
+    if !tokio::fs::try_exists(&metadata_path).await.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&metadata_path)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to read metadata file: {}", e)))?;
+
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| AppError::Other(format!("Failed to parse metadata file: {}", e)))
+}
+
+/// Which skins `get_champion_skins` returns, every skin if a field is unset.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkinFilter {
+    /// Only legacy skins if true, only skins that are not legacy if false.
+    #[serde(default)]
+    pub legacy: Option<bool>,
+    /// Only skins with this availability, compared ignoring case.
+    #[serde(default)]
+    pub availability: Option<String>,
+}
+
+impl SkinFilter {
+    fn matches(&self, skin: &SkinData) -> bool {
+        self.legacy.is_none_or(|legacy| skin.is_legacy == legacy)
+            && self.availability.as_ref().is_none_or(|availability| {
+                skin.availability
+                    .as_ref()
+                    .is_some_and(|a| a.eq_ignore_ascii_case(availability))
+            })
+    }
+}
+
 #[tauri::command]
 pub async fn get_champion_skins(
     app_handle: AppHandle,
     champion_id: i32,
+    filter: Option<SkinFilter>,
 ) -> IpcResult<Vec<SkinData>> {
-    get_champion_skins_inner(&app_handle, champion_id).await.into()
+    get_champion_skins_inner(&app_handle, champion_id, filter.unwrap_or_default()).await.into()
 }
 
 async fn get_champion_skins_inner(
     app_handle: &AppHandle,
     champion_id: i32,
+    filter: SkinFilter,
 ) -> AppResult<Vec<SkinData>> {
-    let data_dir = get_data_dir(app_handle)?;
-    // Path: data/{id}/metadata.json
-    let metadata_path = data_dir
-        .join("data")
-        .join(champion_id.to_string())
-        .join("metadata.json");
+    validate_champion_id(app_handle, champion_id).await?;
 
-    if !tokio::fs::try_exists(&metadata_path).await.unwrap_or(false) {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let Some(metadata) = cached_champion_metadata(app_handle, &layout, champion_id).await? else {
         return Ok(Vec::new());
-    }
-
-    let content = fs::read_to_string(&metadata_path)
-        .await
-        .map_err(|e| AppError::Other(format!("Failed to read metadata file: {}", e)))?;
+    };
+    let catalog = cached_catalog(app_handle, &layout).await?;
+    let mut skins: Vec<SkinData> = metadata
+        .skins
+        .iter()
+        .cloned()
+        .map(|mut skin| {
+            // The metadata keeps the flag from when it was downloaded
+            if let Some(is_legacy) = catalog.is_legacy(skin.id) {
+                skin.is_legacy = is_legacy;
+            }
+            skin
+        })
+        .filter(|skin| filter.matches(skin))
+        .collect();
 
-    let metadata: ChampionMetadata = serde_json::from_str(&content)
-        .map_err(|e| AppError::Other(format!("Failed to parse metadata file: {}", e)))?;
+    let annotations = cached_annotations(app_handle, &layout).await;
+    for skin in &mut skins {
+        skin.annotation = annotations.get(&skin.id.to_string()).cloned();
+    }
 
-    Ok(metadata.skins)
+    Ok(skins)
 }
 
 
@@ -1,8 +1,9 @@
-use crate::error::{AppError, AppResult, IpcResult};
+use crate::commands::validate_champion_id;
+use crate::error::IpcResult;
+use crate::workspace::WorkspaceLayout;
 use anyhow::Context;
 use serde::Deserialize;
-use std::path::PathBuf;
-use tauri::{command, Manager};
+use tauri::command;
 use tokio::io::AsyncWriteExt;
 
 #[derive(Deserialize)]
@@ -26,21 +27,6 @@ struct Chroma {
     tile_path: String,
 }
 
-fn get_data_dir(app_handle: &tauri::AppHandle) -> AppResult<PathBuf> {
-    let settings_state = app_handle.state::<crate::state::SettingsState>();
-    let settings = settings_state
-        .0
-        .lock()
-        .map_err(|e| AppError::InternalState(e.to_string()))?;
-
-    match &settings.workspace_path {
-        Some(path) => Ok(path.clone()),
-        None => Err(AppError::Other(
-            "Workspace path not configured. Please set it in Settings.".to_string(),
-        )),
-    }
-}
-
 #[command]
 pub async fn download_champion_images(
     app_handle: tauri::AppHandle,
@@ -48,12 +34,7 @@ pub async fn download_champion_images(
 ) -> IpcResult<String> {
     match download_champion_images_inner(app_handle, champion_id).await {
         Ok(msg) => IpcResult::Ok { value: msg },
-        Err(e) => IpcResult::Err {
-            error: crate::error::AppErrorResponse::new(
-                crate::error::ErrorCode::Unknown,
-                format!("{:#}", e),
-            ),
-        },
+        Err(e) => IpcResult::Err { error: e.into() },
     }
 }
 
@@ -61,10 +42,11 @@ pub async fn download_champion_images_inner(
     app_handle: tauri::AppHandle,
     champion_id: i32,
 ) -> anyhow::Result<String> {
+    validate_champion_id(&app_handle, champion_id).await?;
+
     // Construct path to metadata.json
-    let data_dir_root = get_data_dir(&app_handle).context("Failed to get data directory")?;
-    let data_dir = data_dir_root.join("data").join(champion_id.to_string());
-    let metadata_path = data_dir.join("metadata.json");
+    let layout = WorkspaceLayout::from_app(&app_handle).context("Failed to get data directory")?;
+    let metadata_path = layout.champion_metadata_file(champion_id)?;
 
     if !metadata_path.exists() {
         return Err(anyhow::anyhow!(
@@ -80,7 +62,7 @@ pub async fn download_champion_images_inner(
     let metadata: Metadata =
         serde_json::from_str(&content).context("Failed to parse metadata.json")?;
 
-    let images_dir = data_dir.join("images");
+    let images_dir = layout.champion_images_dir(champion_id)?;
     if !images_dir.exists() {
         tokio::fs::create_dir_all(&images_dir)
             .await
@@ -172,12 +154,7 @@ pub async fn get_skin_image(
 ) -> IpcResult<String> {
     match get_skin_image_inner(app_handle, champion_id, skin_id).await {
         Ok(data) => IpcResult::Ok { value: data },
-        Err(e) => IpcResult::Err {
-            error: crate::error::AppErrorResponse::new(
-                crate::error::ErrorCode::Unknown,
-                format!("{:#}", e),
-            ),
-        },
+        Err(e) => IpcResult::Err { error: e.into() },
     }
 }
 
@@ -188,11 +165,9 @@ async fn get_skin_image_inner(
 ) -> anyhow::Result<String> {
     use base64::Engine;
 
-    let data_dir_root = get_data_dir(&app_handle).context("Failed to get data directory")?;
-    let images_dir = data_dir_root
-        .join("data")
-        .join(champion_id.to_string())
-        .join("images");
+    validate_champion_id(&app_handle, champion_id).await?;
+    let layout = WorkspaceLayout::from_app(&app_handle).context("Failed to get data directory")?;
+    let images_dir = layout.champion_images_dir(champion_id)?;
 
     // Extensions to try/use. We generally save as jpg
     let file_path = images_dir.join(format!("{}.jpg", skin_id));
@@ -205,10 +180,7 @@ async fn get_skin_image_inner(
     }
 
     // 2. If not exists, we need to find the URL from metadata
-    let metadata_path = data_dir_root
-        .join("data")
-        .join(champion_id.to_string())
-        .join("metadata.json");
+    let metadata_path = layout.champion_metadata_file(champion_id)?;
 
     if !metadata_path.exists() {
         return Err(anyhow::anyhow!(
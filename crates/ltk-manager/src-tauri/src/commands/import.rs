@@ -0,0 +1,316 @@
+//! Importing mods from other mod managers into the workspace.
+//!
+//! cslol-manager keeps every installed mod as a folder in its `installed` directory, and
+//! Fantome mods are `.fantome` zip archives. Both use the same `META/info.json` + `WAD/`
+//! layout the workspace uses, so importing copies (or hard links) the files into
+//! `data/imported/{name}` and writes a normalized `META/info.json`.
+
+use crate::commands::announcer::{collect_files, link_tree};
+use crate::commands::mod_skin::extract_archive;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::workspace::{validate_name, WorkspaceLayout};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const CSLOL_INSTALLED_DIRNAME: &str = "installed";
+const FANTOME_EXTENSIONS: [&str; 2] = ["fantome", "zip"];
+const INFO_PATH: &str = "META/info.json";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportSourceKind {
+    /// A mod folder of cslol-manager's `installed` directory.
+    CslolManager,
+    /// A `.fantome` (or `.zip`) archive.
+    Fantome,
+}
+
+/// A mod found in an import source.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCandidate {
+    /// Folder name the mod gets in the workspace, pass it to `import_mods` to select the mod.
+    pub name: String,
+    pub kind: ImportSourceKind,
+    pub source: PathBuf,
+    pub info: ImportedModInfo,
+    /// A mod with the same name is already imported.
+    pub already_imported: bool,
+}
+
+/// Contents of `META/info.json`, shared by cslol-manager, Fantome and the workspace.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ImportedModInfo {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFailure {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub imported: Vec<String>,
+    /// Mods that were already imported and left untouched.
+    pub skipped: Vec<String>,
+    pub failed: Vec<ImportFailure>,
+}
+
+/// Folders in the usual download locations that look like a cslol-manager install.
+#[tauri::command]
+pub fn detect_import_sources(app_handle: AppHandle) -> IpcResult<Vec<PathBuf>> {
+    let path = app_handle.path();
+    let roots = [path.download_dir(), path.desktop_dir(), path.document_dir()];
+
+    let mut sources = Vec::new();
+    for root in roots.into_iter().flatten() {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            let is_cslol = dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.to_lowercase().contains("cslol"));
+            if is_cslol && dir.join(CSLOL_INSTALLED_DIRNAME).is_dir() {
+                sources.push(dir);
+            }
+        }
+    }
+    sources.sort();
+
+    IpcResult::ok(sources)
+}
+
+/// List the mods of a cslol-manager folder, its `installed` directory, or a directory of
+/// Fantome archives.
+#[tauri::command]
+pub fn scan_import_source(app_handle: AppHandle, path: String) -> IpcResult<Vec<ImportCandidate>> {
+    scan_import_source_inner(&app_handle, Path::new(&path)).into()
+}
+
+fn scan_import_source_inner(
+    app_handle: &AppHandle,
+    path: &Path,
+) -> AppResult<Vec<ImportCandidate>> {
+    if !path.is_dir() {
+        return Err(AppError::InvalidPath(path.display().to_string()));
+    }
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+
+    let installed_dir = path.join(CSLOL_INSTALLED_DIRNAME);
+    let mod_root = if installed_dir.is_dir() {
+        installed_dir
+    } else {
+        path.to_path_buf()
+    };
+
+    let mut candidates = Vec::new();
+    for entry in std::fs::read_dir(&mod_root)? {
+        let source = entry?.path();
+        let kind = if source.is_dir() && source.join("WAD").is_dir() {
+            ImportSourceKind::CslolManager
+        } else if source.is_file() && has_fantome_extension(&source) {
+            ImportSourceKind::Fantome
+        } else {
+            continue;
+        };
+
+        // Folder names can contain dots, archive names drop their extension
+        let file_name = match kind {
+            ImportSourceKind::CslolManager => source.file_name(),
+            ImportSourceKind::Fantome => source.file_stem(),
+        };
+        let Some(name) = file_name
+            .and_then(|n| n.to_str())
+            .map(sanitize_name)
+            .filter(|n| validate_name(n, "mod name").is_ok())
+        else {
+            continue;
+        };
+
+        let mut info = match kind {
+            ImportSourceKind::CslolManager => read_folder_info(&source),
+            ImportSourceKind::Fantome => read_archive_info(&source),
+        }
+        .unwrap_or_default();
+        if info.name.trim().is_empty() {
+            info.name = name.clone();
+        }
+
+        candidates.push(ImportCandidate {
+            already_imported: layout.imported_mod_dir(&name)?.exists(),
+            name,
+            kind,
+            source,
+            info,
+        });
+    }
+    candidates.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(candidates)
+}
+
+/// Import the mods `names` from `path` (all of them if empty).
+///
+/// With `link`, the files of cslol-manager folders are hard linked instead of copied, so the
+/// import takes no extra disk space. Fantome archives are always extracted.
+#[tauri::command]
+pub async fn import_mods(
+    app_handle: AppHandle,
+    path: String,
+    names: Vec<String>,
+    link: bool,
+) -> IpcResult<ImportReport> {
+    import_mods_inner(app_handle, path, names, link)
+        .await
+        .into()
+}
+
+async fn import_mods_inner(
+    app_handle: AppHandle,
+    path: String,
+    names: Vec<String>,
+    link: bool,
+) -> AppResult<ImportReport> {
+    tokio::task::spawn_blocking(move || {
+        import_mods_blocking(&app_handle, Path::new(&path), &names, link)
+    })
+    .await
+    .map_err(|e| AppError::Other(format!("Import task failed: {}", e)))?
+}
+
+fn import_mods_blocking(
+    app_handle: &AppHandle,
+    path: &Path,
+    names: &[String],
+    link: bool,
+) -> AppResult<ImportReport> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let candidates = scan_import_source_inner(app_handle, path)?;
+
+    let mut report = ImportReport::default();
+    for candidate in candidates {
+        if !names.is_empty() && !names.contains(&candidate.name) {
+            continue;
+        }
+        if candidate.already_imported {
+            report.skipped.push(candidate.name);
+            continue;
+        }
+
+        let target = layout.imported_mod_dir(&candidate.name)?;
+        match import_candidate(&candidate, &target, link) {
+            Ok(()) => {
+                tracing::info!("Imported {} from {:?}", candidate.name, candidate.source);
+                report.imported.push(candidate.name);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to import {}: {:#}", candidate.name, e);
+                // Don't leave a half imported mod that looks installed
+                let _ = std::fs::remove_dir_all(&target);
+                report.failed.push(ImportFailure {
+                    name: candidate.name,
+                    error: format!("{:#}", e),
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn import_candidate(candidate: &ImportCandidate, target: &Path, link: bool) -> anyhow::Result<()> {
+    match candidate.kind {
+        ImportSourceKind::CslolManager if link => link_tree(&candidate.source, target, &|file| {
+            is_info_file(&candidate.source, file)
+        })?,
+        ImportSourceKind::CslolManager => copy_tree(&candidate.source, target)?,
+        ImportSourceKind::Fantome => extract_archive(&candidate.source, target)?,
+    }
+
+    // Written fresh rather than linked, so editing it doesn't touch the other manager's copy
+    let info_path = target.join(INFO_PATH);
+    if info_path.exists() {
+        std::fs::remove_file(&info_path)?;
+    }
+    if let Some(parent) = info_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&info_path, serde_json::to_string_pretty(&candidate.info)?)?;
+
+    Ok(())
+}
+
+pub(crate) fn copy_tree(source: &Path, target: &Path) -> std::io::Result<()> {
+    let mut files = Vec::new();
+    collect_files(source, &mut files)?;
+
+    for file in files {
+        let destination = target.join(file.strip_prefix(source).unwrap_or(&file));
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(&file, &destination)?;
+    }
+
+    Ok(())
+}
+
+fn is_info_file(mod_dir: &Path, file: &Path) -> bool {
+    file == mod_dir.join(INFO_PATH)
+}
+
+fn has_fantome_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| FANTOME_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Replace characters that can't appear in a workspace folder name.
+pub(crate) fn sanitize_name(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect();
+    name.trim_start_matches('.').to_string()
+}
+
+fn read_folder_info(mod_dir: &Path) -> Option<ImportedModInfo> {
+    let content = std::fs::read_to_string(mod_dir.join(INFO_PATH)).ok()?;
+    parse_info(&content)
+}
+
+fn read_archive_info(archive_path: &Path) -> Option<ImportedModInfo> {
+    let file = std::fs::File::open(archive_path).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    let mut entry = archive.by_name(INFO_PATH).ok()?;
+
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    parse_info(&content)
+}
+
+fn parse_info(content: &str) -> Option<ImportedModInfo> {
+    // Some tools write a UTF-8 BOM
+    serde_json::from_str(content.trim_start_matches('\u{feff}')).ok()
+}
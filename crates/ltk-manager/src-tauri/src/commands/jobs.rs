@@ -0,0 +1,42 @@
+use crate::error::IpcResult;
+use crate::jobs::{Job, JobId, JobRegistry, JobResume};
+use tauri::{AppHandle, State};
+
+/// List running jobs and the most recently finished ones, oldest first.
+#[tauri::command]
+pub fn list_jobs(jobs: State<JobRegistry>) -> IpcResult<Vec<Job>> {
+    IpcResult::ok(jobs.list())
+}
+
+#[tauri::command]
+pub fn get_job(jobs: State<JobRegistry>, id: JobId) -> IpcResult<Option<Job>> {
+    IpcResult::ok(jobs.get(id))
+}
+
+/// Request cancellation of a job. Returns false if the job isn't running anymore.
+#[tauri::command]
+pub fn cancel_job(jobs: State<JobRegistry>, id: JobId) -> IpcResult<bool> {
+    IpcResult::ok(jobs.cancel(id))
+}
+
+/// Restart a download that was interrupted by an app exit.
+pub async fn resume_job(app_handle: AppHandle, resume: JobResume) {
+    tracing::info!("Resuming interrupted job {:?}", resume);
+
+    let result = match resume {
+        JobResume::Skin {
+            champion_id,
+            skin_id,
+        } => crate::commands::mod_skin::download_skin(app_handle, champion_id, skin_id).await,
+        JobResume::Cosmetic { category, item_id } => {
+            crate::commands::download_ward_skin(app_handle, category, item_id).await
+        }
+        JobResume::BrowserMod { mod_id } => {
+            crate::commands::download_browser_mod(app_handle, mod_id).await
+        }
+    };
+
+    if let IpcResult::Err { error } = result {
+        tracing::warn!("Resumed job failed: {}", error.message);
+    }
+}
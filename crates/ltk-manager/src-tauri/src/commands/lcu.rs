@@ -0,0 +1,47 @@
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::lcu::{LcuClient, LcuCredentials};
+use crate::state::SettingsState;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentChampion {
+    pub champion_id: i32,
+    /// Whether the champion is locked in rather than hovered.
+    pub locked: bool,
+}
+
+/// Champion the player has picked in the current champion select.
+///
+/// Returns `None` when the client isn't running or no champion select is in progress.
+#[tauri::command]
+pub async fn get_current_champion(app_handle: AppHandle) -> IpcResult<Option<CurrentChampion>> {
+    get_current_champion_inner(&app_handle).await.into()
+}
+
+async fn get_current_champion_inner(app_handle: &AppHandle) -> AppResult<Option<CurrentChampion>> {
+    let league_path = app_handle
+        .state::<SettingsState>()
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?
+        .league_path
+        .clone()
+        .ok_or(AppError::LeagueNotFound)?;
+
+    let Some(credentials) = LcuCredentials::from_install_dir(&league_path) else {
+        return Ok(None);
+    };
+    let Some(session) = LcuClient::new(credentials)?.champ_select_session().await? else {
+        return Ok(None);
+    };
+
+    let locked = session.locked_champion();
+    Ok(session
+        .current_champion()
+        .map(|champion_id| CurrentChampion {
+            champion_id,
+            locked: locked == Some(champion_id),
+        }))
+}
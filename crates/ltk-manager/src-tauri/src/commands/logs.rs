@@ -0,0 +1,15 @@
+use crate::error::IpcResult;
+use crate::logs::{log_file_path, set_stream_level, LogLevel};
+
+/// Stream backend logs at `level` and above as `log` app events, or stop with `null`.
+#[tauri::command]
+pub fn subscribe_logs(level: Option<LogLevel>) -> IpcResult<()> {
+    set_stream_level(level);
+    IpcResult::ok(())
+}
+
+/// Path of the backend log file, `None` if it couldn't be created.
+#[tauri::command]
+pub fn get_log_file_path() -> IpcResult<Option<String>> {
+    IpcResult::ok(log_file_path().map(|path| path.display().to_string()))
+}
@@ -1,8 +1,8 @@
 use crate::error::{AppError, AppResult, IpcResult};
+use crate::workspace::WorkspaceLayout;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 use tokio::fs;
 
 // ============================================================================
@@ -34,6 +34,10 @@ pub struct RawSkin {
     #[serde(default)]
     pub rarity: Option<String>,
     #[serde(default)]
+    pub is_legacy: bool,
+    #[serde(default)]
+    pub availability: Option<String>,
+    #[serde(default)]
     pub chromas: Option<Vec<RawChroma>>,
 }
 
@@ -68,6 +72,10 @@ pub struct PrunedSkin {
     pub skin_classification: Option<String>,
     pub tile_path: Option<String>,
     pub rarity: Option<String>,
+    /// Vaulted skins that are only sold during special events.
+    pub is_legacy: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub availability: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub chromas: Option<Vec<PrunedChroma>>,
 }
@@ -84,24 +92,9 @@ pub struct PrunedChroma {
 // HELPER FUNCTIONS
 // ============================================================================
 
-fn get_data_dir(app_handle: &AppHandle) -> AppResult<PathBuf> {
-    let settings_state = app_handle.state::<crate::state::SettingsState>();
-    let settings = settings_state
-        .0
-        .lock()
-        .map_err(|e| AppError::InternalState(e.to_string()))?;
-
-    match &settings.workspace_path {
-        Some(path) => Ok(path.clone()),
-        None => Err(AppError::Other(
-            "Workspace path not configured. Please set it in Settings.".to_string(),
-        )),
-    }
-}
-
 /// Load champions_with_skins.json and extract all valid skin IDs
-async fn load_valid_skin_ids(data_dir: &PathBuf) -> AppResult<HashMap<i32, HashSet<i32>>> {
-    let file_path = data_dir.join("champions_with_skins.json");
+async fn load_valid_skin_ids(layout: &WorkspaceLayout) -> AppResult<HashMap<i32, HashSet<i32>>> {
+    let file_path = layout.champions_file();
 
     if !file_path.exists() {
         return Err(AppError::Other(
@@ -179,6 +172,8 @@ pub fn prune_metadata(raw: RawMetadata, valid_skin_ids: &HashSet<i32>) -> Pruned
                 skin_classification: skin.skin_classification,
                 tile_path: transform_tile_path(skin.tile_path),
                 rarity: skin.rarity,
+                is_legacy: skin.is_legacy,
+                availability: skin.availability,
                 chromas,
             }
         })
@@ -213,10 +208,10 @@ pub async fn prune_all_metadata(app_handle: AppHandle) -> IpcResult<PruneResult>
 async fn prune_all_metadata_inner(app_handle: &AppHandle) -> AppResult<PruneResult> {
     tracing::info!("Starting metadata pruning...");
 
-    let data_dir = get_data_dir(app_handle)?;
-    let valid_skin_ids_map = load_valid_skin_ids(&data_dir).await?;
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let valid_skin_ids_map = load_valid_skin_ids(&layout).await?;
 
-    let metadata_base_dir = data_dir.join("data");
+    let metadata_base_dir = layout.data_dir();
     if !metadata_base_dir.exists() {
         return Ok(PruneResult {
             success: true,
@@ -312,6 +307,8 @@ async fn prune_all_metadata_inner(app_handle: &AppHandle) -> AppResult<PruneResu
         );
     }
 
+    crate::cache::invalidate(app_handle);
+
     Ok(PruneResult {
         success: true,
         message: format!(
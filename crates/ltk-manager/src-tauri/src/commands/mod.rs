@@ -1,14 +1,70 @@
+mod annotations;
+mod announcer;
 mod app;
+mod app_update;
+mod backup;
+mod bisect;
+mod browser;
+mod champion_info;
+mod compat;
+mod cslol_export;
 mod data;
 mod images;
+mod import;
+mod jobs;
+mod lcu;
+mod logs;
 mod merge_data;
+mod mod_list;
 pub mod mod_skin;
+mod patch_history;
 mod patcher;
+mod peer_sync;
+mod plugins;
+mod quick_switch;
+mod randomizer;
+mod rollback;
+mod self_test;
 mod settings;
+mod skin_bindings;
+mod skin_catalog;
+mod tft;
+mod tools;
+mod verify;
+mod ward_skin;
+mod watchdog;
 
+pub use annotations::*;
+pub use announcer::*;
 pub use app::*;
+pub use app_update::*;
+pub use backup::*;
+pub use bisect::*;
+pub use browser::*;
+pub use champion_info::*;
+pub use compat::*;
+pub use cslol_export::*;
 pub use data::*;
 pub use images::*;
+pub use import::*;
+pub use jobs::*;
+pub use lcu::*;
+pub use logs::*;
 pub use merge_data::*;
+pub use mod_list::*;
+pub use patch_history::*;
 pub use patcher::*;
+pub use peer_sync::*;
+pub use plugins::*;
+pub use quick_switch::*;
+pub use randomizer::*;
+pub use rollback::*;
+pub use self_test::*;
 pub use settings::*;
+pub use skin_bindings::*;
+pub use skin_catalog::*;
+pub use tft::*;
+pub use tools::*;
+pub use verify::*;
+pub use ward_skin::*;
+pub use watchdog::*;
@@ -0,0 +1,440 @@
+use crate::commands::data::{get_skin_database_inner, load_saved_version};
+use crate::commands::ward_skin::{load_cosmetics, CosmeticCategory};
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::jobs::{run_job, JobHandle, JobKind};
+use crate::workspace::WorkspaceLayout;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const MOD_LIST_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ModListFormat {
+    /// Plain text meant to be pasted into bug reports and chats.
+    Text,
+    /// A manifest that can be passed back to `apply_mod_list`.
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ModListKind {
+    Skin,
+    WardSkin,
+    Emote,
+    Browser,
+    Announcer,
+    Imported,
+}
+
+impl ModListKind {
+    fn label(self) -> &'static str {
+        match self {
+            ModListKind::Skin => "Skins",
+            ModListKind::WardSkin => "Ward skins",
+            ModListKind::Emote => "Emotes",
+            ModListKind::Browser => "Browser mods",
+            ModListKind::Announcer => "Announcer packs",
+            ModListKind::Imported => "Imported mods",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModListEntry {
+    pub kind: ModListKind,
+    /// Skin id, item id, index id or announcer pack name depending on `kind`.
+    pub id: String,
+    #[serde(default)]
+    pub champion_id: Option<i32>,
+    #[serde(default)]
+    pub name: String,
+    /// Version from the mod's `META/info.json`, if it has one.
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModListManifest {
+    pub format_version: u32,
+    #[serde(default)]
+    pub generated_at: String,
+    #[serde(default)]
+    pub game_version: Option<String>,
+    #[serde(default)]
+    pub active_announcer: Option<String>,
+    pub mods: Vec<ModListEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModListFailure {
+    pub entry: ModListEntry,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyModListReport {
+    pub installed: Vec<ModListEntry>,
+    pub already_installed: usize,
+    pub failed: Vec<ModListFailure>,
+    /// Announcer pack that was made active, a missing pack is reported in `failed`.
+    pub active_announcer: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ModInfo {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+fn read_mod_info(mod_dir: &Path) -> Option<ModInfo> {
+    let content = std::fs::read_to_string(mod_dir.join("META").join("info.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// List the sub directories of `dir` with their names, sorted by name.
+///
+/// Unfinished extractions (`*.partial`) are skipped.
+fn list_subdirs(dir: &Path) -> AppResult<Vec<(String, PathBuf)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if let Some(name) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .filter(|n| !n.ends_with(".partial"))
+            {
+                dirs.push((name.to_string(), path.clone()));
+            }
+        }
+    }
+    dirs.sort();
+
+    Ok(dirs)
+}
+
+/// Directory an entry is installed to, `None` if the entry is malformed.
+pub(crate) fn entry_dir(layout: &WorkspaceLayout, entry: &ModListEntry) -> Option<PathBuf> {
+    let dir = match entry.kind {
+        ModListKind::Skin => {
+            let skin_id: i32 = entry.id.parse().ok()?;
+            let champion_id = entry.champion_id.unwrap_or(skin_id / 1000);
+            layout.skin_dir(champion_id, skin_id)
+        }
+        ModListKind::WardSkin => {
+            layout.cosmetic_mod_dir(CosmeticCategory::WardSkin, entry.id.parse().ok()?)
+        }
+        ModListKind::Emote => {
+            layout.cosmetic_mod_dir(CosmeticCategory::Emote, entry.id.parse().ok()?)
+        }
+        ModListKind::Browser => layout.browser_mod_dir(&entry.id),
+        ModListKind::Announcer => layout.announcer_dir(&entry.id),
+        ModListKind::Imported => layout.imported_mod_dir(&entry.id),
+    };
+    dir.ok()
+}
+
+pub(crate) async fn collect_mod_list(app_handle: &AppHandle) -> AppResult<ModListManifest> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let skin_names = get_skin_database_inner(app_handle).await?;
+
+    let mut cosmetic_names: HashMap<(ModListKind, i32), String> = HashMap::new();
+    for (kind, category) in [
+        (ModListKind::WardSkin, CosmeticCategory::WardSkin),
+        (ModListKind::Emote, CosmeticCategory::Emote),
+    ] {
+        for item in load_cosmetics(app_handle, category).await? {
+            cosmetic_names.insert((kind, item.id), item.name);
+        }
+    }
+
+    let mut mods = Vec::new();
+    let mut push = |kind: ModListKind,
+                    id: String,
+                    champion_id: Option<i32>,
+                    dir: &Path,
+                    fallback: Option<&String>| {
+        let info = read_mod_info(dir);
+        let name = info
+            .as_ref()
+            .and_then(|i| i.name.clone())
+            .filter(|n| !n.is_empty())
+            .or_else(|| fallback.cloned())
+            .unwrap_or_else(|| id.clone());
+        mods.push(ModListEntry {
+            kind,
+            id,
+            champion_id,
+            name,
+            version: info.and_then(|i| i.version),
+        });
+    };
+
+    // Skins live in data/{champion_id}/{skin_id}
+    for (champion, champion_dir) in list_subdirs(&layout.data_dir())? {
+        let Ok(champion_id) = champion.parse::<i32>() else {
+            continue;
+        };
+        for (skin, skin_dir) in list_subdirs(&champion_dir)? {
+            if skin.parse::<i32>().is_ok() {
+                let fallback = skin_names.get(&skin);
+                push(
+                    ModListKind::Skin,
+                    skin,
+                    Some(champion_id),
+                    &skin_dir,
+                    fallback,
+                );
+            }
+        }
+    }
+
+    for (kind, category) in [
+        (ModListKind::WardSkin, CosmeticCategory::WardSkin),
+        (ModListKind::Emote, CosmeticCategory::Emote),
+    ] {
+        for (id, dir) in list_subdirs(&layout.cosmetic_dir(category))? {
+            let Ok(item_id) = id.parse::<i32>() else {
+                continue;
+            };
+            let fallback = cosmetic_names.get(&(kind, item_id));
+            push(kind, id, None, &dir, fallback);
+        }
+    }
+
+    for (id, dir) in list_subdirs(&layout.browser_mods_dir())? {
+        push(ModListKind::Browser, id, None, &dir, None);
+    }
+
+    for (id, dir) in list_subdirs(&layout.announcers_dir())? {
+        push(ModListKind::Announcer, id, None, &dir, None);
+    }
+
+    for (id, dir) in list_subdirs(&layout.imported_dir())? {
+        push(ModListKind::Imported, id, None, &dir, None);
+    }
+
+    let active_announcer = {
+        let settings_state = app_handle.state::<crate::state::SettingsState>();
+        let settings = settings_state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        settings.active_announcer.clone()
+    };
+
+    Ok(ModListManifest {
+        format_version: MOD_LIST_FORMAT_VERSION,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        game_version: load_saved_version(app_handle).await?.map(|v| v.version),
+        active_announcer,
+        mods,
+    })
+}
+
+fn format_mod_list_text(manifest: &ModListManifest) -> String {
+    let mut text = String::new();
+    let _ = writeln!(text, "LTK Manager mod list ({})", manifest.generated_at);
+    let _ = writeln!(
+        text,
+        "Game version: {}",
+        manifest.game_version.as_deref().unwrap_or("unknown")
+    );
+    if let Some(announcer) = &manifest.active_announcer {
+        let _ = writeln!(text, "Active announcer: {}", announcer);
+    }
+
+    let mut entries: Vec<&ModListEntry> = manifest.mods.iter().collect();
+    entries.sort_by_key(|e| e.kind);
+
+    let mut current_kind = None;
+    for entry in entries {
+        if current_kind != Some(entry.kind) {
+            current_kind = Some(entry.kind);
+            let count = manifest
+                .mods
+                .iter()
+                .filter(|e| e.kind == entry.kind)
+                .count();
+            let _ = writeln!(text, "\n{} ({})", entry.kind.label(), count);
+        }
+
+        let _ = write!(text, "  - {} [{}]", entry.name, entry.id);
+        if let Some(version) = &entry.version {
+            let _ = write!(text, " v{}", version);
+        }
+        text.push('\n');
+    }
+
+    if manifest.mods.is_empty() {
+        text.push_str("\nNo mods installed\n");
+    }
+
+    text
+}
+
+/// Export the installed mods as readable text or as a JSON manifest for `apply_mod_list`.
+#[tauri::command]
+pub async fn export_mod_list(app_handle: AppHandle, format: ModListFormat) -> IpcResult<String> {
+    export_mod_list_inner(&app_handle, format).await.into()
+}
+
+async fn export_mod_list_inner(app_handle: &AppHandle, format: ModListFormat) -> AppResult<String> {
+    let manifest = collect_mod_list(app_handle).await?;
+
+    match format {
+        ModListFormat::Text => Ok(format_mod_list_text(&manifest)),
+        ModListFormat::Json => Ok(serde_json::to_string_pretty(&manifest)?),
+    }
+}
+
+/// Download every mod of the manifest that is not installed yet.
+///
+/// Announcer packs and imported mods come from local files and are reported as failures when
+/// missing. The manifest's active announcer pack is selected if it is imported.
+#[tauri::command]
+pub async fn apply_mod_list(
+    app_handle: AppHandle,
+    manifest: ModListManifest,
+) -> IpcResult<ApplyModListReport> {
+    install_mod_list(&app_handle, manifest).await.into()
+}
+
+pub(crate) async fn install_mod_list(
+    app_handle: &AppHandle,
+    manifest: ModListManifest,
+) -> AppResult<ApplyModListReport> {
+    run_job(app_handle, JobKind::Download, "Install mod list", |job| {
+        apply_mod_list_inner(app_handle, manifest, job)
+    })
+    .await
+}
+
+async fn apply_mod_list_inner(
+    app_handle: &AppHandle,
+    manifest: ModListManifest,
+    job: JobHandle,
+) -> AppResult<ApplyModListReport> {
+    if manifest.format_version > MOD_LIST_FORMAT_VERSION {
+        return Err(AppError::ValidationFailed(format!(
+            "Unsupported mod list format version {}",
+            manifest.format_version
+        )));
+    }
+
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let mut report = ApplyModListReport::default();
+    let total = manifest.mods.len();
+
+    for (index, entry) in manifest.mods.into_iter().enumerate() {
+        job.progress(index, total, format!("Installing {}", entry.name));
+
+        let Some(dir) = entry_dir(&layout, &entry) else {
+            report.failed.push(ModListFailure {
+                error: format!("Invalid id {:?}", entry.id),
+                entry,
+            });
+            continue;
+        };
+
+        if dir.exists() {
+            report.already_installed += 1;
+            continue;
+        }
+
+        let result = match entry.kind {
+            ModListKind::Skin => {
+                let skin_id: i32 = entry.id.parse().unwrap_or_default();
+                let champion_id = entry.champion_id.unwrap_or(skin_id / 1000);
+                crate::commands::mod_skin::download_skin_inner(
+                    app_handle.clone(),
+                    champion_id,
+                    skin_id,
+                )
+                .await
+            }
+            ModListKind::WardSkin | ModListKind::Emote => {
+                let category = if entry.kind == ModListKind::WardSkin {
+                    CosmeticCategory::WardSkin
+                } else {
+                    CosmeticCategory::Emote
+                };
+                crate::commands::ward_skin::download_ward_skin_inner(
+                    app_handle.clone(),
+                    category,
+                    entry.id.parse().unwrap_or_default(),
+                )
+                .await
+            }
+            ModListKind::Browser => {
+                crate::commands::browser::download_browser_mod_inner(
+                    app_handle.clone(),
+                    entry.id.clone(),
+                )
+                .await
+            }
+            ModListKind::Announcer => Err(anyhow::anyhow!(
+                "Announcer packs can't be downloaded, import the pack manually"
+            )),
+            ModListKind::Imported => Err(anyhow::anyhow!(
+                "Imported mods can't be downloaded, import the mod again"
+            )),
+        };
+
+        match result {
+            Ok(_) => report.installed.push(entry),
+            Err(e) => {
+                tracing::warn!("Failed to install {:?} {}: {:#}", entry.kind, entry.id, e);
+                report.failed.push(ModListFailure {
+                    error: format!("{:#}", e),
+                    entry,
+                });
+            }
+        }
+    }
+
+    if let Some(name) = manifest.active_announcer {
+        match crate::commands::announcer::set_active_announcer_inner(app_handle, Some(name.clone()))
+        {
+            Ok(()) => report.active_announcer = Some(name),
+            Err(e) => {
+                tracing::warn!("Failed to select announcer pack {}: {:?}", name, e);
+                let error = match e {
+                    AppError::ModNotFound(_) => {
+                        "Active announcer pack isn't imported, import it and select it manually"
+                            .to_string()
+                    }
+                    e => e.to_string(),
+                };
+                report.failed.push(ModListFailure {
+                    entry: ModListEntry {
+                        kind: ModListKind::Announcer,
+                        id: name.clone(),
+                        champion_id: None,
+                        name,
+                        version: None,
+                    },
+                    error,
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
@@ -1,48 +1,54 @@
 use crate::{
-    error::{AppError, AppResult, IpcResult},
+    commands::{compat_warnings, validate_skin_id},
+    error::IpcResult,
+    events::{emit, AppEvent, EventSource},
+    jobs::{run_job_with_token, run_resumable_job, JobKind, JobResume},
     patcher::PatcherState,
+    tools::{resolve_tool, Tool},
+    workspace::WorkspaceLayout,
 };
 use anyhow::Context;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use tauri::{command, Manager};
 use tokio::io::AsyncWriteExt;
 use tracing::{error, info, warn};
 
-#[cfg(target_os = "windows")]
-
-fn get_data_dir(app_handle: &tauri::AppHandle) -> AppResult<PathBuf> {
-    let settings_state = app_handle.state::<crate::state::SettingsState>();
-    let settings = settings_state
-        .0
-        .lock()
-        .map_err(|e| AppError::InternalState(e.to_string()))?;
-
-    match &settings.workspace_path {
-        Some(path) => Ok(path.clone()),
-        None => Err(AppError::Other(
-            "Workspace path not configured. Please set it in Settings.".to_string(),
-        )),
-    }
-}
-
 #[command]
 pub async fn download_skin(
     app_handle: tauri::AppHandle,
     champion_id: i32,
     skin_id: i32,
 ) -> IpcResult<String> {
-    match download_skin_inner(app_handle, champion_id, skin_id).await {
-        Ok(msg) => IpcResult::Ok { value: msg },
-        Err(e) => IpcResult::Err {
-            error: crate::error::AppErrorResponse::new(
-                crate::error::ErrorCode::Unknown,
-                format!("{:#}", e),
-            ),
+    let download = run_resumable_job(
+        &app_handle,
+        JobKind::Download,
+        format!("Download skin {}", skin_id),
+        JobResume::Skin {
+            champion_id,
+            skin_id,
         },
+        |_| download_skin_inner(app_handle.clone(), champion_id, skin_id),
+    );
+    match download.await {
+        Ok(msg) => {
+            emit(
+                &app_handle,
+                AppEvent::completed(EventSource::Download, &msg),
+            );
+            IpcResult::Ok { value: msg }
+        }
+        Err(e) => {
+            emit(
+                &app_handle,
+                AppEvent::error(EventSource::Download, format!("{:#}", e)),
+            );
+            IpcResult::Err { error: e.into() }
+        }
     }
 }
 
-async fn download_skin_inner(
+pub(crate) async fn download_skin_inner(
     app_handle: tauri::AppHandle,
     champion_id: i32,
     skin_id: i32,
@@ -51,8 +57,9 @@ async fn download_skin_inner(
         "Starting download_skin_inner: champion_id={}, skin_id={}",
         champion_id, skin_id
     );
-    let data_dir_root = get_data_dir(&app_handle).context("Failed to get data directory")?;
-    let champion_dir = data_dir_root.join("data").join(champion_id.to_string());
+    validate_skin_id(&app_handle, champion_id, skin_id).await?;
+    let layout = WorkspaceLayout::from_app(&app_handle).context("Failed to get data directory")?;
+    let champion_dir = layout.champion_dir(champion_id)?;
 
     if !champion_dir.exists() {
         tokio::fs::create_dir_all(&champion_dir)
@@ -60,7 +67,7 @@ async fn download_skin_inner(
             .context("Failed to create champion directory")?;
     }
 
-    let extract_to = champion_dir.join(skin_id.to_string());
+    let extract_to = layout.skin_dir(champion_id, skin_id)?;
     if extract_to.exists() {
         info!("Skin {} already downloaded at {:?}", skin_id, extract_to);
         return Ok(format!(
@@ -68,44 +75,74 @@ async fn download_skin_inner(
             skin_id, extract_to
         ));
     }
+    let urls: Vec<String> = ["zip", "fantome"]
+        .iter()
+        .map(|ext| {
+            format!(
+                "https://github.com/Alban1911/LeagueSkins/raw/main/skins/{}/{}/{}.{}",
+                champion_id, skin_id, skin_id, ext
+            )
+        })
+        .collect();
+
+    download_and_extract(&urls, &champion_dir, &extract_to)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to download skin (checked zip and fantome) for champion {} skin {}",
+                champion_id, skin_id
+            )
+        })?;
+
+    Ok(format!(
+        "Downloaded and extracted skin {} to {:?}",
+        skin_id, extract_to
+    ))
+}
+
+/// Download the first reachable archive from `urls` into `archive_dir` and extract it to `extract_to`.
+///
+/// The archive is extracted into a `.partial` sibling first and only moved to `extract_to` once
+/// complete, so an interrupted download never looks installed. The archive is removed after
+/// extraction. Returns the URL that worked.
+pub(crate) async fn download_and_extract(
+    urls: &[String],
+    archive_dir: &Path,
+    extract_to: &Path,
+) -> anyhow::Result<String> {
     let client = reqwest::Client::new();
-    let extensions = ["zip", "fantome"];
     let mut final_response = None;
     let mut file_path = PathBuf::new();
     let mut worked_url = String::new();
 
-    for ext in extensions {
-        let url = format!(
-            "https://github.com/Alban1911/LeagueSkins/raw/main/skins/{}/{}/{}.{}",
-            champion_id, skin_id, skin_id, ext
-        );
+    for url in urls {
         info!("Checking URL: {}", url);
 
         // We use a match to safely handle potential network errors on a per-attempt basis if needed,
         // but here we primarily care about the status code.
-        match client.get(&url).send().await {
+        match client.get(url).send().await {
             Ok(res) => {
                 if res.status().is_success() {
                     final_response = Some(res);
-                    file_path = champion_dir.join(format!("{}.{}", skin_id, ext));
-                    worked_url = url;
+                    let file_name = url
+                        .rsplit('/')
+                        .next()
+                        .and_then(|name| name.split(['?', '#']).next())
+                        .filter(|name| !name.is_empty())
+                        .unwrap_or("download.zip");
+                    file_path = archive_dir.join(file_name);
+                    worked_url = url.clone();
                     break;
                 }
             }
             Err(e) => {
                 warn!("Failed to request {}: {}", url, e);
-                // Continue to try the next extension
+                // Continue to try the next URL
             }
         }
     }
 
-    let response = final_response.ok_or_else(|| {
-        anyhow::anyhow!(
-            "Failed to download skin (checked zip and fantome) for champion {} skin {}",
-            champion_id,
-            skin_id
-        )
-    })?;
+    let response = final_response.ok_or_else(|| anyhow::anyhow!("No download URL succeeded"))?;
 
     info!(
         "Download connection established: {}, status: {}",
@@ -122,19 +159,31 @@ async fn download_skin_inner(
         bytes.len()
     );
 
-    // Unzip logic
-    let file = std::fs::File::open(&file_path)?;
+    extract_archive(&file_path, extract_to)?;
+
+    // Cleanup zip file
+    tokio::fs::remove_file(&file_path).await?;
+    info!("Extraction complete. Removed zip file.");
+
+    Ok(worked_url)
+}
+
+/// Extract the zip archive at `archive_path` to `extract_to`, through a `.partial` sibling.
+pub(crate) fn extract_archive(archive_path: &Path, extract_to: &Path) -> anyhow::Result<()> {
+    let file = std::fs::File::open(archive_path)?;
     let mut archive = zip::ZipArchive::new(file)?;
 
-    // let extract_to = champion_dir.join(skin_id.to_string()); // Moved up
-    if !extract_to.exists() {
-        std::fs::create_dir_all(&extract_to)?;
+    // Leftover of an interrupted extraction
+    let partial_dir = partial_extract_dir(extract_to);
+    if partial_dir.exists() {
+        std::fs::remove_dir_all(&partial_dir)?;
     }
+    std::fs::create_dir_all(&partial_dir)?;
 
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)?;
         let outpath = match file.enclosed_name() {
-            Some(path) => extract_to.join(path),
+            Some(path) => partial_dir.join(path),
             None => continue,
         };
 
@@ -151,14 +200,19 @@ async fn download_skin_inner(
         }
     }
 
-    // Cleanup zip file
-    tokio::fs::remove_file(&file_path).await?;
-    info!("Extraction complete. Removed zip file.");
+    std::fs::rename(&partial_dir, extract_to)
+        .with_context(|| format!("Failed to move extracted files to {:?}", extract_to))?;
+    Ok(())
+}
 
-    Ok(format!(
-        "Downloaded and extracted skin {} to {:?}",
-        skin_id, extract_to
-    ))
+/// Directory an archive is extracted to before it is moved to `extract_to`.
+fn partial_extract_dir(extract_to: &Path) -> PathBuf {
+    let mut name = extract_to
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    name.push(".partial");
+    extract_to.with_file_name(name)
 }
 
 #[command]
@@ -167,18 +221,46 @@ pub async fn run_skin(
     champion_id: i32,
     skin_id: i32,
 ) -> IpcResult<String> {
+    let cancel_token = match begin_overlay_run(&app_handle).await {
+        Ok(token) => token,
+        Err(error) => return IpcResult::Err { error },
+    };
+
+    let run = run_job_with_token(
+        &app_handle,
+        JobKind::Overlay,
+        format!("Run skin {}", skin_id),
+        None,
+        cancel_token.clone(),
+        |_| run_skin_inner(app_handle.clone(), champion_id, skin_id, cancel_token),
+    );
+    match run.await {
+        Ok(msg) => IpcResult::Ok { value: msg },
+        Err(e) => {
+            error!("run_skin error: {:#}", e);
+            emit(
+                &app_handle,
+                AppEvent::error(EventSource::Overlay, format!("{:#}", e)),
+            );
+            IpcResult::Err { error: e.into() }
+        }
+    }
+}
+
+/// Stop any running overlay and register a fresh cancellation token for the next one.
+pub(crate) async fn begin_overlay_run(
+    app_handle: &tauri::AppHandle,
+) -> Result<tokio_util::sync::CancellationToken, crate::error::AppErrorResponse> {
     {
         let child_process_to_kill = {
             let patcher_state_arc = app_handle.state::<PatcherState>();
             let mut patcher_state = match patcher_state_arc.0.lock() {
                 Ok(state) => state,
                 Err(e) => {
-                    return IpcResult::Err {
-                        error: crate::error::AppErrorResponse::new(
-                            crate::error::ErrorCode::Unknown,
-                            format!("Failed to lock patcher state: {}", e),
-                        ),
-                    };
+                    return Err(crate::error::AppErrorResponse::new(
+                        crate::error::ErrorCode::Unknown,
+                        format!("Failed to lock patcher state: {}", e),
+                    ));
                 }
             };
 
@@ -206,29 +288,16 @@ pub async fn run_skin(
         let mut patcher_state = match patcher_state_arc.0.lock() {
             Ok(state) => state,
             Err(e) => {
-                return IpcResult::Err {
-                    error: crate::error::AppErrorResponse::new(
-                        crate::error::ErrorCode::Unknown,
-                        format!("Failed to lock patcher state: {}", e),
-                    ),
-                };
+                return Err(crate::error::AppErrorResponse::new(
+                    crate::error::ErrorCode::Unknown,
+                    format!("Failed to lock patcher state: {}", e),
+                ));
             }
         };
         patcher_state.cancel_token = Some(cancel_token.clone());
     }
 
-    match run_skin_inner(app_handle, champion_id, skin_id, cancel_token).await {
-        Ok(msg) => IpcResult::Ok { value: msg },
-        Err(e) => {
-            error!("run_skin error: {:#}", e);
-            IpcResult::Err {
-                error: crate::error::AppErrorResponse::new(
-                    crate::error::ErrorCode::Unknown,
-                    format!("{:#}", e),
-                ),
-            }
-        }
-    }
+    Ok(cancel_token)
 }
 
 #[command]
@@ -272,70 +341,145 @@ pub async fn stop_all_mods(app_handle: tauri::AppHandle) -> IpcResult<String> {
     }
 }
 
-fn resolve_tool_path(app_handle: &tauri::AppHandle, tool_name: &str) -> anyhow::Result<PathBuf> {
-    let resource_path = app_handle
-        .path()
-        .resource_dir()
-        .context("Failed to get resource directory")?
-        .join(tool_name);
+async fn run_skin_inner(
+    app_handle: tauri::AppHandle,
+    champion_id: i32,
+    skin_id: i32,
+    cancel_token: tokio_util::sync::CancellationToken,
+) -> anyhow::Result<String> {
+    info!(
+        "Starting run_skin_inner: champion_id={}, skin_id={}",
+        champion_id, skin_id
+    );
+
+    validate_skin_id(&app_handle, champion_id, skin_id).await?;
+    let layout = WorkspaceLayout::from_app(&app_handle).context("Failed to get data directory")?;
+    let mods_base_dir = layout.champion_dir(champion_id)?;
+    let skin_dir = layout.skin_dir(champion_id, skin_id)?;
 
-    if resource_path.exists() {
-        return Ok(resource_path);
+    if !skin_dir.exists() {
+        return Err(anyhow::anyhow!(
+            "Skin directory not found. Please download first: {:?}",
+            skin_dir
+        ));
     }
 
-    // Fallback for development
-    let dev_path = std::env::current_exe()
-        .ok()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-        .map(|p| p.join(tool_name));
+    warn_known_crashes(&app_handle, &skin_id.to_string(), Some(champion_id)).await;
 
-    if let Some(path) = &dev_path {
-        if path.exists() {
-            info!("Found tool in dev path: {:?}", path);
-            return Ok(path.clone());
-        }
-    }
+    run_overlay(
+        &app_handle,
+        &mods_base_dir,
+        &skin_id.to_string(),
+        cancel_token,
+    )
+    .await?;
 
-    if let Ok(cwd) = std::env::current_dir() {
-        let source_path = cwd.join("src-tauri").join("resources").join(tool_name);
-        if source_path.exists() {
-            info!("Found tool in source resources: {:?}", source_path);
-            return Ok(source_path);
-        }
-        let workspace_source_path = cwd
-            .join("crates")
-            .join("ltk-manager")
-            .join("src-tauri")
-            .join("resources")
-            .join(tool_name);
-        if workspace_source_path.exists() {
-            info!(
-                "Found tool in workspace source resources: {:?}",
-                workspace_source_path
+    Ok("Skin run active".to_string())
+}
+
+/// Tell the frontend if `mod_id` is known to crash, without stopping the build.
+async fn warn_known_crashes(app_handle: &tauri::AppHandle, mod_id: &str, champion_id: Option<i32>) {
+    match compat_warnings(app_handle, mod_id, champion_id).await {
+        Ok(warnings) if !warnings.is_empty() => {
+            for warning in &warnings {
+                warn!("Mod {} is known to crash: {}", mod_id, warning.entry.reason);
+            }
+            emit(
+                app_handle,
+                AppEvent::CompatWarnings {
+                    mod_id: mod_id.to_string(),
+                    warnings,
+                },
             );
-            return Ok(workspace_source_path);
         }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to check known crashes for mod {}: {}", mod_id, e),
     }
+}
 
-    warn!(
-        "Tool {} not found in resource dir ({:?}) or dev dev path ({:?})",
-        tool_name, resource_path, dev_path
-    );
-    Err(anyhow::anyhow!("Tool not found: {}", tool_name))
+/// Build an overlay containing the mod folder `mod_name` of `mods_base_dir` and start running it.
+///
+/// The running mod-tools process is stored in [`PatcherState`] so it can be stopped later.
+pub(crate) async fn run_overlay(
+    app_handle: &tauri::AppHandle,
+    mods_base_dir: &Path,
+    mod_name: &str,
+    cancel_token: tokio_util::sync::CancellationToken,
+) -> anyhow::Result<()> {
+    run_overlay_inner(app_handle, mods_base_dir, mod_name, true, cancel_token).await
 }
 
-async fn run_skin_inner(
-    app_handle: tauri::AppHandle,
-    champion_id: i32,
-    skin_id: i32,
+/// Build an overlay of exactly the mod folders `mods` of `mods_base_dir` and start running it.
+///
+/// Unlike [`run_overlay`], the active announcer pack is not added.
+pub(crate) async fn run_staged_overlay(
+    app_handle: &tauri::AppHandle,
+    mods_base_dir: &Path,
+    mods: &[String],
     cancel_token: tokio_util::sync::CancellationToken,
-) -> anyhow::Result<String> {
-    info!(
-        "Starting run_skin_inner: champion_id={}, skin_id={}",
-        champion_id, skin_id
-    );
+) -> anyhow::Result<()> {
+    run_overlay_inner(
+        app_handle,
+        mods_base_dir,
+        &mods.join("/"),
+        false,
+        cancel_token,
+    )
+    .await
+}
 
-    let (workspace_path, league_path) = {
+async fn run_overlay_inner(
+    app_handle: &tauri::AppHandle,
+    mods_base_dir: &Path,
+    mod_name: &str,
+    with_announcer: bool,
+    cancel_token: tokio_util::sync::CancellationToken,
+) -> anyhow::Result<()> {
+    let announcer = if with_announcer {
+        let settings_state = app_handle.state::<crate::state::SettingsState>();
+        let settings = settings_state
+            .0
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to lock settings: {}", e))?;
+        settings.active_announcer.clone()
+    } else {
+        None
+    };
+
+    let inputs = OverlayInputs {
+        mods_base_dir: mods_base_dir.to_path_buf(),
+        mod_name: mod_name.to_string(),
+        announcer,
+    };
+    run_overlay_from(app_handle, inputs, cancel_token).await
+}
+
+/// What an overlay is built from, enough to build the same overlay again later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayInputs {
+    pub mods_base_dir: PathBuf,
+    /// Mod folder names of `mods_base_dir`, joined by `/`.
+    pub mod_name: String,
+    /// Announcer pack added to the overlay.
+    #[serde(default)]
+    pub announcer: Option<String>,
+}
+
+impl OverlayInputs {
+    /// The mod folder names of `mod_name`, without the announcer pack.
+    pub fn mod_ids(&self) -> Vec<String> {
+        self.mod_name.split('/').map(str::to_string).collect()
+    }
+}
+
+/// Build the overlay described by `inputs` and start running it.
+pub(crate) async fn run_overlay_from(
+    app_handle: &tauri::AppHandle,
+    inputs: OverlayInputs,
+    cancel_token: tokio_util::sync::CancellationToken,
+) -> anyhow::Result<()> {
+    let (workspace_path, league_path, include_tft) = {
         let settings_state = app_handle.state::<crate::state::SettingsState>();
         let settings = settings_state
             .0
@@ -350,25 +494,17 @@ async fn run_skin_inner(
             .league_path
             .clone()
             .ok_or_else(|| anyhow::anyhow!("League path not configured"))?;
-        (workspace_path, league_path)
+        (workspace_path, league_path, settings.include_tft)
     };
+    let mod_name = inputs.mod_name.as_str();
 
     info!(
         "Settings loaded. Workspace: {:?}, League: {:?}",
         workspace_path, league_path
     );
 
-    let mods_base_dir = workspace_path.join("data").join(champion_id.to_string());
-    let skin_dir = mods_base_dir.join(skin_id.to_string());
-
-    if !skin_dir.exists() {
-        return Err(anyhow::anyhow!(
-            "Skin directory not found. Please download first: {:?}",
-            skin_dir
-        ));
-    }
-
-    let overlay_dir = workspace_path.join("data").join("overlay");
+    let layout = WorkspaceLayout::new(workspace_path);
+    let overlay_dir = layout.overlay_dir();
     if overlay_dir.exists() {
         tokio::fs::remove_dir_all(&overlay_dir)
             .await
@@ -377,7 +513,7 @@ async fn run_skin_inner(
     tokio::fs::create_dir_all(&overlay_dir)
         .await
         .context("Failed to create overlay dir")?;
-    let mod_tools_path = resolve_tool_path(&app_handle, "mod-tools.exe")?;
+    let mod_tools_path = resolve_tool(app_handle, Tool::ModTools)?;
     info!("Using mod-tools at: {:?}", mod_tools_path);
     let game_dir = league_path.join("Game");
     let game_path_str = if game_dir.exists() {
@@ -388,15 +524,38 @@ async fn run_skin_inner(
 
     info!("Using game path: {}", game_path_str);
 
-    let args_mk = vec![
+    // Add the active announcer pack next to the mod, the mod wins on shared audio banks
+    let (mods_base_dir, mods) = match &inputs.announcer {
+        Some(announcer) => {
+            let (staging_dir, mods, conflicts) = crate::commands::announcer::stage_with_announcer(
+                &layout,
+                &inputs.mods_base_dir,
+                mod_name,
+                announcer,
+            )
+            .context("Failed to stage announcer pack")?;
+            for path in &conflicts {
+                warn!(
+                    "Announcer pack {} conflicts with mod {} on {}, using the mod's file",
+                    announcer, mod_name, path
+                );
+            }
+            (staging_dir, mods)
+        }
+        None => (inputs.mods_base_dir.clone(), mod_name.to_string()),
+    };
+
+    let mut args_mk = vec![
         "mkoverlay".to_string(),
         mods_base_dir.to_string_lossy().to_string(),
         overlay_dir.to_string_lossy().to_string(),
         format!("--game:{}", game_path_str),
-        format!("--mods:{}", skin_id),
-        "--noTFT".to_string(),
-        "--ignoreConflict".to_string(),
+        format!("--mods:{}", mods),
     ];
+    if !include_tft {
+        args_mk.push("--noTFT".to_string());
+    }
+    args_mk.push("--ignoreConflict".to_string());
 
     // Check cancellation before mkoverlay
     if cancel_token.is_cancelled() {
@@ -404,6 +563,13 @@ async fn run_skin_inner(
     }
 
     info!("Running mkoverlay: {:?}", args_mk);
+    emit(
+        app_handle,
+        AppEvent::status(
+            EventSource::Overlay,
+            format!("Building overlay for {}", mod_name),
+        ),
+    );
 
     let output_mk = tokio::process::Command::new(&mod_tools_path)
         .args(&args_mk)
@@ -473,6 +639,15 @@ async fn run_skin_inner(
 
         patcher_state.child_process = Some(child);
     }
+    // `mods` also lists the announcer pack, which is never the crash culprit
+    crate::watchdog::set_active_overlay(app_handle, inputs.clone());
+    emit(
+        app_handle,
+        AppEvent::status(
+            EventSource::Overlay,
+            format!("Overlay running for {}", mod_name),
+        ),
+    );
 
     // Spawn task to stream logs
     tokio::spawn(async move {
@@ -504,5 +679,5 @@ async fn run_skin_inner(
         }
     });
 
-    Ok("Skin run active".to_string())
+    Ok(())
 }
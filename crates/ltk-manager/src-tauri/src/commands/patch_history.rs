@@ -0,0 +1,669 @@
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::events::{emit, AppEvent, EventSource};
+use crate::workspace::WorkspaceLayout;
+use league_toolkit::wad::Wad;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::fs;
+
+const PATCH_MANIFEST_EXTENSION: &str = "json";
+/// Number of manifests kept when `patchHistoryLimit` is not configured.
+const DEFAULT_PATCH_HISTORY_LIMIT: usize = 5;
+/// Written by the Riot client whenever it patches the game.
+const CONTENT_METADATA_PATH: &str = "Game/content-metadata.json";
+/// How often the installed game is checked for a new version.
+const VERSION_POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Deserialize)]
+struct ContentMetadata {
+    version: String,
+}
+
+/// Compact description of a single WAD chunk.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+struct ManifestChunk {
+    #[serde(rename = "h")]
+    path_hash: u64,
+    #[serde(rename = "c")]
+    checksum: u64,
+    #[serde(rename = "s")]
+    uncompressed_size: u64,
+}
+
+/// Chunk manifest of every WAD in a game installation for a single game version.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PatchManifest {
+    version: String,
+    created_at: String,
+    /// WAD path relative to `Game/DATA/FINAL` -> chunks sorted by path hash.
+    wads: BTreeMap<String, Vec<ManifestChunk>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchSnapshotInfo {
+    pub version: String,
+    pub created_at: String,
+    pub wad_count: usize,
+    pub chunk_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChunkChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkChange {
+    pub kind: ChunkChangeKind,
+    /// Path hash formatted as 16 hex digits.
+    pub path_hash: String,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WadDiff {
+    pub wad: String,
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    pub size_delta: i64,
+    /// Per-chunk changes, only populated when the WAD was requested explicitly.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<ChunkChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchDiff {
+    pub from_version: String,
+    pub to_version: String,
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    pub size_delta: i64,
+    pub wads: Vec<WadDiff>,
+}
+
+fn get_patch_history_dir(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    Ok(WorkspaceLayout::from_app(app_handle)?.patch_history_dir())
+}
+
+fn manifest_path(history_dir: &Path, version: &str) -> AppResult<PathBuf> {
+    // Versions come from the game ("14.23.636.3004"), reject anything that could escape the directory
+    if version.is_empty()
+        || !version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+    {
+        return Err(AppError::ValidationFailed(format!(
+            "Invalid game version: {}",
+            version
+        )));
+    }
+
+    Ok(history_dir.join(format!("{}.{}", version, PATCH_MANIFEST_EXTENSION)))
+}
+
+fn get_league_path(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    let settings_state = app_handle.state::<crate::state::SettingsState>();
+    let settings = settings_state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+    settings.league_path.clone().ok_or(AppError::LeagueNotFound)
+}
+
+fn get_patch_history_limit(app_handle: &AppHandle) -> AppResult<usize> {
+    let settings_state = app_handle.state::<crate::state::SettingsState>();
+    let settings = settings_state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+    Ok(settings
+        .patch_history_limit
+        .unwrap_or(DEFAULT_PATCH_HISTORY_LIMIT)
+        .max(1))
+}
+
+/// Version of the installed game, from the content metadata the Riot client writes.
+fn read_installed_version(league_path: &Path) -> AppResult<String> {
+    let content = std::fs::read_to_string(league_path.join(CONTENT_METADATA_PATH))?;
+    parse_content_metadata(&content)
+}
+
+/// Take the version out of `{"version": "14.23.636.3004+branch.releases-14-23.content.release"}`,
+/// without the branch.
+fn parse_content_metadata(content: &str) -> AppResult<String> {
+    let metadata: ContentMetadata = serde_json::from_str(content)
+        .map_err(|e| AppError::Other(format!("Failed to parse game content metadata: {}", e)))?;
+
+    let version = metadata
+        .version
+        .split('+')
+        .next()
+        .unwrap_or_default()
+        .trim();
+    if version.is_empty() {
+        return Err(AppError::Other(
+            "Game content metadata has no version".to_string(),
+        ));
+    }
+    Ok(version.to_string())
+}
+
+/// Recursively collect all `.wad.client` files below `dir`.
+pub(crate) fn collect_wad_files(dir: &Path, wads: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_wad_files(&path, wads)?;
+        } else if path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.ends_with(".wad.client"))
+        {
+            wads.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the table of contents of a single WAD, sorted by path hash.
+fn read_wad_chunks(wad_path: &Path) -> AppResult<Vec<ManifestChunk>> {
+    let file = File::open(wad_path)?;
+    let wad =
+        Wad::mount(file).map_err(|e| AppError::Other(format!("Failed to mount WAD: {}", e)))?;
+
+    let mut chunks: Vec<ManifestChunk> = wad
+        .chunks()
+        .values()
+        .map(|chunk| ManifestChunk {
+            path_hash: chunk.path_hash(),
+            checksum: chunk.checksum(),
+            uncompressed_size: chunk.uncompressed_size() as u64,
+        })
+        .collect();
+    chunks.sort_by_key(|c| c.path_hash);
+
+    Ok(chunks)
+}
+
+/// Read the table of contents of every WAD in the game installation.
+///
+/// Fails if any WAD can't be read, a manifest missing WADs would show their chunks as added or
+/// removed in every diff against it.
+fn build_patch_manifest(
+    app_handle: &AppHandle,
+    league_path: &Path,
+    version: &str,
+) -> AppResult<PatchManifest> {
+    let final_dir = league_path.join("Game").join("DATA").join("FINAL");
+    if !final_dir.exists() {
+        return Err(AppError::InvalidPath(final_dir.display().to_string()));
+    }
+
+    let mut wad_paths = Vec::new();
+    collect_wad_files(&final_dir, &mut wad_paths)?;
+    wad_paths.sort();
+
+    let total = wad_paths.len();
+    let mut wads = BTreeMap::new();
+    let mut failed = Vec::new();
+
+    for (index, wad_path) in wad_paths.iter().enumerate() {
+        let relative = wad_path
+            .strip_prefix(&final_dir)
+            .unwrap_or(wad_path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        match read_wad_chunks(wad_path) {
+            Ok(chunks) => {
+                wads.insert(relative, chunks);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read WAD {}: {:?}", wad_path.display(), e);
+                failed.push(relative);
+            }
+        }
+
+        emit(
+            app_handle,
+            AppEvent::progress(
+                EventSource::PatchSnapshot,
+                index + 1,
+                total,
+                format!("Indexing game files... {}/{}", index + 1, total),
+            ),
+        );
+    }
+
+    if !failed.is_empty() {
+        return Err(AppError::Other(format!(
+            "Failed to read {} of {} WADs ({}), the game may be updating",
+            failed.len(),
+            total,
+            failed.join(", ")
+        )));
+    }
+
+    Ok(PatchManifest {
+        version: version.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        wads,
+    })
+}
+
+async fn load_manifest(path: &Path) -> AppResult<PatchManifest> {
+    let content = fs::read_to_string(path)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to read patch manifest: {}", e)))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::Other(format!("Failed to parse patch manifest: {}", e)))
+}
+
+async fn load_snapshot_infos(history_dir: &Path) -> AppResult<Vec<PatchSnapshotInfo>> {
+    let mut infos = Vec::new();
+    if !history_dir.exists() {
+        return Ok(infos);
+    }
+
+    let mut entries = fs::read_dir(history_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(PATCH_MANIFEST_EXTENSION) {
+            continue;
+        }
+
+        match load_manifest(&path).await {
+            Ok(manifest) => infos.push(PatchSnapshotInfo {
+                chunk_count: manifest.wads.values().map(Vec::len).sum(),
+                wad_count: manifest.wads.len(),
+                version: manifest.version,
+                created_at: manifest.created_at,
+            }),
+            Err(e) => tracing::warn!("Ignoring patch manifest {}: {:?}", path.display(), e),
+        }
+    }
+
+    // Newest first
+    infos.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(infos)
+}
+
+/// Delete the oldest manifests so that at most `limit` remain.
+async fn prune_snapshots(history_dir: &Path, limit: usize) -> AppResult<()> {
+    let infos = load_snapshot_infos(history_dir).await?;
+    for info in infos.iter().skip(limit) {
+        let path = manifest_path(history_dir, &info.version)?;
+        tracing::info!("Removing old patch manifest for version {}", info.version);
+        if let Err(e) = fs::remove_file(&path).await {
+            tracing::warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Store a chunk manifest for `version` unless one already exists.
+///
+/// Called automatically whenever a new game version is detected.
+async fn snapshot_game_version(app_handle: &AppHandle, version: &str) -> AppResult<bool> {
+    let history_dir = get_patch_history_dir(app_handle)?;
+    let path = manifest_path(&history_dir, version)?;
+
+    if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Ok(false);
+    }
+
+    let league_path = get_league_path(app_handle)?;
+    let limit = get_patch_history_limit(app_handle)?;
+
+    tracing::info!("Creating patch manifest for game version {}", version);
+
+    let handle = app_handle.clone();
+    let version_owned = version.to_string();
+    let manifest = tokio::task::spawn_blocking(move || {
+        build_patch_manifest(&handle, &league_path, &version_owned)
+    })
+    .await
+    .map_err(|e| AppError::Other(format!("Patch manifest task failed: {}", e)))??;
+
+    fs::create_dir_all(&history_dir).await?;
+    let json = serde_json::to_string(&manifest)?;
+    fs::write(&path, json).await?;
+
+    tracing::info!(
+        "Saved patch manifest for {} ({} WADs)",
+        version,
+        manifest.wads.len()
+    );
+
+    prune_snapshots(&history_dir, limit).await?;
+
+    Ok(true)
+}
+
+/// Snapshot the installed game in the background whenever its version changes.
+///
+/// The first check runs right away, so a game patched while the app was closed is picked up
+/// on the next start.
+pub fn spawn_version_watcher(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut snapshotted: Option<String> = None;
+
+        loop {
+            if let Err(e) = snapshot_if_changed(&app_handle, &mut snapshotted).await {
+                tracing::debug!("Skipping patch manifest check: {:?}", e);
+            }
+            tokio::time::sleep(VERSION_POLL_INTERVAL).await;
+        }
+    });
+}
+
+async fn snapshot_if_changed(
+    app_handle: &AppHandle,
+    snapshotted: &mut Option<String>,
+) -> AppResult<()> {
+    let league_path = get_league_path(app_handle)?;
+    let version = read_installed_version(&league_path)?;
+    if snapshotted.as_ref() == Some(&version) {
+        return Ok(());
+    }
+
+    if let Err(e) = snapshot_game_version(app_handle, &version).await {
+        tracing::warn!("Failed to create patch manifest: {:?}", e);
+        return Ok(());
+    }
+    *snapshotted = Some(version);
+    Ok(())
+}
+
+fn record_change(
+    diff: &mut WadDiff,
+    detailed: bool,
+    kind: ChunkChangeKind,
+    path_hash: u64,
+    old_size: Option<u64>,
+    new_size: Option<u64>,
+) {
+    match kind {
+        ChunkChangeKind::Added => diff.added += 1,
+        ChunkChangeKind::Removed => diff.removed += 1,
+        ChunkChangeKind::Modified => diff.modified += 1,
+    }
+    diff.size_delta += new_size.unwrap_or(0) as i64 - old_size.unwrap_or(0) as i64;
+
+    if detailed {
+        diff.changes.push(ChunkChange {
+            kind,
+            path_hash: format!("{:016x}", path_hash),
+            old_size,
+            new_size,
+        });
+    }
+}
+
+fn diff_wad(wad: &str, old: &[ManifestChunk], new: &[ManifestChunk], detailed: bool) -> WadDiff {
+    let old_map: HashMap<u64, &ManifestChunk> = old.iter().map(|c| (c.path_hash, c)).collect();
+    let new_map: HashMap<u64, &ManifestChunk> = new.iter().map(|c| (c.path_hash, c)).collect();
+
+    let mut diff = WadDiff {
+        wad: wad.to_string(),
+        added: 0,
+        removed: 0,
+        modified: 0,
+        size_delta: 0,
+        changes: Vec::new(),
+    };
+
+    for chunk in new {
+        match old_map.get(&chunk.path_hash) {
+            None => record_change(
+                &mut diff,
+                detailed,
+                ChunkChangeKind::Added,
+                chunk.path_hash,
+                None,
+                Some(chunk.uncompressed_size),
+            ),
+            Some(old_chunk) if old_chunk.checksum != chunk.checksum => record_change(
+                &mut diff,
+                detailed,
+                ChunkChangeKind::Modified,
+                chunk.path_hash,
+                Some(old_chunk.uncompressed_size),
+                Some(chunk.uncompressed_size),
+            ),
+            Some(_) => {}
+        }
+    }
+
+    for chunk in old {
+        if !new_map.contains_key(&chunk.path_hash) {
+            record_change(
+                &mut diff,
+                detailed,
+                ChunkChangeKind::Removed,
+                chunk.path_hash,
+                Some(chunk.uncompressed_size),
+                None,
+            );
+        }
+    }
+
+    diff
+}
+
+fn diff_manifests(old: &PatchManifest, new: &PatchManifest, wad_filter: Option<&str>) -> PatchDiff {
+    let mut result = PatchDiff {
+        from_version: old.version.clone(),
+        to_version: new.version.clone(),
+        added: 0,
+        removed: 0,
+        modified: 0,
+        size_delta: 0,
+        wads: Vec::new(),
+    };
+
+    let mut wad_names: Vec<&String> = old.wads.keys().chain(new.wads.keys()).collect();
+    wad_names.sort();
+    wad_names.dedup();
+
+    for wad in wad_names {
+        if wad_filter.is_some_and(|filter| filter != wad) {
+            continue;
+        }
+
+        let old_chunks = old.wads.get(wad).map(Vec::as_slice).unwrap_or_default();
+        let new_chunks = new.wads.get(wad).map(Vec::as_slice).unwrap_or_default();
+        let wad_diff = diff_wad(wad, old_chunks, new_chunks, wad_filter.is_some());
+
+        if wad_diff.added + wad_diff.removed + wad_diff.modified == 0 {
+            continue;
+        }
+
+        result.added += wad_diff.added;
+        result.removed += wad_diff.removed;
+        result.modified += wad_diff.modified;
+        result.size_delta += wad_diff.size_delta;
+        result.wads.push(wad_diff);
+    }
+
+    result
+}
+
+/// List stored patch manifests, newest first.
+#[tauri::command]
+pub async fn list_patch_snapshots(app_handle: AppHandle) -> IpcResult<Vec<PatchSnapshotInfo>> {
+    list_patch_snapshots_inner(&app_handle).await.into()
+}
+
+async fn list_patch_snapshots_inner(app_handle: &AppHandle) -> AppResult<Vec<PatchSnapshotInfo>> {
+    let history_dir = get_patch_history_dir(app_handle)?;
+    load_snapshot_infos(&history_dir).await
+}
+
+/// Diff two stored patch manifests.
+///
+/// When `wad` is given, only that WAD is compared and per-chunk changes are included.
+#[tauri::command]
+pub async fn diff_patch_snapshots(
+    app_handle: AppHandle,
+    from_version: String,
+    to_version: String,
+    wad: Option<String>,
+) -> IpcResult<PatchDiff> {
+    diff_patch_snapshots_inner(&app_handle, &from_version, &to_version, wad.as_deref())
+        .await
+        .into()
+}
+
+async fn diff_patch_snapshots_inner(
+    app_handle: &AppHandle,
+    from_version: &str,
+    to_version: &str,
+    wad: Option<&str>,
+) -> AppResult<PatchDiff> {
+    let history_dir = get_patch_history_dir(app_handle)?;
+
+    let from_path = manifest_path(&history_dir, from_version)?;
+    let to_path = manifest_path(&history_dir, to_version)?;
+
+    for (version, path) in [(from_version, &from_path), (to_version, &to_path)] {
+        if !path.exists() {
+            return Err(AppError::Other(format!(
+                "No patch manifest stored for version {}",
+                version
+            )));
+        }
+    }
+
+    let old = load_manifest(&from_path).await?;
+    let new = load_manifest(&to_path).await?;
+
+    Ok(diff_manifests(&old, &new, wad))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(path_hash: u64, checksum: u64, uncompressed_size: u64) -> ManifestChunk {
+        ManifestChunk {
+            path_hash,
+            checksum,
+            uncompressed_size,
+        }
+    }
+
+    fn manifest(version: &str, wads: &[(&str, Vec<ManifestChunk>)]) -> PatchManifest {
+        PatchManifest {
+            version: version.to_string(),
+            created_at: String::new(),
+            wads: wads
+                .iter()
+                .map(|(name, chunks)| (name.to_string(), chunks.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff_wad_added_removed_and_modified() {
+        let old = [chunk(1, 10, 100), chunk(2, 20, 200), chunk(3, 30, 300)];
+        let new = [chunk(1, 10, 100), chunk(2, 21, 250), chunk(4, 40, 400)];
+
+        let diff = diff_wad("Aatrox.wad.client", &old, &new, true);
+        assert_eq!((diff.added, diff.removed, diff.modified), (1, 1, 1));
+        assert_eq!(diff.size_delta, 400 - 300 + 50);
+
+        let change = |kind| diff.changes.iter().find(|c| c.kind == kind).unwrap();
+        let added = change(ChunkChangeKind::Added);
+        assert_eq!(added.path_hash, "0000000000000004");
+        assert_eq!((added.old_size, added.new_size), (None, Some(400)));
+        let removed = change(ChunkChangeKind::Removed);
+        assert_eq!(removed.path_hash, "0000000000000003");
+        assert_eq!((removed.old_size, removed.new_size), (Some(300), None));
+        let modified = change(ChunkChangeKind::Modified);
+        assert_eq!(modified.path_hash, "0000000000000002");
+        assert_eq!(
+            (modified.old_size, modified.new_size),
+            (Some(200), Some(250))
+        );
+    }
+
+    #[test]
+    fn test_diff_wad_skips_details_unless_requested() {
+        let diff = diff_wad("Aatrox.wad.client", &[], &[chunk(1, 10, 100)], false);
+        assert_eq!(diff.added, 1);
+        assert!(diff.changes.is_empty());
+    }
+
+    #[test]
+    fn test_diff_manifests_totals_changed_wads() {
+        let old = manifest(
+            "14.22.1",
+            &[
+                ("Aatrox.wad.client", vec![chunk(1, 10, 100)]),
+                ("Ahri.wad.client", vec![chunk(5, 50, 500)]),
+                ("Old.wad.client", vec![chunk(7, 70, 700)]),
+            ],
+        );
+        let new = manifest(
+            "14.23.1",
+            &[
+                ("Aatrox.wad.client", vec![chunk(1, 10, 100)]),
+                ("Ahri.wad.client", vec![chunk(5, 51, 550)]),
+                ("New.wad.client", vec![chunk(8, 80, 800), chunk(9, 90, 900)]),
+            ],
+        );
+
+        let diff = diff_manifests(&old, &new, None);
+        assert_eq!(
+            (diff.from_version.as_str(), diff.to_version.as_str()),
+            ("14.22.1", "14.23.1")
+        );
+        assert_eq!((diff.added, diff.removed, diff.modified), (2, 1, 1));
+        assert_eq!(diff.size_delta, 50 - 700 + 1700);
+        // Unchanged WADs are left out
+        let wads: Vec<&str> = diff.wads.iter().map(|w| w.wad.as_str()).collect();
+        assert_eq!(
+            wads,
+            ["Ahri.wad.client", "New.wad.client", "Old.wad.client"]
+        );
+        assert!(diff.wads.iter().all(|w| w.changes.is_empty()));
+
+        let diff = diff_manifests(&old, &new, Some("Old.wad.client"));
+        assert_eq!((diff.added, diff.removed, diff.modified), (0, 1, 0));
+        assert_eq!(diff.wads.len(), 1);
+        assert_eq!(diff.wads[0].changes[0].kind, ChunkChangeKind::Removed);
+    }
+
+    #[test]
+    fn test_parse_content_metadata_drops_branch() {
+        let version = parse_content_metadata(
+            r#"{"version": "14.23.636.3004+branch.releases-14-23.content.release"}"#,
+        )
+        .unwrap();
+        assert_eq!(version, "14.23.636.3004");
+        assert!(parse_content_metadata(r#"{"version": ""}"#).is_err());
+        assert!(parse_content_metadata("{}").is_err());
+    }
+}
@@ -1,12 +1,13 @@
 use crate::error::{AppError, AppResult, IpcResult};
-use crate::patcher::api::{CSLogLevel, PatcherApi, PatcherError, PATCHER_DLL_NAME};
+use crate::patcher::api::{CSLogLevel, PatcherApi, PatcherError};
 use crate::patcher::PatcherState;
+use crate::tools::{resolve_tool, Tool};
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, State};
 
 /// Default timeout for hook initialization (5 minutes in milliseconds).
 const DEFAULT_HOOK_TIMEOUT_MS: u32 = 300_000;
@@ -47,36 +48,6 @@ enum PatcherLoopError {
     Stopped,
 }
 
-/// Resolve the path to the patcher DLL from bundled resources.
-fn resolve_patcher_dll_path(app_handle: &AppHandle) -> AppResult<PathBuf> {
-    let resource_path = app_handle
-        .path()
-        .resource_dir()
-        .map_err(|e| AppError::Other(format!("Failed to get resource directory: {}", e)))?
-        .join(PATCHER_DLL_NAME);
-
-    if resource_path.exists() {
-        return Ok(resource_path);
-    }
-
-    // Fallback for development: check next to executable
-    let dev_path = std::env::current_exe()
-        .ok()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-        .map(|p| p.join(PATCHER_DLL_NAME));
-
-    if let Some(path) = dev_path {
-        if path.exists() {
-            return Ok(path);
-        }
-    }
-
-    Err(AppError::Other(format!(
-        "Patcher DLL not found. Expected at: {}",
-        resource_path.display()
-    )))
-}
-
 /// Start the patcher with the given configuration.
 ///
 /// The patcher runs in a background thread, continuously monitoring for the
@@ -104,7 +75,7 @@ pub(crate) fn start_patcher_inner(
         return Err(AppError::Other("Patcher is already running".to_string()));
     }
 
-    let dll_path = resolve_patcher_dll_path(app_handle)?;
+    let dll_path = resolve_tool(app_handle, Tool::PatcherDll)?;
     tracing::info!("Using patcher DLL: {}", dll_path.display());
     tracing::info!("Starting patcher with config path: {}", config.config_path);
 
@@ -0,0 +1,128 @@
+use crate::commands::mod_list::{collect_mod_list, install_mod_list, ApplyModListReport};
+use crate::commands::quick_switch::register_quick_switch_hotkeys;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::peer_sync::{self, PeerInfo, ProfileBundle};
+use crate::state::{save_settings_to_disk, SettingsState};
+use tauri::{AppHandle, Manager};
+
+/// Announce this instance on the local network as `name` and accept profiles from peers.
+#[tauri::command]
+pub async fn start_peer_sync(app_handle: AppHandle, name: String) -> IpcResult<()> {
+    peer_sync::start(&app_handle, name).await.into()
+}
+
+#[tauri::command]
+pub fn stop_peer_sync(app_handle: AppHandle) -> IpcResult<()> {
+    peer_sync::stop(&app_handle).into()
+}
+
+#[tauri::command]
+pub fn list_peers(app_handle: AppHandle) -> IpcResult<Vec<PeerInfo>> {
+    peer_sync::peers(&app_handle).into()
+}
+
+/// Send the installed mods, skin bindings and quick switches to the peer at `address`.
+#[tauri::command]
+pub async fn send_profile_to_peer(
+    app_handle: AppHandle,
+    address: String,
+    name: String,
+) -> IpcResult<()> {
+    send_profile_to_peer_inner(&app_handle, &address, name)
+        .await
+        .into()
+}
+
+async fn send_profile_to_peer_inner(
+    app_handle: &AppHandle,
+    address: &str,
+    name: String,
+) -> AppResult<()> {
+    let mod_list = collect_mod_list(app_handle).await?;
+    let (skin_bindings, quick_switches) = {
+        let state = app_handle.state::<SettingsState>();
+        let settings = state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        (
+            settings.skin_bindings.clone(),
+            settings.quick_switches.clone(),
+        )
+    };
+
+    peer_sync::send_bundle(
+        address,
+        name,
+        ProfileBundle {
+            mod_list,
+            skin_bindings,
+            quick_switches,
+        },
+    )
+    .await
+}
+
+/// Accept or reject a received profile.
+///
+/// Accepting installs the missing mods and adds the bindings and quick switches, keeping
+/// existing ones with the same champion or name. Returns the install report when accepted.
+#[tauri::command]
+pub async fn respond_to_peer_offer(
+    app_handle: AppHandle,
+    id: u64,
+    accept: bool,
+) -> IpcResult<Option<ApplyModListReport>> {
+    let offer = match peer_sync::take_offer(&app_handle, id) {
+        Ok(Some(offer)) => offer,
+        Ok(None) => {
+            return IpcResult::err(AppError::ValidationFailed(format!(
+                "No pending profile with id {}",
+                id
+            )))
+        }
+        Err(e) => return IpcResult::err(e),
+    };
+    if !accept {
+        tracing::info!("Rejected profile from {}", offer.from_name);
+        return IpcResult::ok(None);
+    }
+
+    accept_offer(&app_handle, offer.bundle)
+        .await
+        .map(Some)
+        .into()
+}
+
+async fn accept_offer(
+    app_handle: &AppHandle,
+    bundle: ProfileBundle,
+) -> AppResult<ApplyModListReport> {
+    merge_profile_settings(app_handle, &bundle)?;
+    register_quick_switch_hotkeys(app_handle);
+    install_mod_list(app_handle, bundle.mod_list).await
+}
+
+fn merge_profile_settings(app_handle: &AppHandle, bundle: &ProfileBundle) -> AppResult<()> {
+    let state = app_handle.state::<SettingsState>();
+    let mut settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+    for (&champion_id, &skin_id) in &bundle.skin_bindings {
+        settings.skin_bindings.entry(champion_id).or_insert(skin_id);
+    }
+    for quick_switch in &bundle.quick_switches {
+        if !settings
+            .quick_switches
+            .iter()
+            .any(|existing| existing.name.eq_ignore_ascii_case(&quick_switch.name))
+        {
+            settings.quick_switches.push(quick_switch.clone());
+        }
+    }
+
+    save_settings_to_disk(app_handle, &settings)?;
+    Ok(())
+}
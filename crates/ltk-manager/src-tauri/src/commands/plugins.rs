@@ -0,0 +1,334 @@
+//! Executable plugins that add commands to the app.
+//!
+//! Every folder in the workspace `plugins` directory holds a `plugin.json` manifest and the
+//! executable it names. Running a plugin command starts the executable with the command name
+//! as its only argument and the JSON arguments on stdin, and reads a JSON result from stdout.
+//!
+//! Plugins are native programs and are not sandboxed. They only run after the user enabled
+//! them, which grants the permissions the manifest declares at that time. The permissions
+//! decide which paths are passed to the plugin; a plugin that declares more permissions after
+//! an update is disabled until the user enables it again.
+
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::state::{save_settings_to_disk, SettingsState};
+use crate::workspace::{validate_name, WorkspaceLayout};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::fs;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+const MANIFEST_FILENAME: &str = "plugin.json";
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginPermission {
+    /// Read and write the workspace, passed as `LTK_WORKSPACE_PATH`.
+    Workspace,
+    /// Read the game files, passed as `LTK_LEAGUE_PATH`.
+    LeaguePath,
+    /// Access the network. Shown to the user only, it can't be enforced.
+    Network,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginCommand {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginManifest {
+    /// Must match the plugin's folder name.
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// File name of the executable in the plugin folder.
+    pub executable: String,
+    #[serde(default)]
+    pub permissions: Vec<PluginPermission>,
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    #[serde(flatten)]
+    pub manifest: PluginManifest,
+    pub enabled: bool,
+}
+
+async fn load_manifest(layout: &WorkspaceLayout, plugin_id: &str) -> AppResult<PluginManifest> {
+    let path = layout.plugin_dir(plugin_id)?.join(MANIFEST_FILENAME);
+    let content = fs::read_to_string(&path).await.map_err(|e| {
+        AppError::Other(format!(
+            "Failed to read manifest of plugin {}: {}",
+            plugin_id, e
+        ))
+    })?;
+    let manifest: PluginManifest = serde_json::from_str(&content)
+        .map_err(|e| AppError::Other(format!("Invalid manifest of plugin {}: {}", plugin_id, e)))?;
+
+    if manifest.id != plugin_id {
+        return Err(AppError::ValidationFailed(format!(
+            "Plugin {} declares id {:?}",
+            plugin_id, manifest.id
+        )));
+    }
+    validate_name(&manifest.executable, "plugin executable")?;
+    Ok(manifest)
+}
+
+/// Whether the user enabled `manifest` with all the permissions it declares.
+fn is_enabled(app_handle: &AppHandle, manifest: &PluginManifest) -> AppResult<bool> {
+    let state = app_handle.state::<SettingsState>();
+    let settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+    Ok(settings
+        .enabled_plugins
+        .get(&manifest.id)
+        .is_some_and(|granted| manifest.permissions.iter().all(|p| granted.contains(p))))
+}
+
+#[tauri::command]
+pub async fn list_plugins(app_handle: AppHandle) -> IpcResult<Vec<PluginInfo>> {
+    list_plugins_inner(&app_handle).await.into()
+}
+
+async fn list_plugins_inner(app_handle: &AppHandle) -> AppResult<Vec<PluginInfo>> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let mut entries = match fs::read_dir(layout.plugins_dir()).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut plugins = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if !entry.file_type().await?.is_dir() {
+            continue;
+        }
+        let plugin_id = entry.file_name().to_string_lossy().to_string();
+        // A broken plugin shouldn't hide the others
+        match load_manifest(&layout, &plugin_id).await {
+            Ok(manifest) => plugins.push(PluginInfo {
+                enabled: is_enabled(app_handle, &manifest)?,
+                manifest,
+            }),
+            Err(e) => tracing::warn!("Skipping plugin {}: {}", plugin_id, e),
+        }
+    }
+
+    plugins.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    Ok(plugins)
+}
+
+/// Enable a plugin with the permissions its manifest declares, or disable it.
+#[tauri::command]
+pub async fn set_plugin_enabled(
+    app_handle: AppHandle,
+    plugin_id: String,
+    enabled: bool,
+) -> IpcResult<PluginInfo> {
+    set_plugin_enabled_inner(&app_handle, &plugin_id, enabled)
+        .await
+        .into()
+}
+
+async fn set_plugin_enabled_inner(
+    app_handle: &AppHandle,
+    plugin_id: &str,
+    enabled: bool,
+) -> AppResult<PluginInfo> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let manifest = load_manifest(&layout, plugin_id).await?;
+
+    {
+        let state = app_handle.state::<SettingsState>();
+        let mut settings = state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        if enabled {
+            settings
+                .enabled_plugins
+                .insert(manifest.id.clone(), manifest.permissions.clone());
+        } else {
+            settings.enabled_plugins.remove(&manifest.id);
+        }
+        save_settings_to_disk(app_handle, &settings)?;
+    }
+
+    tracing::info!(
+        "{} plugin {} {}",
+        if enabled { "Enabled" } else { "Disabled" },
+        manifest.id,
+        manifest.version
+    );
+    Ok(PluginInfo { manifest, enabled })
+}
+
+/// Run `command` of an enabled plugin with `args` and return the JSON it prints.
+#[tauri::command]
+pub async fn run_plugin_command(
+    app_handle: AppHandle,
+    plugin_id: String,
+    command: String,
+    args: Option<serde_json::Value>,
+) -> IpcResult<serde_json::Value> {
+    run_plugin_command_inner(&app_handle, &plugin_id, &command, args)
+        .await
+        .into()
+}
+
+async fn run_plugin_command_inner(
+    app_handle: &AppHandle,
+    plugin_id: &str,
+    command: &str,
+    args: Option<serde_json::Value>,
+) -> AppResult<serde_json::Value> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let manifest = load_manifest(&layout, plugin_id).await?;
+    if !is_enabled(app_handle, &manifest)? {
+        return Err(AppError::ValidationFailed(format!(
+            "Plugin {} is not enabled",
+            plugin_id
+        )));
+    }
+    if !manifest.commands.iter().any(|c| c.name == command) {
+        return Err(AppError::ValidationFailed(format!(
+            "Plugin {} has no command {:?}",
+            plugin_id, command
+        )));
+    }
+
+    let plugin_dir = layout.plugin_dir(plugin_id)?;
+    let mut process = tokio::process::Command::new(plugin_dir.join(&manifest.executable));
+    process
+        .arg(command)
+        .current_dir(&plugin_dir)
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    for (name, value) in permitted_paths(app_handle, &layout, &manifest)? {
+        process.env(name, value);
+    }
+
+    tracing::info!("Running command {} of plugin {}", command, plugin_id);
+    let mut child = process
+        .spawn()
+        .map_err(|e| AppError::Other(format!("Failed to start plugin {}: {}", plugin_id, e)))?;
+    let input = serde_json::to_vec(&args.unwrap_or(serde_json::Value::Null))?;
+    let stdin = child.stdin.take();
+    let write_input = async move {
+        if let Some(mut stdin) = stdin {
+            // Plugins that take no arguments may exit without reading them
+            if let Err(e) = stdin.write_all(&input).await {
+                tracing::debug!("Plugin {} didn't read its arguments: {}", plugin_id, e);
+            }
+        }
+        // Dropping stdin closes it, so the plugin sees the end of its arguments
+        Ok(())
+    };
+    let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+
+    // Writing the arguments is part of the run, a plugin that never reads them must time out too
+    let finished = tokio::time::timeout(COMMAND_TIMEOUT, async {
+        tokio::try_join!(
+            write_input,
+            child.wait(),
+            read_pipe(stdout),
+            read_pipe(stderr)
+        )
+    })
+    .await;
+    let (_, status, stdout, stderr) = match finished {
+        Ok(result) => result?,
+        Err(_) => {
+            if let Err(e) = child.kill().await {
+                tracing::warn!("Failed to kill plugin {}: {}", plugin_id, e);
+            }
+            return Err(AppError::Other(format!(
+                "Plugin {} didn't finish {} within {}s",
+                plugin_id,
+                command,
+                COMMAND_TIMEOUT.as_secs()
+            )));
+        }
+    };
+
+    let stderr = String::from_utf8_lossy(&stderr);
+    if !status.success() {
+        return Err(AppError::Other(format!(
+            "Plugin {} failed with {}: {}",
+            plugin_id,
+            status,
+            stderr.trim()
+        )));
+    }
+    if !stderr.trim().is_empty() {
+        tracing::debug!("Plugin {}: {}", plugin_id, stderr.trim());
+    }
+
+    if stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(serde_json::Value::Null);
+    }
+    serde_json::from_slice(&stdout)
+        .map_err(|e| AppError::Other(format!("Plugin {} returned invalid JSON: {}", plugin_id, e)))
+}
+
+async fn read_pipe(pipe: Option<impl AsyncRead + Unpin>) -> std::io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    if let Some(mut pipe) = pipe {
+        pipe.read_to_end(&mut data).await?;
+    }
+    Ok(data)
+}
+
+/// Environment variables with the paths `manifest`'s permissions allow.
+fn permitted_paths(
+    app_handle: &AppHandle,
+    layout: &WorkspaceLayout,
+    manifest: &PluginManifest,
+) -> AppResult<Vec<(&'static str, PathBuf)>> {
+    let league_path = {
+        let state = app_handle.state::<SettingsState>();
+        let settings = state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        settings.league_path.clone()
+    };
+
+    let mut paths = Vec::new();
+    for permission in &manifest.permissions {
+        match permission {
+            PluginPermission::Workspace => {
+                paths.push(("LTK_WORKSPACE_PATH", layout.root().to_path_buf()))
+            }
+            PluginPermission::LeaguePath => {
+                if let Some(league_path) = &league_path {
+                    paths.push(("LTK_LEAGUE_PATH", league_path.clone()));
+                }
+            }
+            PluginPermission::Network => {}
+        }
+    }
+    Ok(paths)
+}
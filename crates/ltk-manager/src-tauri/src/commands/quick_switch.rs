@@ -0,0 +1,253 @@
+//! Quick switches: named skins that are built and run in one step, from the UI, the HTTP API or
+//! a global hotkey.
+//!
+//! Hotkeys are registered with the OS at startup and again whenever the quick switches change,
+//! see [`register_quick_switch_hotkeys`].
+
+use crate::commands::mod_skin::run_skin;
+use crate::commands::validate_skin_id;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::state::{save_settings_to_disk, QuickSwitch, SettingsState};
+use std::str::FromStr;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+#[tauri::command]
+pub fn list_quick_switches(state: State<SettingsState>) -> IpcResult<Vec<QuickSwitch>> {
+    list_quick_switches_inner(&state).into()
+}
+
+fn list_quick_switches_inner(state: &SettingsState) -> AppResult<Vec<QuickSwitch>> {
+    let settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+    Ok(settings.quick_switches.clone())
+}
+
+/// Add a quick switch. Its name and hotkey must not be used by another quick switch.
+#[tauri::command]
+pub async fn create_quick_switch(
+    app_handle: AppHandle,
+    quick_switch: QuickSwitch,
+) -> IpcResult<Vec<QuickSwitch>> {
+    save_quick_switch_inner(&app_handle, None, quick_switch)
+        .await
+        .into()
+}
+
+/// Replace the quick switch called `name`, which may be renamed along the way.
+#[tauri::command]
+pub async fn update_quick_switch(
+    app_handle: AppHandle,
+    name: String,
+    quick_switch: QuickSwitch,
+) -> IpcResult<Vec<QuickSwitch>> {
+    save_quick_switch_inner(&app_handle, Some(&name), quick_switch)
+        .await
+        .into()
+}
+
+#[tauri::command]
+pub fn delete_quick_switch(app_handle: AppHandle, name: String) -> IpcResult<Vec<QuickSwitch>> {
+    delete_quick_switch_inner(&app_handle, &name).into()
+}
+
+async fn save_quick_switch_inner(
+    app_handle: &AppHandle,
+    replaces: Option<&str>,
+    mut quick_switch: QuickSwitch,
+) -> AppResult<Vec<QuickSwitch>> {
+    quick_switch.name = quick_switch.name.trim().to_string();
+    if quick_switch.name.is_empty() {
+        return Err(AppError::ValidationFailed(
+            "Quick switch name cannot be empty".to_string(),
+        ));
+    }
+    validate_skin_id(app_handle, quick_switch.champion_id, quick_switch.skin_id).await?;
+
+    quick_switch.hotkey = quick_switch
+        .hotkey
+        .map(|hotkey| hotkey.trim().to_string())
+        .filter(|hotkey| !hotkey.is_empty());
+    let shortcut = quick_switch
+        .hotkey
+        .as_deref()
+        .map(parse_hotkey)
+        .transpose()?;
+
+    let quick_switches = {
+        let state = app_handle.state::<SettingsState>();
+        let mut settings = state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+        let existing = match replaces {
+            Some(name) => Some(position_of(&settings.quick_switches, name)?),
+            None => None,
+        };
+
+        for (i, other) in settings.quick_switches.iter().enumerate() {
+            if Some(i) == existing {
+                continue;
+            }
+            if other.name.eq_ignore_ascii_case(&quick_switch.name) {
+                return Err(AppError::ValidationFailed(format!(
+                    "A quick switch named {} already exists",
+                    other.name
+                )));
+            }
+            let other_shortcut = other.hotkey.as_deref().and_then(|h| parse_hotkey(h).ok());
+            if shortcut.is_some() && other_shortcut == shortcut {
+                return Err(AppError::ValidationFailed(format!(
+                    "{} is already the hotkey of {}",
+                    quick_switch.hotkey.as_deref().unwrap_or_default(),
+                    other.name
+                )));
+            }
+        }
+
+        // Fail before saving if another app already holds the hotkey
+        if let Some(shortcut) = shortcut {
+            let global_shortcut = app_handle.global_shortcut();
+            if !global_shortcut.is_registered(shortcut) {
+                global_shortcut.register(shortcut).map_err(|e| {
+                    AppError::ValidationFailed(format!(
+                        "{} can't be used as a hotkey: {}",
+                        quick_switch.hotkey.as_deref().unwrap_or_default(),
+                        e
+                    ))
+                })?;
+            }
+        }
+
+        match existing {
+            Some(i) => settings.quick_switches[i] = quick_switch,
+            None => settings.quick_switches.push(quick_switch),
+        }
+        save_settings_to_disk(app_handle, &settings)?;
+        settings.quick_switches.clone()
+    };
+
+    register_quick_switch_hotkeys(app_handle);
+    Ok(quick_switches)
+}
+
+fn delete_quick_switch_inner(app_handle: &AppHandle, name: &str) -> AppResult<Vec<QuickSwitch>> {
+    let quick_switches = {
+        let state = app_handle.state::<SettingsState>();
+        let mut settings = state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+        let index = position_of(&settings.quick_switches, name)?;
+        settings.quick_switches.remove(index);
+        save_settings_to_disk(app_handle, &settings)?;
+        settings.quick_switches.clone()
+    };
+
+    register_quick_switch_hotkeys(app_handle);
+    Ok(quick_switches)
+}
+
+fn position_of(quick_switches: &[QuickSwitch], name: &str) -> AppResult<usize> {
+    quick_switches
+        .iter()
+        .position(|quick_switch| quick_switch.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| AppError::ValidationFailed(format!("No quick switch named {}", name)))
+}
+
+fn parse_hotkey(hotkey: &str) -> AppResult<Shortcut> {
+    Shortcut::from_str(hotkey)
+        .map_err(|e| AppError::ValidationFailed(format!("Invalid hotkey {}: {}", hotkey, e)))
+}
+
+/// Register the hotkey of every quick switch with the OS, replacing the previous ones.
+///
+/// A hotkey that is invalid or already taken by another app is skipped with a warning, so one
+/// bad entry doesn't disable the others.
+pub fn register_quick_switch_hotkeys(app_handle: &AppHandle) {
+    let global_shortcut = app_handle.global_shortcut();
+    if let Err(e) = global_shortcut.unregister_all() {
+        tracing::warn!("Failed to unregister quick switch hotkeys: {}", e);
+    }
+
+    let quick_switches =
+        list_quick_switches_inner(&app_handle.state::<SettingsState>()).unwrap_or_default();
+    for quick_switch in quick_switches {
+        let Some(hotkey) = quick_switch.hotkey.as_deref() else {
+            continue;
+        };
+        match parse_hotkey(hotkey) {
+            Ok(shortcut) => {
+                if let Err(e) = global_shortcut.register(shortcut) {
+                    tracing::warn!(
+                        "Failed to register hotkey {} of quick switch {}: {}",
+                        hotkey,
+                        quick_switch.name,
+                        e
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("Skipping quick switch {}: {}", quick_switch.name, e),
+        }
+    }
+}
+
+/// Global shortcut handler: trigger the quick switch bound to `shortcut`.
+pub fn handle_quick_switch_hotkey(
+    app_handle: &AppHandle,
+    shortcut: &Shortcut,
+    event: ShortcutEvent,
+) {
+    if event.state != ShortcutState::Pressed {
+        return;
+    }
+
+    let quick_switches =
+        list_quick_switches_inner(&app_handle.state::<SettingsState>()).unwrap_or_default();
+    let Some(quick_switch) = quick_switches.into_iter().find(|quick_switch| {
+        quick_switch
+            .hotkey
+            .as_deref()
+            .and_then(|hotkey| parse_hotkey(hotkey).ok())
+            .as_ref()
+            == Some(shortcut)
+    }) else {
+        return;
+    };
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let IpcResult::Err { error } = trigger_quick_switch(app_handle, quick_switch.name).await
+        {
+            tracing::error!("Quick switch hotkey failed: {:?}", error);
+        }
+    });
+}
+
+/// Build and run the skin of the quick switch called `name`, replacing the running overlay.
+#[tauri::command]
+pub async fn trigger_quick_switch(app_handle: AppHandle, name: String) -> IpcResult<String> {
+    let quick_switch = match find_quick_switch(&app_handle, &name) {
+        Ok(quick_switch) => quick_switch,
+        Err(e) => return IpcResult::err(e),
+    };
+
+    tracing::info!(
+        "Quick switch {} triggered: champion {}, skin {}",
+        quick_switch.name,
+        quick_switch.champion_id,
+        quick_switch.skin_id
+    );
+    run_skin(app_handle, quick_switch.champion_id, quick_switch.skin_id).await
+}
+
+fn find_quick_switch(app_handle: &AppHandle, name: &str) -> AppResult<QuickSwitch> {
+    list_quick_switches_inner(&app_handle.state::<SettingsState>())?
+        .into_iter()
+        .find(|quick_switch| quick_switch.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| AppError::ValidationFailed(format!("No quick switch named {}", name)))
+}
@@ -0,0 +1,81 @@
+use crate::commands::mod_skin::run_skin;
+use crate::commands::{validate_champion_id, validate_skin_id};
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::randomizer::pick_random_skin;
+use crate::state::{save_settings_to_disk, ChampionScope, RandomSkinConfig, SettingsState};
+use tauri::{AppHandle, Manager, State};
+
+/// Turn on the randomizer for the champions in `champion_scope`.
+///
+/// Skins are picked from `pool`, or from every skin of the champion when it is empty.
+#[tauri::command]
+pub async fn enable_random_skin(
+    app_handle: AppHandle,
+    champion_scope: ChampionScope,
+    pool: Vec<i32>,
+) -> IpcResult<()> {
+    enable_random_skin_inner(&app_handle, champion_scope, pool)
+        .await
+        .into()
+}
+
+async fn enable_random_skin_inner(
+    app_handle: &AppHandle,
+    champion_scope: ChampionScope,
+    pool: Vec<i32>,
+) -> AppResult<()> {
+    if let ChampionScope::Champions { champion_ids } = &champion_scope {
+        for &champion_id in champion_ids {
+            validate_champion_id(app_handle, champion_id).await?;
+        }
+    }
+    for &skin_id in &pool {
+        validate_skin_id(app_handle, skin_id / 1000, skin_id).await?;
+    }
+
+    set_random_skin(
+        app_handle,
+        &app_handle.state::<SettingsState>(),
+        Some(RandomSkinConfig {
+            champion_scope,
+            pool,
+        }),
+    )
+}
+
+#[tauri::command]
+pub fn disable_random_skin(app_handle: AppHandle, state: State<SettingsState>) -> IpcResult<()> {
+    set_random_skin(&app_handle, &state, None).into()
+}
+
+fn set_random_skin(
+    app_handle: &AppHandle,
+    state: &SettingsState,
+    config: Option<RandomSkinConfig>,
+) -> AppResult<()> {
+    let mut settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+    settings.random_skin = config;
+    save_settings_to_disk(app_handle, &settings)?;
+
+    Ok(())
+}
+
+/// Build and run a random skin of `champion_id` from the randomizer pool.
+#[tauri::command]
+pub async fn run_random_skin(app_handle: AppHandle, champion_id: i32) -> IpcResult<String> {
+    let skin_id = match pick_random_skin(&app_handle, champion_id).await {
+        Ok(Some(skin_id)) => skin_id,
+        Ok(None) => {
+            return IpcResult::err(AppError::ValidationFailed(format!(
+                "The randomizer has no skin to pick for champion {}",
+                champion_id
+            )))
+        }
+        Err(e) => return IpcResult::err(e),
+    };
+
+    run_skin(app_handle, champion_id, skin_id).await
+}
@@ -0,0 +1,112 @@
+//! Rolling back to the last overlay a game ran with without crashing.
+//!
+//! The crash watchdog stores the build inputs of every modded game that didn't crash. Since the
+//! mod folders stay in the workspace, rebuilding from them gives back the known-good overlay.
+
+use crate::commands::mod_skin::{begin_overlay_run, run_overlay_from, OverlayInputs};
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::events::{emit, AppEvent, EventSource};
+use crate::jobs::{run_job_with_token, JobKind};
+use crate::workspace::WorkspaceLayout;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::fs;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastGoodOverlay {
+    #[serde(flatten)]
+    pub inputs: OverlayInputs,
+    /// When the game that ran with this overlay exited.
+    pub played_at: String,
+}
+
+async fn load_last_good(layout: &WorkspaceLayout) -> AppResult<Option<LastGoodOverlay>> {
+    let path = layout.last_good_overlay_file();
+    if !fs::try_exists(&path).await.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to read last good overlay: {}", e)))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| AppError::Other(format!("Failed to parse last good overlay: {}", e)))
+}
+
+/// Store `inputs` as the overlay to roll back to.
+pub(crate) async fn remember_last_good(
+    app_handle: &AppHandle,
+    inputs: &OverlayInputs,
+) -> AppResult<()> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let last_good = LastGoodOverlay {
+        inputs: inputs.clone(),
+        played_at: chrono::Utc::now().to_rfc3339(),
+    };
+    fs::write(
+        layout.last_good_overlay_file(),
+        serde_json::to_string_pretty(&last_good)?,
+    )
+    .await
+    .map_err(|e| AppError::Other(format!("Failed to write last good overlay: {}", e)))?;
+
+    tracing::info!("Remembered overlay for {} as known good", inputs.mod_name);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_last_good_overlay(app_handle: AppHandle) -> IpcResult<Option<LastGoodOverlay>> {
+    get_last_good_overlay_inner(&app_handle).await.into()
+}
+
+async fn get_last_good_overlay_inner(app_handle: &AppHandle) -> AppResult<Option<LastGoodOverlay>> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    load_last_good(&layout).await
+}
+
+/// Replace the running overlay with the last one a game ran with without crashing.
+#[tauri::command]
+pub async fn rollback_to_last_good(app_handle: AppHandle) -> IpcResult<LastGoodOverlay> {
+    rollback_to_last_good_inner(&app_handle).await.into()
+}
+
+async fn rollback_to_last_good_inner(app_handle: &AppHandle) -> AppResult<LastGoodOverlay> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let last_good = load_last_good(&layout).await?.ok_or_else(|| {
+        AppError::ValidationFailed("No game has run with an overlay yet".to_string())
+    })?;
+    if !last_good.inputs.mods_base_dir.is_dir() {
+        return Err(AppError::ModNotFound(
+            last_good.inputs.mods_base_dir.display().to_string(),
+        ));
+    }
+    tracing::info!(
+        "Rolling back to the overlay for {} from {}",
+        last_good.inputs.mod_name,
+        last_good.played_at
+    );
+
+    let cancel_token = begin_overlay_run(app_handle)
+        .await
+        .map_err(|e| AppError::Other(e.message))?;
+    let inputs = last_good.inputs.clone();
+    let run = run_job_with_token(
+        app_handle,
+        JobKind::Overlay,
+        "Roll back overlay",
+        None,
+        cancel_token.clone(),
+        |_| run_overlay_from(app_handle, inputs, cancel_token),
+    );
+    if let Err(e) = run.await {
+        emit(
+            app_handle,
+            AppEvent::error(EventSource::Overlay, format!("{:#}", e)),
+        );
+        return Err(AppError::Other(format!("{:#}", e)));
+    }
+
+    Ok(last_good)
+}
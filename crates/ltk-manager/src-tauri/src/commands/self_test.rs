@@ -0,0 +1,363 @@
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::state::SettingsState;
+use crate::tools::{resolve_tool, tool_status, Tool};
+use crate::workspace::WorkspaceLayout;
+use league_toolkit::wad::{Wad, WadBuilder, WadChunkBuilder};
+use ltk_modpkg::utils::hash_chunk_name;
+use serde::Serialize;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+const TEST_CHUNK_PATH: &str = "data/ltk-manager/self-test.txt";
+const TEST_CHUNK_DATA: &[u8] = b"LTK Manager self test";
+const TEST_MOD_NAME: &str = "self-test";
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SelfTestOutcome {
+    Passed,
+    Failed,
+    /// Not run because an earlier stage failed.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestStage {
+    pub name: String,
+    pub outcome: SelfTestOutcome,
+    pub message: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub stages: Vec<SelfTestStage>,
+}
+
+/// State passed from one stage to the next.
+#[derive(Default)]
+struct SelfTestContext {
+    scratch_dir: PathBuf,
+    wad_data: Vec<u8>,
+    extracted: Vec<u8>,
+    game_dir: PathBuf,
+    /// File name of the game WAD the test mod adds its chunk to.
+    base_wad: String,
+}
+
+type StageFn = fn(&AppHandle, &mut SelfTestContext) -> AppResult<String>;
+
+/// Run a miniature mod pipeline on a generated WAD and report which stage fails.
+///
+/// Nothing outside a scratch directory in the system temp folder is modified.
+#[tauri::command]
+pub async fn run_self_test(app_handle: AppHandle) -> IpcResult<SelfTestReport> {
+    run_self_test_inner(app_handle).await.into()
+}
+
+async fn run_self_test_inner(app_handle: AppHandle) -> AppResult<SelfTestReport> {
+    tokio::task::spawn_blocking(move || run_stages(&app_handle))
+        .await
+        .map_err(|e| AppError::Other(format!("Self test task failed: {}", e)))
+}
+
+fn run_stages(app_handle: &AppHandle) -> SelfTestReport {
+    const STAGES: [(&str, StageFn); 7] = [
+        ("Workspace", check_workspace),
+        ("Build WAD", build_wad),
+        ("Mount WAD", mount_wad),
+        ("Extract chunk", extract_chunk),
+        ("Bundled tools", check_tools),
+        ("Stage overlay mod", stage_overlay_mod),
+        ("Build overlay", build_overlay),
+    ];
+
+    let mut context = SelfTestContext {
+        scratch_dir: std::env::temp_dir()
+            .join(format!("ltk-manager-self-test-{}", uuid::Uuid::new_v4())),
+        ..Default::default()
+    };
+    let mut stages = Vec::new();
+    let mut failed = false;
+
+    for (name, stage) in STAGES {
+        if failed {
+            stages.push(SelfTestStage {
+                name: name.to_string(),
+                outcome: SelfTestOutcome::Skipped,
+                message: None,
+                duration_ms: 0,
+            });
+            continue;
+        }
+
+        let started = Instant::now();
+        let result = stage(app_handle, &mut context);
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        let (outcome, message) = match result {
+            Ok(message) => (SelfTestOutcome::Passed, message),
+            Err(e) => {
+                tracing::warn!("Self test stage {} failed: {}", name, e);
+                failed = true;
+                (SelfTestOutcome::Failed, e.to_string())
+            }
+        };
+        stages.push(SelfTestStage {
+            name: name.to_string(),
+            outcome,
+            message: Some(message),
+            duration_ms,
+        });
+    }
+
+    if context.scratch_dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&context.scratch_dir) {
+            tracing::warn!(
+                "Failed to remove self test directory {:?}: {}",
+                context.scratch_dir,
+                e
+            );
+        }
+    }
+
+    SelfTestReport {
+        passed: !failed,
+        stages,
+    }
+}
+
+fn check_workspace(app_handle: &AppHandle, context: &mut SelfTestContext) -> AppResult<String> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    if !layout.root().is_dir() {
+        return Err(AppError::InvalidPath(layout.root().display().to_string()));
+    }
+
+    // The overlay and downloads are written below the workspace
+    let probe = layout
+        .root()
+        .join(format!(".self-test-{}", uuid::Uuid::new_v4()));
+    std::fs::write(&probe, TEST_CHUNK_DATA)?;
+    std::fs::remove_file(&probe)?;
+
+    std::fs::create_dir_all(&context.scratch_dir)?;
+
+    Ok(format!("{} is writable", layout.root().display()))
+}
+
+fn build_wad(_: &AppHandle, context: &mut SelfTestContext) -> AppResult<String> {
+    let mut cursor = Cursor::new(Vec::new());
+    WadBuilder::default()
+        .with_chunk(WadChunkBuilder::default().with_path(TEST_CHUNK_PATH))
+        .build_to_writer(&mut cursor, |_, data| {
+            data.write_all(TEST_CHUNK_DATA)?;
+            Ok(())
+        })
+        .map_err(|e| AppError::Other(format!("Failed to build WAD: {}", e)))?;
+
+    context.wad_data = cursor.into_inner();
+    Ok(format!("Built a {} byte WAD", context.wad_data.len()))
+}
+
+fn mount_wad(_: &AppHandle, context: &mut SelfTestContext) -> AppResult<String> {
+    let wad = Wad::mount(Cursor::new(&context.wad_data))
+        .map_err(|e| AppError::Other(format!("Failed to mount WAD: {}", e)))?;
+    if !wad.chunks().contains_key(&hash_chunk_name(TEST_CHUNK_PATH)) {
+        return Err(AppError::Other(format!(
+            "Chunk {} missing from the mounted WAD",
+            TEST_CHUNK_PATH
+        )));
+    }
+
+    Ok(format!("Mounted {} chunk(s)", wad.chunks().len()))
+}
+
+fn extract_chunk(_: &AppHandle, context: &mut SelfTestContext) -> AppResult<String> {
+    let mut wad = Wad::mount(Cursor::new(&context.wad_data))
+        .map_err(|e| AppError::Other(format!("Failed to mount WAD: {}", e)))?;
+    let (mut decoder, chunks) = wad.decode();
+    let chunk = chunks
+        .get(&hash_chunk_name(TEST_CHUNK_PATH))
+        .ok_or_else(|| AppError::Other(format!("Chunk {} not found", TEST_CHUNK_PATH)))?;
+    let data = decoder
+        .load_chunk_decompressed(chunk)
+        .map_err(|e| AppError::Other(format!("Failed to decompress chunk: {}", e)))?;
+
+    if &*data != TEST_CHUNK_DATA {
+        return Err(AppError::Other(
+            "Extracted chunk doesn't match the original data".to_string(),
+        ));
+    }
+
+    let output = context.scratch_dir.join("extracted").join(TEST_CHUNK_PATH);
+    write_file(&output, &data)?;
+    context.extracted = data.into_vec();
+
+    Ok(format!("Extracted {} bytes", context.extracted.len()))
+}
+
+/// Lay out the test WAD as a mod the way mod-tools expects it for `mkoverlay`.
+///
+/// mod-tools matches mod WADs to game WADs by file name, so the test WAD is named after the
+/// smallest WAD of the game to keep the overlay build short.
+fn stage_overlay_mod(app_handle: &AppHandle, context: &mut SelfTestContext) -> AppResult<String> {
+    context.game_dir = game_dir(app_handle)?;
+    context.base_wad = find_files(&context.game_dir.join("DATA"), &|name| {
+        name.ends_with(".wad.client")
+    })
+    .into_iter()
+    .filter_map(|path| Some((std::fs::metadata(&path).ok()?.len(), path)))
+    .min()
+    .and_then(|(_, path)| Some(path.file_name()?.to_string_lossy().into_owned()))
+    .ok_or_else(|| {
+        AppError::Other(format!(
+            "No WAD files found in {}",
+            context.game_dir.display()
+        ))
+    })?;
+
+    let mod_dir = context.scratch_dir.join("mods").join(TEST_MOD_NAME);
+    let info = serde_json::json!({
+        "Name": TEST_MOD_NAME,
+        "Author": "LTK Manager",
+        "Version": "1.0.0",
+        "Description": "Self test",
+    });
+    write_file(
+        &mod_dir.join("META").join("info.json"),
+        serde_json::to_string_pretty(&info)?.as_bytes(),
+    )?;
+    write_file(
+        &mod_dir.join("WAD").join(&context.base_wad),
+        &context.wad_data,
+    )?;
+
+    Ok(format!(
+        "Staged mod at {} on top of {}",
+        mod_dir.display(),
+        context.base_wad
+    ))
+}
+
+/// Run mod-tools `mkoverlay` on the staged mod and check the test chunk made it into the overlay.
+fn build_overlay(app_handle: &AppHandle, context: &mut SelfTestContext) -> AppResult<String> {
+    let mod_tools_path = resolve_tool(app_handle, Tool::ModTools)?;
+    let overlay_dir = context.scratch_dir.join("overlay");
+    std::fs::create_dir_all(&overlay_dir)?;
+
+    let mut command = std::process::Command::new(&mod_tools_path);
+    command.args([
+        "mkoverlay".to_string(),
+        context
+            .scratch_dir
+            .join("mods")
+            .to_string_lossy()
+            .to_string(),
+        overlay_dir.to_string_lossy().to_string(),
+        format!("--game:{}", context.game_dir.to_string_lossy()),
+        format!("--mods:{}", TEST_MOD_NAME),
+        "--noTFT".to_string(),
+        "--ignoreConflict".to_string(),
+    ]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(AppError::Other(format!(
+            "mkoverlay failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let base_wad = context.base_wad.as_str();
+    let overlay_wad = find_files(&overlay_dir, &|name| name.eq_ignore_ascii_case(base_wad))
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            AppError::Other(format!(
+                "mkoverlay didn't write {} to the overlay",
+                base_wad
+            ))
+        })?;
+    let wad = Wad::mount(std::fs::File::open(&overlay_wad)?)
+        .map_err(|e| AppError::Other(format!("Failed to mount overlay WAD: {}", e)))?;
+    if !wad.chunks().contains_key(&hash_chunk_name(TEST_CHUNK_PATH)) {
+        return Err(AppError::Other(format!(
+            "Chunk {} missing from the overlay's {}",
+            TEST_CHUNK_PATH, base_wad
+        )));
+    }
+
+    Ok(format!("mkoverlay wrote {}", overlay_wad.display()))
+}
+
+fn game_dir(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    let league_path = {
+        let state = app_handle.state::<SettingsState>();
+        let settings = state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        settings
+            .league_path
+            .clone()
+            .ok_or(AppError::LeagueNotFound)?
+    };
+
+    let game_dir = league_path.join("Game");
+    Ok(if game_dir.is_dir() {
+        game_dir
+    } else {
+        league_path
+    })
+}
+
+/// Files below `dir` whose name matches `matches`.
+fn find_files(dir: &Path, matches: &dyn Fn(&str) -> bool) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if matches(&entry.file_name().to_string_lossy()) {
+                found.push(path);
+            }
+        }
+    }
+    found
+}
+
+fn check_tools(app_handle: &AppHandle, _: &mut SelfTestContext) -> AppResult<String> {
+    let problems: Vec<String> = Tool::ALL
+        .into_iter()
+        .filter_map(|tool| tool_status(app_handle, tool).problem)
+        .collect();
+    if !problems.is_empty() {
+        return Err(AppError::Other(problems.join(" ")));
+    }
+
+    Ok("mod-tools and the patcher DLL are usable".to_string())
+}
+
+fn write_file(path: &Path, data: &[u8]) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, data)?;
+    Ok(())
+}
@@ -18,6 +18,10 @@ fn get_settings_inner(state: &State<SettingsState>) -> AppResult<Settings> {
 }
 
 /// Save settings.
+///
+/// Fields that have their own commands (announcer, quick switches, skin bindings, randomizer
+/// and plugins) keep their current values, so a settings page with an outdated copy doesn't
+/// undo changes made through those commands.
 #[tauri::command]
 pub fn save_settings(
     settings: Settings,
@@ -28,10 +32,15 @@ pub fn save_settings(
 }
 
 fn save_settings_inner(
-    settings: Settings,
+    mut settings: Settings,
     app_handle: &AppHandle,
     state: &State<SettingsState>,
 ) -> AppResult<()> {
+    let mut current = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+    keep_command_fields(&mut settings, &current);
     save_settings_to_disk(app_handle, &settings)?;
 
     // Dynamically update fs scope if workspace path is set
@@ -41,15 +50,20 @@ fn save_settings_inner(
         tracing::info!("Updated fs scope for workspace path: {:?}", path);
     }
 
-    let mut current = state
-        .0
-        .lock()
-        .map_err(|e| AppError::InternalState(e.to_string()))?;
     *current = settings;
 
     Ok(())
 }
 
+/// Copy the fields that are only changed through their own commands from `current`.
+fn keep_command_fields(settings: &mut Settings, current: &Settings) {
+    settings.active_announcer = current.active_announcer.clone();
+    settings.quick_switches = current.quick_switches.clone();
+    settings.skin_bindings = current.skin_bindings.clone();
+    settings.random_skin = current.random_skin.clone();
+    settings.enabled_plugins = current.enabled_plugins.clone();
+}
+
 /// Auto-detect League of Legends installation path.
 #[tauri::command]
 pub fn auto_detect_league_path() -> IpcResult<Option<PathBuf>> {
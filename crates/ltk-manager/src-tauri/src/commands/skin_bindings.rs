@@ -0,0 +1,74 @@
+use crate::commands::validate_skin_id;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::state::{save_settings_to_disk, SettingsState};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager, State};
+
+/// Preferred skin id per champion id.
+#[tauri::command]
+pub fn get_skin_bindings(state: State<SettingsState>) -> IpcResult<BTreeMap<i32, i32>> {
+    get_skin_bindings_inner(&state).into()
+}
+
+fn get_skin_bindings_inner(state: &SettingsState) -> AppResult<BTreeMap<i32, i32>> {
+    let settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+    Ok(settings.skin_bindings.clone())
+}
+
+/// Bind `skin_id` as the preferred skin of `champion_id`, replacing an existing binding.
+#[tauri::command]
+pub async fn set_skin_binding(
+    app_handle: AppHandle,
+    champion_id: i32,
+    skin_id: i32,
+) -> IpcResult<()> {
+    set_skin_binding_inner(&app_handle, champion_id, skin_id)
+        .await
+        .into()
+}
+
+async fn set_skin_binding_inner(
+    app_handle: &AppHandle,
+    champion_id: i32,
+    skin_id: i32,
+) -> AppResult<()> {
+    validate_skin_id(app_handle, champion_id, skin_id).await?;
+
+    update_skin_bindings(
+        app_handle,
+        &app_handle.state::<SettingsState>(),
+        |bindings| {
+            bindings.insert(champion_id, skin_id);
+        },
+    )
+}
+
+#[tauri::command]
+pub fn clear_skin_binding(
+    app_handle: AppHandle,
+    state: State<SettingsState>,
+    champion_id: i32,
+) -> IpcResult<()> {
+    update_skin_bindings(&app_handle, &state, |bindings| {
+        bindings.remove(&champion_id);
+    })
+    .into()
+}
+
+fn update_skin_bindings(
+    app_handle: &AppHandle,
+    state: &SettingsState,
+    update: impl FnOnce(&mut BTreeMap<i32, i32>),
+) -> AppResult<()> {
+    let mut settings = state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+    update(&mut settings.skin_bindings);
+    save_settings_to_disk(app_handle, &settings)?;
+
+    Ok(())
+}
@@ -0,0 +1,412 @@
+//! Normalized skin catalog built from `skin_ids.json`.
+//!
+//! Skin and chroma ids are `champion_id * 1000 + n`, so keeping the skins in a map ordered by
+//! id turns a champion's skins into a range lookup. The catalog is stored as MessagePack in
+//! the workspace and a database refresh only rewrites it when the skins changed.
+//!
+//! The catalog also holds which skins are legacy. Skins are vaulted long after their champion
+//! metadata was downloaded, so the flag stored in the metadata goes stale while the list here
+//! is fetched again on every refresh.
+
+use crate::cache::cached_catalog;
+use crate::commands::data::ChampionWithSkins;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::workspace::WorkspaceLayout;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
+use tauri::AppHandle;
+use tokio::fs;
+
+/// Bumped when the stored layout changes, older files are rebuilt from `skin_ids.json`.
+const CATALOG_FORMAT_VERSION: u32 = 2;
+const SKINS_URL: &str =
+    "https://raw.communitydragon.org/latest/plugins/rcp-be-lol-game-data/global/vi_vn/v1/skins.json";
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SkinCatalog {
+    /// Champion id to name, taken from the champion's base skin.
+    champions: BTreeMap<i32, String>,
+    /// Skin and chroma id to name.
+    skins: BTreeMap<i32, String>,
+    /// Ids of legacy skins, `None` until the list was downloaded.
+    legacy: Option<BTreeSet<i32>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CatalogFile {
+    format_version: u32,
+    catalog: SkinCatalog,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkinInfo {
+    pub id: i32,
+    pub champion_id: i32,
+    pub champion_name: Option<String>,
+    pub name: String,
+    /// Whether this is the champion's default skin.
+    pub is_base: bool,
+}
+
+/// Differences between two versions of the catalog.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CatalogChanges {
+    pub(crate) added: usize,
+    pub(crate) removed: usize,
+    pub(crate) renamed: usize,
+    /// Skins that became or stopped being legacy.
+    pub(crate) legacy_changed: usize,
+}
+
+impl CatalogChanges {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.added == 0 && self.removed == 0 && self.renamed == 0 && self.legacy_changed == 0
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSkinEntry {
+    id: i32,
+    #[serde(default)]
+    is_legacy: bool,
+}
+
+/// Ids of the skins Community Dragon currently lists as legacy.
+pub(crate) async fn download_legacy_skin_ids() -> AppResult<BTreeSet<i32>> {
+    tracing::info!("Fetching legacy skins from {}", SKINS_URL);
+    let skins: HashMap<String, RawSkinEntry> = reqwest::get(SKINS_URL)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::Other(format!("Failed to fetch skin list: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to parse skin list: {}", e)))?;
+
+    Ok(skins
+        .into_values()
+        .filter(|skin| skin.is_legacy)
+        .map(|skin| skin.id)
+        .collect())
+}
+
+/// Skin ids belonging to `champion_id`.
+fn champion_skin_range(champion_id: i32) -> Range<i32> {
+    champion_id.saturating_mul(1000)..champion_id.saturating_add(1).saturating_mul(1000)
+}
+
+impl SkinCatalog {
+    /// Build the catalog from the skin id to name map of `skin_ids.json`.
+    pub(crate) fn from_skin_ids(skin_ids: &HashMap<String, String>) -> Self {
+        let mut catalog = Self::default();
+        for (id, name) in skin_ids {
+            let Ok(id) = id.parse::<i32>() else {
+                continue;
+            };
+            if id % 1000 == 0 {
+                catalog.champions.insert(id / 1000, name.clone());
+            }
+            catalog.skins.insert(id, name.clone());
+        }
+        catalog
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.skins.is_empty()
+    }
+
+    pub(crate) fn has_champion(&self, champion_id: i32) -> bool {
+        self.champion_skins(champion_id).next().is_some()
+    }
+
+    pub(crate) fn has_skin(&self, skin_id: i32) -> bool {
+        self.skins.contains_key(&skin_id)
+    }
+
+    pub(crate) fn legacy_skin_ids(&self) -> Option<&BTreeSet<i32>> {
+        self.legacy.as_ref()
+    }
+
+    pub(crate) fn set_legacy_skin_ids(&mut self, legacy: Option<BTreeSet<i32>>) {
+        self.legacy = legacy;
+    }
+
+    /// Whether `skin_id` is a legacy skin, `None` if the legacy list wasn't downloaded.
+    pub(crate) fn is_legacy(&self, skin_id: i32) -> Option<bool> {
+        self.legacy.as_ref().map(|legacy| legacy.contains(&skin_id))
+    }
+
+    /// Skins and chromas of `champion_id`, ordered by id.
+    pub(crate) fn champion_skins(&self, champion_id: i32) -> impl Iterator<Item = (i32, &str)> {
+        self.skins
+            .range(champion_skin_range(champion_id))
+            .map(|(id, name)| (*id, name.as_str()))
+    }
+
+    pub(crate) fn skin(&self, skin_id: i32) -> Option<SkinInfo> {
+        let name = self.skins.get(&skin_id)?;
+        Some(self.skin_info(skin_id, name))
+    }
+
+    fn skin_info(&self, skin_id: i32, name: &str) -> SkinInfo {
+        let champion_id = skin_id / 1000;
+        SkinInfo {
+            id: skin_id,
+            champion_id,
+            champion_name: self.champions.get(&champion_id).cloned(),
+            name: name.to_string(),
+            is_base: skin_id % 1000 == 0,
+        }
+    }
+
+    /// Skins whose name contains `query`, ignoring case, ordered by id.
+    pub(crate) fn search(&self, query: &str, limit: usize) -> Vec<SkinInfo> {
+        let query = query.trim().to_lowercase();
+        self.skins
+            .iter()
+            .filter(|(_, name)| name.to_lowercase().contains(&query))
+            .take(limit)
+            .map(|(id, name)| self.skin_info(*id, name))
+            .collect()
+    }
+
+    /// Champions that have a base skin, with all their skins.
+    pub(crate) fn champions_with_skins(&self) -> Vec<ChampionWithSkins> {
+        self.champions
+            .iter()
+            .map(|(id, name)| ChampionWithSkins {
+                id: *id,
+                name: name.clone(),
+                skin_collection: self
+                    .champion_skins(*id)
+                    .map(|(skin_id, skin_name)| (skin_id.to_string(), skin_name.to_string()))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// What changed from `previous` to this catalog.
+    pub(crate) fn changes_from(&self, previous: &SkinCatalog) -> CatalogChanges {
+        let mut changes = CatalogChanges::default();
+        for (id, name) in &self.skins {
+            match previous.skins.get(id) {
+                None => changes.added += 1,
+                Some(previous_name) if previous_name != name => changes.renamed += 1,
+                Some(_) => {}
+            }
+        }
+        changes.removed = previous
+            .skins
+            .keys()
+            .filter(|id| !self.skins.contains_key(id))
+            .count();
+        if let Some(legacy) = &self.legacy {
+            changes.legacy_changed = legacy
+                .symmetric_difference(previous.legacy.as_ref().unwrap_or(&BTreeSet::new()))
+                .count();
+        }
+        changes
+    }
+}
+
+/// Load the catalog, rebuilding it from `skin_ids.json` if it is missing or outdated.
+///
+/// Returns an empty catalog while the skin database hasn't been downloaded.
+pub(crate) async fn load_catalog(layout: &WorkspaceLayout) -> AppResult<SkinCatalog> {
+    if let Ok(bytes) = fs::read(layout.skin_catalog_file()).await {
+        match rmp_serde::from_slice::<CatalogFile>(&bytes) {
+            Ok(file) if file.format_version == CATALOG_FORMAT_VERSION => return Ok(file.catalog),
+            Ok(_) => tracing::info!("Rebuilding skin catalog stored in an older format"),
+            Err(e) => tracing::warn!("Rebuilding unreadable skin catalog: {}", e),
+        }
+    }
+
+    let skin_ids_file = layout.skin_ids_file();
+    if !fs::try_exists(&skin_ids_file).await.unwrap_or(false) {
+        return Ok(SkinCatalog::default());
+    }
+    let content = fs::read_to_string(&skin_ids_file)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to read skin data file: {}", e)))?;
+    let skin_ids: HashMap<String, String> = serde_json::from_str(&content)
+        .map_err(|e| AppError::Other(format!("Failed to parse skin data file: {}", e)))?;
+
+    let catalog = SkinCatalog::from_skin_ids(&skin_ids);
+    save_catalog(layout, &catalog).await?;
+    Ok(catalog)
+}
+
+pub(crate) async fn save_catalog(layout: &WorkspaceLayout, catalog: &SkinCatalog) -> AppResult<()> {
+    let file = CatalogFile {
+        format_version: CATALOG_FORMAT_VERSION,
+        catalog: catalog.clone(),
+    };
+    let bytes = rmp_serde::to_vec(&file)
+        .map_err(|e| AppError::Other(format!("Failed to encode skin catalog: {}", e)))?;
+    fs::write(layout.skin_catalog_file(), bytes)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to write skin catalog: {}", e)))
+}
+
+#[tauri::command]
+pub async fn get_skin_info(app_handle: AppHandle, skin_id: i32) -> IpcResult<Option<SkinInfo>> {
+    get_skin_info_inner(&app_handle, skin_id).await.into()
+}
+
+async fn get_skin_info_inner(app_handle: &AppHandle, skin_id: i32) -> AppResult<Option<SkinInfo>> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    Ok(cached_catalog(app_handle, &layout).await?.skin(skin_id))
+}
+
+/// Skins and chromas whose name contains `query`, at most `limit` (50 by default).
+#[tauri::command]
+pub async fn search_skins(
+    app_handle: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> IpcResult<Vec<SkinInfo>> {
+    search_skins_inner(&app_handle, &query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+        .await
+        .into()
+}
+
+async fn search_skins_inner(
+    app_handle: &AppHandle,
+    query: &str,
+    limit: usize,
+) -> AppResult<Vec<SkinInfo>> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    Ok(cached_catalog(app_handle, &layout)
+        .await?
+        .search(query, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A workspace in the temp folder, removed when dropped.
+    struct TestWorkspace(PathBuf);
+
+    impl TestWorkspace {
+        fn new() -> Self {
+            let root =
+                std::env::temp_dir().join(format!("ltk-catalog-test-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&root).unwrap();
+            Self(root)
+        }
+
+        fn layout(&self) -> WorkspaceLayout {
+            WorkspaceLayout::new(&self.0)
+        }
+    }
+
+    impl Drop for TestWorkspace {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn skin_ids() -> HashMap<String, String> {
+        [
+            ("1000", "Annie"),
+            ("1001", "Goth Annie"),
+            ("1002", "Red Riding Annie"),
+            ("103000", "Ahri"),
+            ("103015", "K/DA Ahri"),
+        ]
+        .into_iter()
+        .map(|(id, name)| (id.to_string(), name.to_string()))
+        .collect()
+    }
+
+    fn write_skin_ids(layout: &WorkspaceLayout) {
+        std::fs::write(
+            layout.skin_ids_file(),
+            serde_json::to_string(&skin_ids()).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_catalog_round_trips_through_messagepack() {
+        let workspace = TestWorkspace::new();
+        let layout = workspace.layout();
+        let mut catalog = SkinCatalog::from_skin_ids(&skin_ids());
+        catalog.set_legacy_skin_ids(Some(BTreeSet::from([1001])));
+
+        save_catalog(&layout, &catalog).await.unwrap();
+        // Without skin_ids.json the catalog can only come from the MessagePack file
+        let loaded = load_catalog(&layout).await.unwrap();
+
+        assert_eq!(loaded, catalog);
+        assert_eq!(loaded.is_legacy(1001), Some(true));
+        assert_eq!(
+            loaded.skin(103015).unwrap().champion_name.as_deref(),
+            Some("Ahri")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_catalog_rebuilds_missing_file_from_skin_ids() {
+        let workspace = TestWorkspace::new();
+        let layout = workspace.layout();
+        write_skin_ids(&layout);
+
+        let loaded = load_catalog(&layout).await.unwrap();
+
+        assert_eq!(loaded, SkinCatalog::from_skin_ids(&skin_ids()));
+        assert!(layout.skin_catalog_file().exists());
+        assert_eq!(loaded.is_legacy(1001), None);
+    }
+
+    #[tokio::test]
+    async fn test_load_catalog_rebuilds_unreadable_and_outdated_files() {
+        let workspace = TestWorkspace::new();
+        let layout = workspace.layout();
+        write_skin_ids(&layout);
+        let expected = SkinCatalog::from_skin_ids(&skin_ids());
+
+        std::fs::write(layout.skin_catalog_file(), b"not messagepack").unwrap();
+        assert_eq!(load_catalog(&layout).await.unwrap(), expected);
+
+        let outdated = CatalogFile {
+            format_version: CATALOG_FORMAT_VERSION - 1,
+            catalog: SkinCatalog::default(),
+        };
+        std::fs::write(
+            layout.skin_catalog_file(),
+            rmp_serde::to_vec(&outdated).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(load_catalog(&layout).await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_load_catalog_is_empty_without_database() {
+        let workspace = TestWorkspace::new();
+
+        let loaded = load_catalog(&workspace.layout()).await.unwrap();
+
+        assert!(loaded.is_empty());
+        assert!(!workspace.layout().skin_catalog_file().exists());
+    }
+
+    #[test]
+    fn test_changes_from_counts_legacy_changes() {
+        let previous = SkinCatalog::from_skin_ids(&skin_ids());
+        let mut current = previous.clone();
+        assert!(current.changes_from(&previous).is_empty());
+
+        current.set_legacy_skin_ids(Some(BTreeSet::from([1001, 1002])));
+        assert_eq!(current.changes_from(&previous).legacy_changed, 2);
+
+        let previous = current.clone();
+        current.set_legacy_skin_ids(Some(BTreeSet::from([1002])));
+        assert_eq!(current.changes_from(&previous).legacy_changed, 1);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::workspace::WorkspaceLayout;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tokio::fs;
+
+const TFT_METADATA_URL_TEMPLATE: &str =
+    "https://raw.communitydragon.org/latest/plugins/rcp-be-lol-game-data/global/vi_vn/v1/{file}";
+
+/// WAD name prefixes that belong to Teamfight Tactics (the same ones mod-tools skips with `--noTFT`).
+const TFT_WAD_PREFIXES: &[&str] = &["map22", "tft"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TftCategory {
+    /// Little Legends.
+    Companions,
+    /// Arena skins.
+    MapSkins,
+    /// Booms.
+    DamageSkins,
+}
+
+impl TftCategory {
+    const ALL: [TftCategory; 3] = [
+        TftCategory::Companions,
+        TftCategory::MapSkins,
+        TftCategory::DamageSkins,
+    ];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            TftCategory::Companions => "companions.json",
+            TftCategory::MapSkins => "tftmapskins.json",
+            TftCategory::DamageSkins => "tftdamageskins.json",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTftItem {
+    item_id: i32,
+    #[serde(default)]
+    content_id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    loadouts_icon: Option<String>,
+    #[serde(default)]
+    rarity: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TftItem {
+    pub item_id: i32,
+    pub content_id: String,
+    pub name: String,
+    pub loadouts_icon: Option<String>,
+    pub rarity: Option<String>,
+}
+
+impl From<RawTftItem> for TftItem {
+    fn from(raw: RawTftItem) -> Self {
+        Self {
+            item_id: raw.item_id,
+            content_id: raw.content_id,
+            name: raw.name,
+            loadouts_icon: raw.loadouts_icon,
+            rarity: raw.rarity,
+        }
+    }
+}
+
+pub(crate) fn is_tft_enabled(app_handle: &AppHandle) -> AppResult<bool> {
+    let settings_state = app_handle.state::<crate::state::SettingsState>();
+    let settings = settings_state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+    Ok(settings.include_tft)
+}
+
+fn tft_metadata_path(layout: &WorkspaceLayout, category: TftCategory) -> PathBuf {
+    layout.tft_dir().join(category.file_name())
+}
+
+/// Download and prune the TFT cosmetic metadata into `data/tft/`. Returns the number of items saved.
+pub(crate) async fn download_tft_metadata(layout: &WorkspaceLayout) -> AppResult<usize> {
+    let tft_dir = layout.tft_dir();
+    fs::create_dir_all(&tft_dir)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to create TFT data dir: {}", e)))?;
+
+    let client = reqwest::Client::new();
+    let mut count = 0;
+
+    for category in TftCategory::ALL {
+        let url = TFT_METADATA_URL_TEMPLATE.replace("{file}", category.file_name());
+        tracing::info!("Downloading TFT metadata from {}", url);
+
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to fetch TFT metadata: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Other(format!(
+                "Failed to fetch TFT metadata {}: HTTP {}",
+                category.file_name(),
+                response.status()
+            )));
+        }
+
+        let raw_items: Vec<RawTftItem> = response
+            .json()
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to parse TFT metadata: {}", e)))?;
+
+        // The default entries (item id 0 or below) are not real cosmetics
+        let items: Vec<TftItem> = raw_items
+            .into_iter()
+            .filter(|item| item.item_id > 0)
+            .map(TftItem::from)
+            .collect();
+        count += items.len();
+
+        let json = serde_json::to_string_pretty(&items)
+            .map_err(|e| AppError::Other(format!("Failed to serialize TFT metadata: {}", e)))?;
+        fs::write(tft_metadata_path(layout, category), json)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to write TFT metadata: {}", e)))?;
+    }
+
+    Ok(count)
+}
+
+/// Returns true if the WAD file name belongs to TFT.
+pub(crate) fn is_tft_wad(file_name: &str) -> bool {
+    let file_name = file_name.to_lowercase();
+    TFT_WAD_PREFIXES
+        .iter()
+        .any(|prefix| file_name.starts_with(prefix))
+}
+
+#[tauri::command]
+pub async fn get_tft_items(
+    app_handle: AppHandle,
+    category: TftCategory,
+) -> IpcResult<Vec<TftItem>> {
+    get_tft_items_inner(&app_handle, category).await.into()
+}
+
+async fn get_tft_items_inner(
+    app_handle: &AppHandle,
+    category: TftCategory,
+) -> AppResult<Vec<TftItem>> {
+    let path = tft_metadata_path(&WorkspaceLayout::from_app(app_handle)?, category);
+
+    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to read TFT metadata: {}", e)))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::Other(format!("Failed to parse TFT metadata: {}", e)))
+}
+
+/// List the TFT WADs of the configured installation, relative to `Game/DATA/FINAL`.
+#[tauri::command]
+pub async fn get_tft_wads(app_handle: AppHandle) -> IpcResult<Vec<String>> {
+    get_tft_wads_inner(&app_handle).await.into()
+}
+
+async fn get_tft_wads_inner(app_handle: &AppHandle) -> AppResult<Vec<String>> {
+    let league_path = {
+        let settings_state = app_handle.state::<crate::state::SettingsState>();
+        let settings = settings_state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        settings
+            .league_path
+            .clone()
+            .ok_or(AppError::LeagueNotFound)?
+    };
+
+    let final_dir = league_path.join("Game").join("DATA").join("FINAL");
+    if !final_dir.exists() {
+        return Err(AppError::LeagueNotFound);
+    }
+
+    tokio::task::spawn_blocking(move || -> AppResult<Vec<String>> {
+        let mut wads = Vec::new();
+        crate::commands::patch_history::collect_wad_files(&final_dir, &mut wads)?;
+
+        let mut tft_wads: Vec<String> = wads
+            .iter()
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(is_tft_wad)
+            })
+            .filter_map(|path| path.strip_prefix(&final_dir).ok())
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .collect();
+        tft_wads.sort();
+
+        Ok(tft_wads)
+    })
+    .await
+    .map_err(|e| AppError::Other(format!("TFT WAD scan task failed: {}", e)))?
+}
@@ -0,0 +1,26 @@
+use crate::error::IpcResult;
+use crate::tools::{tool_status, Tool, ToolStatus, ToolsUpdateInfo};
+use tauri::AppHandle;
+
+/// Location and usability of every bundled external tool.
+#[tauri::command]
+pub fn get_tools_status(app_handle: AppHandle) -> IpcResult<Vec<ToolStatus>> {
+    IpcResult::ok(
+        Tool::ALL
+            .into_iter()
+            .map(|tool| tool_status(&app_handle, tool))
+            .collect(),
+    )
+}
+
+/// Compare the installed tools with the latest cslol-manager release.
+#[tauri::command]
+pub async fn check_tools_update(app_handle: AppHandle) -> IpcResult<ToolsUpdateInfo> {
+    crate::tools::check_tools_update(&app_handle).await.into()
+}
+
+/// Download the tools from the latest cslol-manager release.
+#[tauri::command]
+pub async fn update_tools(app_handle: AppHandle) -> IpcResult<ToolsUpdateInfo> {
+    crate::tools::update_tools(&app_handle).await.into()
+}
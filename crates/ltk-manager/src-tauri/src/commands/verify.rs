@@ -0,0 +1,152 @@
+//! Integrity check of the downloaded champion metadata.
+//!
+//! `verify_database` looks at `data/{id}/metadata.json` of every champion in the skin catalog
+//! and re-downloads only the files that are missing, unreadable or have no skins, so a
+//! corrupted file doesn't require wiping the workspace.
+
+use crate::cache::cached_catalog;
+use crate::commands::data::{
+    download_champion_metadata, load_champion_metadata, ChampionWithSkins,
+};
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::events::{emit, AppEvent, EventSource};
+use crate::jobs::{run_job, JobHandle, JobKind};
+use crate::workspace::WorkspaceLayout;
+use serde::Serialize;
+use tauri::AppHandle;
+use tokio::fs;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MetadataProblem {
+    Missing,
+    /// The file can't be read or parsed.
+    Unreadable,
+    NoSkins,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenMetadata {
+    pub champion_id: i32,
+    pub champion_name: String,
+    pub problem: MetadataProblem,
+    /// Whether the re-downloaded file passed the check.
+    pub repaired: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub checked: usize,
+    pub broken: Vec<BrokenMetadata>,
+}
+
+#[tauri::command]
+pub async fn verify_database(app_handle: AppHandle) -> IpcResult<VerifyReport> {
+    let app = &app_handle;
+    let result = run_job(
+        app,
+        JobKind::DatabaseRefresh,
+        "Verify skin database",
+        |job| async move { verify_database_inner(app, &job).await },
+    )
+    .await;
+
+    match &result {
+        Ok(report) => emit(
+            app,
+            AppEvent::completed(EventSource::Metadata, summary(report)),
+        ),
+        Err(e) => emit(app, AppEvent::error(EventSource::Metadata, e.to_string())),
+    }
+    result.into()
+}
+
+async fn verify_database_inner(app_handle: &AppHandle, job: &JobHandle) -> AppResult<VerifyReport> {
+    let layout = WorkspaceLayout::from_app(app_handle)?;
+    let catalog = cached_catalog(app_handle, &layout).await?;
+    if catalog.is_empty() {
+        return Err(AppError::Other(
+            "Skin database not found. Please refresh the database first.".to_string(),
+        ));
+    }
+
+    let champions: Vec<ChampionWithSkins> = catalog
+        .champions_with_skins()
+        .into_iter()
+        .filter(|c| c.id > 0)
+        .collect();
+
+    job.status("Checking champion metadata...");
+    let mut broken = Vec::new();
+    let mut broken_champions = Vec::new();
+    for champion in &champions {
+        let Some(problem) = check_metadata(&layout, champion.id).await else {
+            continue;
+        };
+        tracing::warn!(
+            "Metadata of {} ({}) is broken: {:?}",
+            champion.name,
+            champion.id,
+            problem
+        );
+
+        // The download skips existing files
+        let metadata_path = layout.champion_metadata_file(champion.id)?;
+        if problem != MetadataProblem::Missing {
+            fs::remove_file(&metadata_path).await.map_err(|e| {
+                AppError::Other(format!(
+                    "Failed to remove broken metadata of {}: {}",
+                    champion.name, e
+                ))
+            })?;
+        }
+
+        broken.push(BrokenMetadata {
+            champion_id: champion.id,
+            champion_name: champion.name.clone(),
+            problem,
+            repaired: false,
+        });
+        broken_champions.push(champion.clone());
+    }
+
+    if !broken_champions.is_empty() {
+        let downloaded =
+            download_champion_metadata(app_handle, job, &layout, &broken_champions).await;
+        crate::cache::invalidate(app_handle);
+        downloaded?;
+
+        for entry in &mut broken {
+            entry.repaired = check_metadata(&layout, entry.champion_id).await.is_none();
+        }
+    }
+
+    let report = VerifyReport {
+        checked: champions.len(),
+        broken,
+    };
+    tracing::info!("{}", summary(&report));
+    Ok(report)
+}
+
+/// What is wrong with the metadata of `champion_id`, if anything.
+async fn check_metadata(layout: &WorkspaceLayout, champion_id: i32) -> Option<MetadataProblem> {
+    match load_champion_metadata(layout, champion_id).await {
+        Ok(None) => Some(MetadataProblem::Missing),
+        Ok(Some(metadata)) if metadata.skins.is_empty() => Some(MetadataProblem::NoSkins),
+        Ok(Some(_)) => None,
+        Err(_) => Some(MetadataProblem::Unreadable),
+    }
+}
+
+fn summary(report: &VerifyReport) -> String {
+    let repaired = report.broken.iter().filter(|b| b.repaired).count();
+    format!(
+        "Checked {} champions, repaired {} of {} broken metadata files",
+        report.checked,
+        repaired,
+        report.broken.len()
+    )
+}
@@ -0,0 +1,286 @@
+use crate::commands::mod_skin::{begin_overlay_run, download_and_extract, run_overlay};
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::events::{emit, AppEvent, EventSource};
+use crate::jobs::{run_job_with_token, run_resumable_job, JobKind, JobResume};
+use crate::workspace::WorkspaceLayout;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle};
+use tokio::fs;
+use tracing::{error, info};
+
+const COSMETIC_METADATA_URL_TEMPLATE: &str =
+    "https://raw.communitydragon.org/latest/plugins/rcp-be-lol-game-data/global/vi_vn/v1/{file}";
+const COSMETIC_DOWNLOAD_URL_TEMPLATE: &str =
+    "https://github.com/Alban1911/LeagueSkins/raw/main/{category}/{id}/{id}.{ext}";
+const COSMETIC_METADATA_FILENAME: &str = "metadata.json";
+
+/// Non-champion content that is swapped with its own mods, parallel to champion skins.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CosmeticCategory {
+    WardSkin,
+    Emote,
+}
+
+impl CosmeticCategory {
+    const ALL: [CosmeticCategory; 2] = [CosmeticCategory::WardSkin, CosmeticCategory::Emote];
+
+    /// Directory below `data/` holding the metadata and one folder per downloaded mod.
+    pub(crate) fn dir_name(self) -> &'static str {
+        match self {
+            CosmeticCategory::WardSkin => "wards",
+            CosmeticCategory::Emote => "emotes",
+        }
+    }
+
+    fn metadata_file_name(self) -> &'static str {
+        match self {
+            CosmeticCategory::WardSkin => "ward-skins.json",
+            CosmeticCategory::Emote => "summoner-emotes.json",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawCosmetic {
+    id: i32,
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "wardImagePath", alias = "inventoryIcon")]
+    image_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CosmeticItem {
+    pub id: i32,
+    pub name: String,
+    pub image_path: Option<String>,
+    /// Whether the mod for this item has been downloaded to the workspace.
+    #[serde(default, skip_deserializing)]
+    pub downloaded: bool,
+}
+
+/// Download ward skin and emote metadata into `data/wards` and `data/emotes`.
+/// Returns the number of items saved.
+pub(crate) async fn download_cosmetic_metadata(layout: &WorkspaceLayout) -> AppResult<usize> {
+    let client = reqwest::Client::new();
+    let mut count = 0;
+
+    for category in CosmeticCategory::ALL {
+        let url = COSMETIC_METADATA_URL_TEMPLATE.replace("{file}", category.metadata_file_name());
+        tracing::info!("Downloading {:?} metadata from {}", category, url);
+
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to fetch {}: {}", url, e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Other(format!(
+                "Failed to fetch {}: HTTP {}",
+                url,
+                response.status()
+            )));
+        }
+
+        let raw_items: Vec<RawCosmetic> = response
+            .json()
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to parse {}: {}", url, e)))?;
+
+        // Id 0 is the default ward / empty emote slot
+        let items: Vec<CosmeticItem> = raw_items
+            .into_iter()
+            .filter(|item| item.id > 0)
+            .map(|item| CosmeticItem {
+                id: item.id,
+                name: item.name,
+                image_path: item.image_path,
+                downloaded: false,
+            })
+            .collect();
+        count += items.len();
+
+        let dir = layout.cosmetic_dir(category);
+        fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to create {:?}: {}", dir, e)))?;
+
+        let json = serde_json::to_string_pretty(&items)
+            .map_err(|e| AppError::Other(format!("Failed to serialize metadata: {}", e)))?;
+        fs::write(dir.join(COSMETIC_METADATA_FILENAME), json)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to write metadata: {}", e)))?;
+    }
+
+    Ok(count)
+}
+
+pub(crate) async fn load_cosmetics(
+    app_handle: &AppHandle,
+    category: CosmeticCategory,
+) -> AppResult<Vec<CosmeticItem>> {
+    let dir = WorkspaceLayout::from_app(app_handle)?.cosmetic_dir(category);
+    let metadata_path = dir.join(COSMETIC_METADATA_FILENAME);
+
+    if !tokio::fs::try_exists(&metadata_path).await.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&metadata_path)
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to read metadata file: {}", e)))?;
+
+    let mut items: Vec<CosmeticItem> = serde_json::from_str(&content)
+        .map_err(|e| AppError::Other(format!("Failed to parse metadata file: {}", e)))?;
+
+    for item in &mut items {
+        item.downloaded = dir.join(item.id.to_string()).exists();
+    }
+
+    Ok(items)
+}
+
+#[tauri::command]
+pub async fn get_ward_skins(app_handle: AppHandle) -> IpcResult<Vec<CosmeticItem>> {
+    load_cosmetics(&app_handle, CosmeticCategory::WardSkin)
+        .await
+        .into()
+}
+
+#[tauri::command]
+pub async fn get_emotes(app_handle: AppHandle) -> IpcResult<Vec<CosmeticItem>> {
+    load_cosmetics(&app_handle, CosmeticCategory::Emote)
+        .await
+        .into()
+}
+
+#[command]
+pub async fn download_ward_skin(
+    app_handle: AppHandle,
+    category: CosmeticCategory,
+    item_id: i32,
+) -> IpcResult<String> {
+    let download = run_resumable_job(
+        &app_handle,
+        JobKind::Download,
+        format!("Download {:?} {}", category, item_id),
+        JobResume::Cosmetic { category, item_id },
+        |_| download_ward_skin_inner(app_handle.clone(), category, item_id),
+    );
+    match download.await {
+        Ok(msg) => IpcResult::Ok { value: msg },
+        Err(e) => IpcResult::Err {
+            error: crate::error::AppErrorResponse::new(
+                crate::error::ErrorCode::Unknown,
+                format!("{:#}", e),
+            ),
+        },
+    }
+}
+
+pub(crate) async fn download_ward_skin_inner(
+    app_handle: AppHandle,
+    category: CosmeticCategory,
+    item_id: i32,
+) -> anyhow::Result<String> {
+    let layout = WorkspaceLayout::from_app(&app_handle).context("Failed to get data directory")?;
+    let dir = layout.cosmetic_dir(category);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .with_context(|| format!("Failed to create {:?}", dir))?;
+
+    let extract_to = layout.cosmetic_mod_dir(category, item_id)?;
+    if extract_to.exists() {
+        info!(
+            "{:?} {} already downloaded at {:?}",
+            category, item_id, extract_to
+        );
+        return Ok(format!(
+            "{} already downloaded at {:?}",
+            item_id, extract_to
+        ));
+    }
+
+    let urls: Vec<String> = ["zip", "fantome"]
+        .iter()
+        .map(|ext| {
+            COSMETIC_DOWNLOAD_URL_TEMPLATE
+                .replace("{category}", category.dir_name())
+                .replace("{id}", &item_id.to_string())
+                .replace("{ext}", ext)
+        })
+        .collect();
+
+    download_and_extract(&urls, &dir, &extract_to)
+        .await
+        .with_context(|| format!("Failed to download {:?} {}", category, item_id))?;
+
+    Ok(format!(
+        "Downloaded and extracted {} to {:?}",
+        item_id, extract_to
+    ))
+}
+
+#[command]
+pub async fn run_ward_skin(
+    app_handle: AppHandle,
+    category: CosmeticCategory,
+    item_id: i32,
+) -> IpcResult<String> {
+    let cancel_token = match begin_overlay_run(&app_handle).await {
+        Ok(token) => token,
+        Err(error) => return IpcResult::Err { error },
+    };
+
+    let run = run_job_with_token(
+        &app_handle,
+        JobKind::Overlay,
+        format!("Run {:?} {}", category, item_id),
+        None,
+        cancel_token.clone(),
+        |_| run_ward_skin_inner(app_handle.clone(), category, item_id, cancel_token),
+    );
+    match run.await {
+        Ok(msg) => IpcResult::Ok { value: msg },
+        Err(e) => {
+            error!("run_ward_skin error: {:#}", e);
+            emit(
+                &app_handle,
+                AppEvent::error(EventSource::Overlay, format!("{:#}", e)),
+            );
+            IpcResult::Err {
+                error: crate::error::AppErrorResponse::new(
+                    crate::error::ErrorCode::Unknown,
+                    format!("{:#}", e),
+                ),
+            }
+        }
+    }
+}
+
+async fn run_ward_skin_inner(
+    app_handle: AppHandle,
+    category: CosmeticCategory,
+    item_id: i32,
+    cancel_token: tokio_util::sync::CancellationToken,
+) -> anyhow::Result<String> {
+    let layout = WorkspaceLayout::from_app(&app_handle).context("Failed to get data directory")?;
+    let dir = layout.cosmetic_dir(category);
+    let mod_dir = layout.cosmetic_mod_dir(category, item_id)?;
+
+    if !mod_dir.exists() {
+        return Err(anyhow::anyhow!(
+            "Mod directory not found. Please download first: {:?}",
+            mod_dir
+        ));
+    }
+
+    run_overlay(&app_handle, &dir, &item_id.to_string(), cancel_token).await?;
+
+    Ok(format!("{:?} run active", category))
+}
@@ -0,0 +1,35 @@
+use crate::commands::mod_skin::stop_all_mods;
+use crate::error::{AppResult, IpcResult};
+use crate::watchdog::{self, CrashSuspect};
+use tauri::AppHandle;
+
+/// The mods of the last game that crashed shortly after starting, if not dismissed.
+#[tauri::command]
+pub fn get_crash_suspect(app_handle: AppHandle) -> IpcResult<Option<CrashSuspect>> {
+    watchdog::suspect(&app_handle).into()
+}
+
+#[tauri::command]
+pub fn dismiss_crash_suspect(app_handle: AppHandle) -> IpcResult<()> {
+    watchdog::dismiss_suspect(&app_handle).into()
+}
+
+/// Stop the running overlay and keep auto-apply from starting one until the next game started.
+#[tauri::command]
+pub async fn run_vanilla_next_game(app_handle: AppHandle) -> IpcResult<()> {
+    run_vanilla_next_game_inner(app_handle).await.into()
+}
+
+async fn run_vanilla_next_game_inner(app_handle: AppHandle) -> AppResult<()> {
+    watchdog::set_vanilla_next_game(&app_handle, true)?;
+    if let IpcResult::Err { error } = stop_all_mods(app_handle).await {
+        tracing::warn!("Failed to stop mods for a vanilla game: {}", error.message);
+    }
+    tracing::info!("Next game runs without mods");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_vanilla_next_game(app_handle: AppHandle) -> IpcResult<()> {
+    watchdog::set_vanilla_next_game(&app_handle, false).into()
+}
@@ -18,10 +18,16 @@ pub enum ErrorCode {
     InvalidPath,
     /// Requested mod was not found
     ModNotFound,
+    /// Champion id is not in the skin database
+    ChampionNotFound,
+    /// Skin id is not in the skin database or belongs to another champion
+    SkinNotFound,
     /// Validation failed (e.g., invalid settings)
     ValidationFailed,
     /// Internal state error (e.g., mutex poisoned)
     InternalState,
+    /// The operation was cancelled by the user
+    Cancelled,
     /// Unknown/unclassified error
     Unknown,
 }
@@ -135,12 +141,21 @@ pub enum AppError {
     #[error("Mod not found: {0}")]
     ModNotFound(String),
 
+    #[error("Champion not found: {0}")]
+    ChampionNotFound(i32),
+
+    #[error("Skin {skin_id} not found for champion {champion_id}")]
+    SkinNotFound { champion_id: i32, skin_id: i32 },
+
     #[error("Validation failed: {0}")]
     ValidationFailed(String),
 
     #[error("Internal state error: {0}")]
     InternalState(String),
 
+    #[error("Operation cancelled")]
+    Cancelled,
+
     #[error("{0}")]
     Other(String),
 }
@@ -170,16 +185,53 @@ impl From<AppError> for AppErrorResponse {
                     .with_context(serde_json::json!({ "modId": id }))
             }
 
+            AppError::ChampionNotFound(id) => AppErrorResponse::new(
+                ErrorCode::ChampionNotFound,
+                format!("Champion not found: {}", id),
+            )
+            .with_context(serde_json::json!({ "championId": id })),
+
+            AppError::SkinNotFound {
+                champion_id,
+                skin_id,
+            } => AppErrorResponse::new(
+                ErrorCode::SkinNotFound,
+                format!("Skin {} not found for champion {}", skin_id, champion_id),
+            )
+            .with_context(serde_json::json!({ "championId": champion_id, "skinId": skin_id })),
+
             AppError::ValidationFailed(msg) => {
                 AppErrorResponse::new(ErrorCode::ValidationFailed, msg)
             }
 
             AppError::InternalState(msg) => AppErrorResponse::new(ErrorCode::InternalState, msg),
 
+            AppError::Cancelled => {
+                AppErrorResponse::new(ErrorCode::Cancelled, "Operation cancelled")
+            }
+
             AppError::Other(msg) => AppErrorResponse::new(ErrorCode::Unknown, msg),
         }
     }
 }
 
+/// Errors of anyhow-based commands keep their code when they wrap an [`AppError`],
+/// everything else is reported as [`ErrorCode::Unknown`] with the full context chain.
+impl From<anyhow::Error> for AppErrorResponse {
+    fn from(error: anyhow::Error) -> Self {
+        let message = format!("{:#}", error);
+        match error.downcast::<AppError>() {
+            Ok(app_error) => app_error.into(),
+            Err(_) => AppErrorResponse::new(ErrorCode::Unknown, message),
+        }
+    }
+}
+
+impl From<crate::jobs::JobCancelled> for AppError {
+    fn from(_: crate::jobs::JobCancelled) -> Self {
+        AppError::Cancelled
+    }
+}
+
 /// Convenience type alias for internal Result usage
 pub type AppResult<T> = Result<T, AppError>;
@@ -0,0 +1,117 @@
+//! Typed events sent from the backend to the frontend.
+//!
+//! Every event goes out on the single [`APP_EVENT`] channel as an [`AppEvent`], tagged with
+//! `type` so the frontend can match on it exhaustively instead of listening to ad-hoc names.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Name of the Tauri event all [`AppEvent`]s are emitted on.
+pub const APP_EVENT: &str = "app-event";
+
+/// The operation an event belongs to.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EventSource {
+    SkinDatabase,
+    Metadata,
+    PatchSnapshot,
+    Download,
+    Overlay,
+    ModIndex,
+    ChampSelect,
+    AppUpdate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AppEvent {
+    Progress {
+        source: EventSource,
+        processed: usize,
+        total: usize,
+        message: String,
+    },
+    Status {
+        source: EventSource,
+        message: String,
+    },
+    Error {
+        source: EventSource,
+        message: String,
+    },
+    Completed {
+        source: EventSource,
+        message: String,
+    },
+    /// A job was started, made progress or finished.
+    Job { job: crate::jobs::Job },
+    /// The mod an overlay is being built for is known to crash the game.
+    CompatWarnings {
+        mod_id: String,
+        warnings: Vec<crate::commands::CompatWarning>,
+    },
+    /// The game crashed shortly after starting with an overlay.
+    GameCrashed {
+        suspect: crate::watchdog::CrashSuspect,
+    },
+    /// A bisect round finished, or the bisect isolated the crashing mod.
+    Bisect { status: crate::bisect::BisectStatus },
+    /// A peer sent a profile that waits for the user to accept it.
+    PeerOffer { offer: crate::peer_sync::PeerOffer },
+    /// A backend log line, sent while the frontend is subscribed to logs.
+    Log {
+        level: crate::logs::LogLevel,
+        target: String,
+        message: String,
+        timestamp: String,
+    },
+}
+
+impl AppEvent {
+    pub fn progress(
+        source: EventSource,
+        processed: usize,
+        total: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::Progress {
+            source,
+            processed,
+            total,
+            message: message.into(),
+        }
+    }
+
+    pub fn status(source: EventSource, message: impl Into<String>) -> Self {
+        Self::Status {
+            source,
+            message: message.into(),
+        }
+    }
+
+    pub fn error(source: EventSource, message: impl Into<String>) -> Self {
+        Self::Error {
+            source,
+            message: message.into(),
+        }
+    }
+
+    pub fn completed(source: EventSource, message: impl Into<String>) -> Self {
+        Self::Completed {
+            source,
+            message: message.into(),
+        }
+    }
+}
+
+/// Send an event to the frontend. Failures are logged, an event never fails a command.
+pub fn emit(app_handle: &AppHandle, event: AppEvent) {
+    if let Err(e) = app_handle.emit(APP_EVENT, &event) {
+        tracing::warn!("Failed to emit {:?}: {}", event, e);
+    }
+}
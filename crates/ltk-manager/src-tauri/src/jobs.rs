@@ -0,0 +1,391 @@
+//! Registry of long-running backend operations.
+//!
+//! Downloads, refreshes and overlay builds run through [`run_job`], which registers
+//! them in the [`JobRegistry`] with an id, a state and a cancellation token. Every change is
+//! sent to the frontend as an [`AppEvent::Job`].
+//!
+//! The job list is persisted to the workspace. Jobs that were still running when the app
+//! exited are marked [`JobState::Interrupted`] on the next start, and resumed if they
+//! carry a [`JobResume`].
+
+use crate::commands::CosmeticCategory;
+use crate::events::{emit, AppEvent};
+use crate::workspace::WorkspaceLayout;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+pub type JobId = u64;
+
+/// Number of finished jobs kept for the activity panel.
+const MAX_FINISHED_JOBS: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JobKind {
+    DatabaseRefresh,
+    Download,
+    Overlay,
+    ModIndex,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JobState {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+    /// The app exited while the job was running.
+    Interrupted,
+}
+
+/// How to restart a job that was interrupted by an app exit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum JobResume {
+    Skin {
+        champion_id: i32,
+        skin_id: i32,
+    },
+    Cosmetic {
+        category: CosmeticCategory,
+        item_id: i32,
+    },
+    BrowserMod {
+        mod_id: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgress {
+    pub processed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: JobId,
+    pub kind: JobKind,
+    pub label: String,
+    pub state: JobState,
+    pub progress: Option<JobProgress>,
+    /// Latest status message, or the error of a failed job.
+    pub message: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    #[serde(default)]
+    pub resume: Option<JobResume>,
+}
+
+/// Returned by a job's future when it was cancelled through [`JobRegistry::cancel`].
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("Operation cancelled")]
+pub struct JobCancelled;
+
+struct JobEntry {
+    job: Job,
+    cancel_token: CancellationToken,
+}
+
+#[derive(Default)]
+struct JobRegistryInner {
+    next_id: JobId,
+    jobs: BTreeMap<JobId, JobEntry>,
+}
+
+/// Application state holding every running job and the most recent finished ones.
+#[derive(Default)]
+pub struct JobRegistry(Mutex<JobRegistryInner>);
+
+impl JobRegistry {
+    fn lock(&self) -> std::sync::MutexGuard<'_, JobRegistryInner> {
+        // A panic while holding the lock can't leave the map half-updated, keep going
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn start(
+        &self,
+        kind: JobKind,
+        label: String,
+        resume: Option<JobResume>,
+        cancel_token: CancellationToken,
+    ) -> (JobId, Job) {
+        let mut inner = self.lock();
+        inner.next_id += 1;
+        let id = inner.next_id;
+
+        let job = Job {
+            id,
+            kind,
+            label,
+            state: JobState::Running,
+            progress: None,
+            message: None,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+            resume,
+        };
+        inner.jobs.insert(
+            id,
+            JobEntry {
+                job: job.clone(),
+                cancel_token,
+            },
+        );
+
+        (id, job)
+    }
+
+    fn update(&self, id: JobId, f: impl FnOnce(&mut Job)) -> Option<Job> {
+        let mut inner = self.lock();
+        let entry = inner.jobs.get_mut(&id)?;
+        f(&mut entry.job);
+        let job = entry.job.clone();
+
+        if job.state != JobState::Running {
+            let finished: Vec<JobId> = inner
+                .jobs
+                .iter()
+                .filter(|(_, entry)| entry.job.state != JobState::Running)
+                .map(|(id, _)| *id)
+                .collect();
+            let excess = finished.len().saturating_sub(MAX_FINISHED_JOBS);
+            for id in finished.into_iter().take(excess) {
+                inner.jobs.remove(&id);
+            }
+        }
+
+        Some(job)
+    }
+
+    pub fn list(&self) -> Vec<Job> {
+        self.lock().jobs.values().map(|e| e.job.clone()).collect()
+    }
+
+    pub fn get(&self, id: JobId) -> Option<Job> {
+        self.lock().jobs.get(&id).map(|e| e.job.clone())
+    }
+
+    /// Add the jobs of a previous session, keeping ids unique.
+    fn restore(&self, jobs: Vec<Job>) {
+        let mut inner = self.lock();
+        for job in jobs {
+            inner.next_id = inner.next_id.max(job.id);
+            inner.jobs.insert(
+                job.id,
+                JobEntry {
+                    job,
+                    cancel_token: CancellationToken::new(),
+                },
+            );
+        }
+    }
+
+    /// Request cancellation of a running job. Returns false if the job isn't running.
+    pub fn cancel(&self, id: JobId) -> bool {
+        let inner = self.lock();
+        match inner.jobs.get(&id) {
+            Some(entry) if entry.job.state == JobState::Running => {
+                entry.cancel_token.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Handle given to a job's future to report its progress.
+#[derive(Clone)]
+pub struct JobHandle {
+    app_handle: AppHandle,
+    id: JobId,
+}
+
+impl JobHandle {
+    pub fn progress(&self, processed: usize, total: usize, message: impl Into<String>) {
+        let message = message.into();
+        self.update(|job| {
+            job.progress = Some(JobProgress { processed, total });
+            job.message = Some(message);
+        });
+    }
+
+    pub fn status(&self, message: impl Into<String>) {
+        let message = message.into();
+        self.update(|job| job.message = Some(message));
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Job)) {
+        let registry = self.app_handle.state::<JobRegistry>();
+        if let Some(job) = registry.update(self.id, f) {
+            emit(&self.app_handle, AppEvent::Job { job });
+        }
+    }
+}
+
+fn job_history_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    WorkspaceLayout::from_app(app_handle)
+        .ok()
+        .map(|layout| layout.job_history_file())
+}
+
+/// Write the job list to the workspace. Skipped while no workspace is configured.
+fn persist_jobs(app_handle: &AppHandle) {
+    let Some(path) = job_history_path(app_handle) else {
+        return;
+    };
+
+    let jobs = app_handle.state::<JobRegistry>().list();
+    let result = serde_json::to_string_pretty(&jobs)
+        .map_err(std::io::Error::from)
+        .and_then(|json| std::fs::write(&path, json));
+    if let Err(e) = result {
+        tracing::warn!("Failed to save job history to {:?}: {}", path, e);
+    }
+}
+
+/// Load the job history of the previous session into the registry.
+///
+/// Jobs that were still running are marked interrupted. Returns the ones that can be resumed.
+pub fn restore_job_history(app_handle: &AppHandle) -> Vec<JobResume> {
+    let Some(path) = job_history_path(app_handle) else {
+        return Vec::new();
+    };
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    let mut jobs: Vec<Job> = match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+    {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable job history {:?}: {}", path, e);
+            return Vec::new();
+        }
+    };
+
+    let mut resumable = Vec::new();
+    for job in &mut jobs {
+        if job.state != JobState::Running {
+            continue;
+        }
+
+        tracing::info!("Job {} ({}) was interrupted", job.id, job.label);
+        job.state = JobState::Interrupted;
+        job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        job.message = Some(match &job.resume {
+            Some(resume) => {
+                resumable.push(resume.clone());
+                "Interrupted by app exit, restarted".to_string()
+            }
+            None => "Interrupted by app exit".to_string(),
+        });
+    }
+
+    app_handle.state::<JobRegistry>().restore(jobs);
+    persist_jobs(app_handle);
+
+    resumable
+}
+
+/// Run `f` as a job of `kind`, see [`run_job_with_token`].
+pub async fn run_job<T, E, F, Fut>(
+    app_handle: &AppHandle,
+    kind: JobKind,
+    label: impl Into<String>,
+    f: F,
+) -> Result<T, E>
+where
+    F: FnOnce(JobHandle) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: std::fmt::Display + From<JobCancelled>,
+{
+    run_job_with_token(app_handle, kind, label, None, CancellationToken::new(), f).await
+}
+
+/// Run `f` as a job that is restarted from `resume` if the app exits before it finishes.
+pub async fn run_resumable_job<T, E, F, Fut>(
+    app_handle: &AppHandle,
+    kind: JobKind,
+    label: impl Into<String>,
+    resume: JobResume,
+    f: F,
+) -> Result<T, E>
+where
+    F: FnOnce(JobHandle) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: std::fmt::Display + From<JobCancelled>,
+{
+    run_job_with_token(
+        app_handle,
+        kind,
+        label,
+        Some(resume),
+        CancellationToken::new(),
+        f,
+    )
+    .await
+}
+
+/// Register a job, run `f` until it finishes or the job is cancelled, and record the outcome.
+///
+/// Cancelling `cancel_token` from elsewhere cancels the job as well.
+pub async fn run_job_with_token<T, E, F, Fut>(
+    app_handle: &AppHandle,
+    kind: JobKind,
+    label: impl Into<String>,
+    resume: Option<JobResume>,
+    cancel_token: CancellationToken,
+    f: F,
+) -> Result<T, E>
+where
+    F: FnOnce(JobHandle) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: std::fmt::Display + From<JobCancelled>,
+{
+    let (id, job) =
+        app_handle
+            .state::<JobRegistry>()
+            .start(kind, label.into(), resume, cancel_token.clone());
+    emit(app_handle, AppEvent::Job { job });
+    persist_jobs(app_handle);
+
+    let handle = JobHandle {
+        app_handle: app_handle.clone(),
+        id,
+    };
+
+    let result = tokio::select! {
+        result = f(handle.clone()) => result,
+        _ = cancel_token.cancelled() => Err(JobCancelled.into()),
+    };
+
+    let (state, message) = match &result {
+        Ok(_) => (JobState::Completed, None),
+        Err(_) if cancel_token.is_cancelled() => (JobState::Cancelled, None),
+        Err(e) => (JobState::Failed, Some(format!("{:#}", e))),
+    };
+    handle.update(|job| {
+        job.state = state;
+        if message.is_some() {
+            job.message = message;
+        }
+        job.finished_at = Some(chrono::Utc::now().to_rfc3339());
+    });
+    persist_jobs(app_handle);
+
+    result
+}
@@ -0,0 +1,307 @@
+//! League Client (LCU) integration for applying skins automatically in champion select.
+//!
+//! The client writes a `lockfile` with its local API port and password to the install
+//! directory while it runs. [`spawn_auto_apply`] polls the champion select session through
+//! that API. Once the local player locks in a champion, it builds and runs a random skin
+//! while the randomizer is on, or the skin bound to the champion in `skinBindings`.
+
+use crate::commands::mod_skin::run_skin;
+use crate::error::{AppError, AppResult, IpcResult};
+use crate::events::{emit, AppEvent, EventSource};
+use crate::randomizer::pick_random_skin;
+use crate::state::SettingsState;
+use crate::watchdog::vanilla_next_game;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const LOCKFILE_NAME: &str = "lockfile";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Connection details the client publishes in its lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LcuCredentials {
+    pub port: u16,
+    pub password: String,
+}
+
+impl LcuCredentials {
+    /// Parse `name:pid:port:password:protocol`.
+    pub fn parse(lockfile: &str) -> Option<Self> {
+        let mut parts = lockfile.trim().split(':');
+        let port = parts.nth(2)?.parse().ok()?;
+        let password = parts.next()?.to_string();
+
+        Some(Self { port, password })
+    }
+
+    /// Read the lockfile of a running client in the League install directory.
+    pub fn from_install_dir(league_path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(league_path.join(LOCKFILE_NAME)).ok()?;
+        Self::parse(&content)
+    }
+}
+
+pub struct LcuClient {
+    client: reqwest::Client,
+    credentials: LcuCredentials,
+}
+
+impl LcuClient {
+    pub fn new(credentials: LcuCredentials) -> AppResult<Self> {
+        // The client serves its API with a self-signed certificate
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .timeout(Duration::from_secs(5))
+            .build()
+            .map_err(|e| AppError::Other(format!("Failed to create LCU client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            credentials,
+        })
+    }
+
+    /// The current champion select session, `None` outside of champion select.
+    pub async fn champ_select_session(&self) -> AppResult<Option<ChampSelectSession>> {
+        let url = format!(
+            "https://127.0.0.1:{}/lol-champ-select/v1/session",
+            self.credentials.port
+        );
+        let response = self
+            .client
+            .get(&url)
+            .basic_auth("riot", Some(&self.credentials.password))
+            .send()
+            .await
+            .map_err(|e| AppError::Other(format!("LCU request failed: {}", e)))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response
+            .error_for_status()
+            .map_err(|e| AppError::Other(format!("LCU request failed: {}", e)))?;
+
+        response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| AppError::Other(format!("Invalid champion select session: {}", e)))
+    }
+
+    /// Ids of the champions in the current free rotation.
+    pub async fn free_champion_ids(&self) -> AppResult<Vec<i32>> {
+        let url = format!(
+            "https://127.0.0.1:{}/lol-champions/v1/owned-champions-minimal",
+            self.credentials.port
+        );
+        let champions: Vec<ChampionMinimal> = self
+            .client
+            .get(&url)
+            .basic_auth("riot", Some(&self.credentials.password))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AppError::Other(format!("LCU request failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::Other(format!("Invalid champion list: {}", e)))?;
+
+        Ok(champions
+            .into_iter()
+            .filter(|c| c.free_to_play)
+            .map(|c| c.id)
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChampionMinimal {
+    id: i32,
+    #[serde(default)]
+    free_to_play: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectSession {
+    pub local_player_cell_id: i64,
+    #[serde(default)]
+    pub my_team: Vec<ChampSelectPlayer>,
+    #[serde(default)]
+    pub actions: Vec<Vec<ChampSelectAction>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectPlayer {
+    pub cell_id: i64,
+    /// Locked in champion, 0 before lock-in.
+    #[serde(default)]
+    pub champion_id: i32,
+    /// Hovered champion, 0 if none.
+    #[serde(default)]
+    pub champion_pick_intent: i32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampSelectAction {
+    pub actor_cell_id: i64,
+    pub champion_id: i32,
+    pub completed: bool,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl ChampSelectSession {
+    /// Champion the local player has locked in, if any.
+    pub fn locked_champion(&self) -> Option<i32> {
+        self.actions
+            .iter()
+            .flatten()
+            .find(|action| {
+                action.actor_cell_id == self.local_player_cell_id
+                    && action.kind == "pick"
+                    && action.completed
+                    && action.champion_id > 0
+            })
+            .map(|action| action.champion_id)
+    }
+
+    /// Champion the local player has locked in or is hovering, if any.
+    pub fn current_champion(&self) -> Option<i32> {
+        if let Some(champion_id) = self.locked_champion() {
+            return Some(champion_id);
+        }
+
+        let player = self
+            .my_team
+            .iter()
+            .find(|player| player.cell_id == self.local_player_cell_id)?;
+        [player.champion_id, player.champion_pick_intent]
+            .into_iter()
+            .find(|&champion_id| champion_id > 0)
+    }
+}
+
+/// Poll champion select in the background for the lifetime of the app.
+pub fn spawn_auto_apply(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        // Champion the last skin was applied for, so each lock-in is handled once
+        let mut applied_for: Option<i32> = None;
+        let mut client: Option<LcuClient> = None;
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let Some(league_path) = auto_apply_league_path(&app_handle) else {
+                applied_for = None;
+                continue;
+            };
+            let Some(credentials) = LcuCredentials::from_install_dir(&league_path) else {
+                applied_for = None;
+                continue;
+            };
+
+            // The password changes whenever the client restarts
+            if client.as_ref().map(|c| &c.credentials) != Some(&credentials) {
+                client = match LcuClient::new(credentials) {
+                    Ok(new_client) => Some(new_client),
+                    Err(e) => {
+                        tracing::warn!("{}", e);
+                        continue;
+                    }
+                };
+            }
+            let Some(client) = &client else {
+                continue;
+            };
+
+            let session = client.champ_select_session().await;
+            let locked = match session {
+                Ok(session) => session.and_then(|s| s.locked_champion()),
+                Err(e) => {
+                    tracing::debug!("Champion select poll failed: {}", e);
+                    continue;
+                }
+            };
+
+            let Some(champion_id) = locked else {
+                applied_for = None;
+                continue;
+            };
+            if applied_for == Some(champion_id) {
+                continue;
+            }
+            applied_for = Some(champion_id);
+
+            // Building the overlay takes a while, keep polling in the meantime
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                apply_for_champion(&app_handle, champion_id).await;
+            });
+        }
+    });
+}
+
+/// The League path while auto-apply or the randomizer is enabled.
+fn auto_apply_league_path(app_handle: &AppHandle) -> Option<PathBuf> {
+    let state = app_handle.state::<SettingsState>();
+    let settings = state.0.lock().ok()?;
+    if !settings.auto_apply_enabled && settings.random_skin.is_none() {
+        return None;
+    }
+    settings.league_path.clone()
+}
+
+async fn apply_for_champion(app_handle: &AppHandle, champion_id: i32) {
+    if vanilla_next_game(app_handle) {
+        tracing::info!(
+            "Locked in champion {}, skipping mods for this game",
+            champion_id
+        );
+        return;
+    }
+
+    let random_skin = pick_random_skin(app_handle, champion_id)
+        .await
+        .inspect_err(|e| tracing::warn!("Failed to pick a random skin: {}", e))
+        .ok()
+        .flatten();
+    // Bound champions are skipped by the randomizer, so this only runs without a random pick
+    let skin_id = random_skin.or_else(|| {
+        let state = app_handle.state::<SettingsState>();
+        let settings = state.0.lock().ok()?;
+        settings.skin_bindings.get(&champion_id).copied()
+    });
+    let Some(skin_id) = skin_id else {
+        tracing::info!("Locked in champion {}, no skin configured", champion_id);
+        return;
+    };
+
+    tracing::info!(
+        "Locked in champion {}, applying skin {}",
+        champion_id,
+        skin_id
+    );
+    emit(
+        app_handle,
+        AppEvent::status(
+            EventSource::ChampSelect,
+            format!("Applying skin {} for the locked in champion", skin_id),
+        ),
+    );
+
+    // run_skin reports its own failures
+    if let IpcResult::Ok { value } = run_skin(app_handle.clone(), champion_id, skin_id).await {
+        emit(
+            app_handle,
+            AppEvent::completed(EventSource::ChampSelect, value),
+        );
+    }
+}
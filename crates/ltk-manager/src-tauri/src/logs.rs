@@ -0,0 +1,174 @@
+//! Backend log file and live log streaming to the frontend.
+//!
+//! [`LogLayer`] is added to the tracing subscriber at startup. Once [`attach`] has run it
+//! appends every event to `ltk-manager.log` in the app log directory, and emits events at or
+//! above the level requested through `subscribe_logs` as [`AppEvent::Log`].
+
+use crate::events::{AppEvent, APP_EVENT};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+const LOG_FILENAME: &str = "ltk-manager.log";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<&Level> for LogLevel {
+    fn from(level: &Level) -> Self {
+        match *level {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            Level::INFO => LogLevel::Info,
+            Level::DEBUG => LogLevel::Debug,
+            Level::TRACE => LogLevel::Trace,
+        }
+    }
+}
+
+#[derive(Default)]
+struct LogSink {
+    app_handle: Option<AppHandle>,
+    file: Option<File>,
+    file_path: Option<PathBuf>,
+    /// Most verbose level streamed to the frontend, `None` while nobody listens.
+    stream_level: Option<LogLevel>,
+}
+
+fn sink() -> &'static Mutex<LogSink> {
+    static SINK: OnceLock<Mutex<LogSink>> = OnceLock::new();
+    SINK.get_or_init(Default::default)
+}
+
+thread_local! {
+    /// Set while an event is being written, so logging done by the emit itself is dropped.
+    static IN_LOG_LAYER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Open the log file and start forwarding events to `app_handle`.
+pub fn attach(app_handle: &AppHandle) {
+    let file_path = app_handle
+        .path()
+        .app_log_dir()
+        .ok()
+        .map(|dir| dir.join(LOG_FILENAME));
+
+    let file = file_path.as_ref().and_then(|path| {
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| File::create(path));
+        result
+            .inspect_err(|e| eprintln!("Failed to create log file {:?}: {}", path, e))
+            .ok()
+    });
+
+    let mut sink = sink().lock().unwrap_or_else(|e| e.into_inner());
+    sink.app_handle = Some(app_handle.clone());
+    sink.file_path = file.as_ref().and(file_path);
+    sink.file = file;
+}
+
+/// Stream events at `level` and above to the frontend, or stop streaming with `None`.
+pub fn set_stream_level(level: Option<LogLevel>) {
+    sink()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .stream_level = level;
+}
+
+pub fn log_file_path() -> Option<PathBuf> {
+    sink()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .file_path
+        .clone()
+}
+
+/// Collects the message and the other fields of an event into one line.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+pub struct LogLayer;
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if IN_LOG_LAYER.with(|flag| flag.replace(true)) {
+            return;
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let level = LogLevel::from(metadata.level());
+        let message = visitor.message + &visitor.fields;
+        let timestamp = chrono::Utc::now().to_rfc3339();
+
+        let mut sink = sink().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(file) = &mut sink.file {
+            let _ = writeln!(
+                file,
+                "{} {:>5} {}: {}",
+                timestamp,
+                metadata.level(),
+                metadata.target(),
+                message
+            );
+        }
+
+        if let (Some(app_handle), Some(stream_level)) = (&sink.app_handle, sink.stream_level) {
+            if level <= stream_level {
+                // Logging a failed emit from here would recurse, drop it
+                let _ = app_handle.emit(
+                    APP_EVENT,
+                    AppEvent::Log {
+                        level,
+                        target: metadata.target().to_string(),
+                        message,
+                        timestamp,
+                    },
+                );
+            }
+        }
+        drop(sink);
+
+        IN_LOG_LAYER.with(|flag| flag.set(false));
+    }
+}
@@ -7,13 +7,26 @@ use tauri::Manager;
 use tauri_plugin_fs::FsExt;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+mod bisect;
+mod cache;
 mod commands;
 mod error;
+mod events;
+mod jobs;
+mod lcu;
+mod logs;
 pub mod patcher;
+mod peer_sync;
+mod randomizer;
+mod server;
 mod state;
+mod tools;
 mod utils;
+mod watchdog;
+mod workspace;
 
 use error::IpcResult;
+use jobs::JobRegistry;
 use patcher::PatcherState;
 use state::SettingsState;
 
@@ -61,6 +74,7 @@ fn main() {
     // Initialize logging
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer())
+        .with(logs::LogLayer)
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "ltk_manager=debug,tauri=info".into()),
@@ -69,14 +83,22 @@ fn main() {
 
     tracing::info!("Starting LTK Manager");
 
+    let server_options = server::ServerOptions::from_args(std::env::args().skip(1));
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(commands::handle_quick_switch_hotkey)
+                .build(),
+        )
         .setup(|app| {
             let app_handle = app.handle();
+            logs::attach(app_handle);
 
             // Create individual states
             let settings_state = SettingsState::new(app_handle);
@@ -93,9 +115,44 @@ fn main() {
                 }
             }
 
+            let server_options = server_options.or_else(|| {
+                let enabled = settings_state.0.lock().is_ok_and(|s| s.http_api_enabled);
+                enabled.then(server::ServerOptions::default)
+            });
+
             // Manage each state separately
             app.manage(settings_state);
             app.manage(patcher_state);
+            app.manage(JobRegistry::default());
+            app.manage(randomizer::RandomSkinHistory::default());
+            app.manage(peer_sync::PeerSyncState::default());
+            app.manage(watchdog::WatchdogState::default());
+            app.manage(bisect::BisectState::default());
+            app.manage(cache::DataCache::default());
+            app.manage(commands::AppUpdateState::default());
+
+            commands::register_quick_switch_hotkeys(app_handle);
+            lcu::spawn_auto_apply(app_handle);
+            watchdog::spawn_watchdog(app_handle);
+            // Keep a chunk manifest of every installed game version for the patch history browser
+            commands::spawn_version_watcher(app_handle);
+
+            // Pick up downloads that were interrupted when the app last exited
+            for resume in jobs::restore_job_history(app_handle) {
+                tauri::async_runtime::spawn(commands::resume_job(app_handle.clone(), resume));
+            }
+
+            if let Some(options) = server_options {
+                // Headless: the window stays hidden while other frontends drive the app
+                if options.headless {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.hide();
+                    }
+                }
+                if let Err(e) = server::start(app_handle, options) {
+                    tracing::error!("Failed to start HTTP API: {}", e);
+                }
+            }
 
             // Auto-check for database updates in background
             let app_handle_clone = app_handle.clone();
@@ -115,22 +172,82 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             // App
             commands::get_app_info,
+            commands::check_app_update,
+            commands::install_app_update,
             // Settings
             commands::get_settings,
             commands::save_settings,
             commands::auto_detect_league_path,
             commands::validate_league_path,
             commands::check_setup_required,
+            // Jobs
+            commands::list_jobs,
+            commands::get_job,
+            commands::cancel_job,
             // Patcher
             commands::start_patcher,
             commands::stop_patcher,
             commands::get_patcher_status,
+            // Tools
+            commands::get_tools_status,
+            commands::check_tools_update,
+            commands::update_tools,
+            commands::run_self_test,
+            // Logs
+            commands::subscribe_logs,
+            commands::get_log_file_path,
             // Data
             commands::refresh_skin_database,
             commands::get_skin_database,
             commands::get_champions_with_skins,
             commands::check_and_update_database,
             commands::get_champion_skins,
+            commands::get_skin_info,
+            commands::search_skins,
+            commands::verify_database,
+            commands::get_champion_info,
+            commands::get_free_rotation,
+            // Patch History
+            commands::list_patch_snapshots,
+            commands::diff_patch_snapshots,
+            // TFT
+            commands::get_tft_items,
+            commands::get_tft_wads,
+            // Mod List
+            commands::export_mod_list,
+            commands::apply_mod_list,
+            // Mod Annotations
+            commands::get_mod_annotations,
+            commands::get_mod_annotation,
+            commands::set_mod_annotation,
+            commands::delete_mod_annotation,
+            // Known Crashes
+            commands::refresh_compat_list,
+            commands::check_mod_compat,
+            commands::record_crash,
+            commands::list_crash_reports,
+            commands::delete_crash_report,
+            // Crash Watchdog
+            commands::get_crash_suspect,
+            commands::dismiss_crash_suspect,
+            commands::run_vanilla_next_game,
+            commands::cancel_vanilla_next_game,
+            // Rollback
+            commands::get_last_good_overlay,
+            commands::rollback_to_last_good,
+            // Bisect
+            commands::start_bisect,
+            commands::get_bisect_status,
+            commands::report_bisect_result,
+            commands::cancel_bisect,
+            // Import & Export
+            commands::detect_import_sources,
+            commands::scan_import_source,
+            commands::import_mods,
+            commands::export_to_cslol,
+            // Backup
+            commands::backup_to,
+            commands::restore_from,
             // Merge Data
             commands::prune_all_metadata,
             // Images
@@ -140,6 +257,46 @@ fn main() {
             commands::mod_skin::download_skin,
             commands::mod_skin::run_skin,
             commands::mod_skin::stop_all_mods,
+            // Champion Select
+            commands::get_current_champion,
+            // Skin Bindings
+            commands::get_skin_bindings,
+            commands::set_skin_binding,
+            commands::clear_skin_binding,
+            // Randomizer
+            commands::enable_random_skin,
+            commands::disable_random_skin,
+            commands::run_random_skin,
+            // Peer Sync
+            commands::start_peer_sync,
+            commands::stop_peer_sync,
+            commands::list_peers,
+            commands::send_profile_to_peer,
+            commands::respond_to_peer_offer,
+            // Plugins
+            commands::list_plugins,
+            commands::set_plugin_enabled,
+            commands::run_plugin_command,
+            // Quick Switch
+            commands::list_quick_switches,
+            commands::trigger_quick_switch,
+            commands::create_quick_switch,
+            commands::update_quick_switch,
+            commands::delete_quick_switch,
+            // Mod Browser
+            commands::refresh_mod_index,
+            commands::search_mods,
+            commands::download_browser_mod,
+            // Announcer Packs
+            commands::import_announcer_pack,
+            commands::list_announcer_packs,
+            commands::delete_announcer_pack,
+            commands::set_active_announcer,
+            // Ward Skins & Emotes
+            commands::get_ward_skins,
+            commands::get_emotes,
+            commands::download_ward_skin,
+            commands::run_ward_skin,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
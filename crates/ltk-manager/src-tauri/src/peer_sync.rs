@@ -0,0 +1,409 @@
+//! Sending profiles to other LTK Manager instances on the local network.
+//!
+//! While peer sync is running, every instance announces itself with a UDP broadcast on
+//! [`DISCOVERY_PORT`] and accepts profile bundles over TCP on [`SYNC_PORT`]. A received
+//! bundle is only held as a [`PeerOffer`] and announced to the frontend; nothing is
+//! installed until the user accepts it.
+//!
+//! TCP messages are a big-endian `u32` length followed by that many bytes of JSON.
+//!
+//! Discovery uses a broadcast on its own port rather than mDNS. Windows already runs an mDNS
+//! responder on port 5353, and a plain broadcast reaches the same local subnet without
+//! sharing that port or adding a DNS-SD implementation.
+//!
+//! Anyone on the network can connect, so the listener limits what a peer can hold on to:
+//! a few connections at a time, a deadline for every message, and a small number of pending
+//! offers that expire when the user doesn't answer them.
+
+use crate::commands::ModListManifest;
+use crate::error::{AppError, AppResult};
+use crate::events::{emit, AppEvent};
+use crate::state::QuickSwitch;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+pub const DISCOVERY_PORT: u16 = 5311;
+pub const SYNC_PORT: u16 = 5312;
+const SERVICE_NAME: &str = "ltk-manager";
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(3);
+/// Peers that haven't announced themselves for this long are considered gone.
+const PEER_TIMEOUT: Duration = Duration::from_secs(10);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time a peer has to send or receive a whole message.
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_MESSAGE_LEN: usize = 1024 * 1024;
+/// Connections handled at once, further ones are closed right away.
+const MAX_CONNECTIONS: usize = 4;
+const MAX_PENDING_OFFERS: usize = 8;
+/// Offers the user didn't answer in this time are dropped.
+const OFFER_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Everything a friend needs to reproduce a setup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileBundle {
+    pub mod_list: ModListManifest,
+    #[serde(default)]
+    pub skin_bindings: BTreeMap<i32, i32>,
+    #[serde(default)]
+    pub quick_switches: Vec<QuickSwitch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo {
+    pub name: String,
+    /// `ip:port` to pass to `send_profile_to_peer`.
+    pub address: String,
+}
+
+/// A bundle received from a peer that waits for the user to accept or reject it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerOffer {
+    pub id: u64,
+    pub from_name: String,
+    pub from_address: String,
+    pub bundle: ProfileBundle,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Announcement {
+    service: String,
+    /// Random per run, so an instance ignores its own broadcasts.
+    instance_id: String,
+    name: String,
+    port: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OfferMessage {
+    from_name: String,
+    bundle: ProfileBundle,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OfferReply {
+    received: bool,
+}
+
+struct Peer {
+    info: PeerInfo,
+    last_seen: Instant,
+}
+
+struct PendingOffer {
+    offer: PeerOffer,
+    received_at: Instant,
+}
+
+#[derive(Default)]
+struct PeerSyncInner {
+    cancel_token: Option<CancellationToken>,
+    peers: HashMap<IpAddr, Peer>,
+    offers: BTreeMap<u64, PendingOffer>,
+    next_offer_id: u64,
+}
+
+#[derive(Default)]
+pub struct PeerSyncState(Mutex<PeerSyncInner>);
+
+impl PeerSyncState {
+    fn lock(&self) -> AppResult<std::sync::MutexGuard<'_, PeerSyncInner>> {
+        self.0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))
+    }
+}
+
+impl PeerSyncInner {
+    fn expire_offers(&mut self) {
+        self.offers
+            .retain(|_, pending| pending.received_at.elapsed() < OFFER_TIMEOUT);
+    }
+
+    /// Hold `offer` until the user answers it, `false` if too many offers are pending.
+    ///
+    /// A newer offer from the same address replaces the older one.
+    fn add_offer(&mut self, offer: PeerOffer) -> bool {
+        self.expire_offers();
+        self.offers
+            .retain(|_, pending| pending.offer.from_address != offer.from_address);
+        if self.offers.len() >= MAX_PENDING_OFFERS {
+            return false;
+        }
+
+        self.offers.insert(
+            offer.id,
+            PendingOffer {
+                offer,
+                received_at: Instant::now(),
+            },
+        );
+        true
+    }
+}
+
+/// Start announcing this instance as `name` and accepting offers, restarting if running.
+pub async fn start(app_handle: &AppHandle, name: String) -> AppResult<()> {
+    stop(app_handle)?;
+
+    let discovery =
+        UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT))).await?;
+    discovery.set_broadcast(true)?;
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, SYNC_PORT))).await?;
+
+    let cancel_token = CancellationToken::new();
+    app_handle.state::<PeerSyncState>().lock()?.cancel_token = Some(cancel_token.clone());
+
+    let announcement = Announcement {
+        service: SERVICE_NAME.to_string(),
+        instance_id: uuid::Uuid::new_v4().to_string(),
+        name,
+        port: SYNC_PORT,
+    };
+    tauri::async_runtime::spawn(run_discovery(
+        app_handle.clone(),
+        discovery,
+        announcement,
+        cancel_token.clone(),
+    ));
+    tauri::async_runtime::spawn(run_listener(app_handle.clone(), listener, cancel_token));
+
+    tracing::info!("Peer sync started");
+    Ok(())
+}
+
+pub fn stop(app_handle: &AppHandle) -> AppResult<()> {
+    let state = app_handle.state::<PeerSyncState>();
+    let mut inner = state.lock()?;
+    if let Some(token) = inner.cancel_token.take() {
+        token.cancel();
+        inner.peers.clear();
+        inner.offers.clear();
+        tracing::info!("Peer sync stopped");
+    }
+    Ok(())
+}
+
+/// Peers that announced themselves recently, sorted by name.
+pub fn peers(app_handle: &AppHandle) -> AppResult<Vec<PeerInfo>> {
+    let state = app_handle.state::<PeerSyncState>();
+    let mut inner = state.lock()?;
+    inner
+        .peers
+        .retain(|_, peer| peer.last_seen.elapsed() < PEER_TIMEOUT);
+
+    let mut peers: Vec<PeerInfo> = inner.peers.values().map(|p| p.info.clone()).collect();
+    peers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(peers)
+}
+
+/// Remove and return a pending offer, `None` if it is unknown or expired.
+pub fn take_offer(app_handle: &AppHandle, id: u64) -> AppResult<Option<PeerOffer>> {
+    let state = app_handle.state::<PeerSyncState>();
+    let mut inner = state.lock()?;
+    inner.expire_offers();
+    Ok(inner.offers.remove(&id).map(|pending| pending.offer))
+}
+
+/// Send `bundle` to the peer at `address`, which shows it to its user as an offer.
+pub async fn send_bundle(address: &str, from_name: String, bundle: ProfileBundle) -> AppResult<()> {
+    let address: SocketAddr = address
+        .parse()
+        .map_err(|_| AppError::ValidationFailed(format!("Invalid peer address: {}", address)))?;
+
+    let mut stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(address))
+        .await
+        .map_err(|_| AppError::Other(format!("Timed out connecting to {}", address)))??;
+    write_message(&mut stream, &OfferMessage { from_name, bundle }).await?;
+
+    let reply: OfferReply = read_message(&mut stream).await?;
+    if !reply.received {
+        return Err(AppError::Other(format!("{} rejected the profile", address)));
+    }
+    Ok(())
+}
+
+async fn run_discovery(
+    app_handle: AppHandle,
+    socket: UdpSocket,
+    announcement: Announcement,
+    cancel_token: CancellationToken,
+) {
+    let Ok(payload) = serde_json::to_vec(&announcement) else {
+        return;
+    };
+    let broadcast = SocketAddr::from((Ipv4Addr::BROADCAST, DISCOVERY_PORT));
+    let mut interval = tokio::time::interval(ANNOUNCE_INTERVAL);
+    let mut buffer = [0u8; 1024];
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            _ = interval.tick() => {
+                if let Err(e) = socket.send_to(&payload, broadcast).await {
+                    tracing::debug!("Failed to announce peer sync: {}", e);
+                }
+            }
+            received = socket.recv_from(&mut buffer) => {
+                let Ok((len, from)) = received else {
+                    continue;
+                };
+                let Ok(peer) = serde_json::from_slice::<Announcement>(&buffer[..len]) else {
+                    continue;
+                };
+                if peer.service != SERVICE_NAME || peer.instance_id == announcement.instance_id {
+                    continue;
+                }
+
+                let state = app_handle.state::<PeerSyncState>();
+                let guard = state.lock();
+                if let Ok(mut inner) = guard {
+                    inner.peers.insert(
+                        from.ip(),
+                        Peer {
+                            info: PeerInfo {
+                                name: peer.name,
+                                address: SocketAddr::new(from.ip(), peer.port).to_string(),
+                            },
+                            last_seen: Instant::now(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+async fn run_listener(
+    app_handle: AppHandle,
+    listener: TcpListener,
+    cancel_token: CancellationToken,
+) {
+    let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    loop {
+        let accepted = tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            accepted = listener.accept() => accepted,
+        };
+        let Ok((stream, from)) = accepted else {
+            continue;
+        };
+        let Ok(permit) = connections.clone().try_acquire_owned() else {
+            tracing::debug!("Too many peer connections, closing the one from {}", from);
+            continue;
+        };
+
+        let app_handle = app_handle.clone();
+        let cancel_token = cancel_token.clone();
+        tauri::async_runtime::spawn(async move {
+            let _permit = permit;
+            tokio::select! {
+                _ = cancel_token.cancelled() => {}
+                result = receive_offer(&app_handle, stream, from) => {
+                    if let Err(e) = result {
+                        tracing::warn!("Failed to receive profile from {}: {}", from, e);
+                    }
+                }
+            }
+        });
+    }
+}
+
+async fn receive_offer(
+    app_handle: &AppHandle,
+    mut stream: TcpStream,
+    from: SocketAddr,
+) -> AppResult<()> {
+    let message: OfferMessage = read_message(&mut stream).await?;
+
+    let offer = {
+        let state = app_handle.state::<PeerSyncState>();
+        let mut inner = state.lock()?;
+        inner.next_offer_id += 1;
+        let offer = PeerOffer {
+            id: inner.next_offer_id,
+            from_name: message.from_name,
+            from_address: from.ip().to_string(),
+            bundle: message.bundle,
+        };
+        inner.add_offer(offer.clone()).then_some(offer)
+    };
+    let Some(offer) = offer else {
+        tracing::warn!("Too many pending profiles, rejecting the one from {}", from);
+        return write_message(&mut stream, &OfferReply { received: false }).await;
+    };
+
+    tracing::info!(
+        "Received a profile with {} mod(s) from {} ({})",
+        offer.bundle.mod_list.mods.len(),
+        offer.from_name,
+        offer.from_address
+    );
+    emit(app_handle, AppEvent::PeerOffer { offer });
+
+    write_message(&mut stream, &OfferReply { received: true }).await
+}
+
+async fn write_message<W: AsyncWrite + Unpin, T: Serialize>(
+    writer: &mut W,
+    message: &T,
+) -> AppResult<()> {
+    let payload = serde_json::to_vec(message)?;
+    with_deadline(async {
+        writer.write_u32(payload.len() as u32).await?;
+        writer.write_all(&payload).await?;
+        writer.flush().await?;
+        Ok(())
+    })
+    .await
+}
+
+async fn read_message<R: AsyncRead + Unpin, T: for<'de> Deserialize<'de>>(
+    reader: &mut R,
+) -> AppResult<T> {
+    let payload = with_deadline(async {
+        let len = reader.read_u32().await? as usize;
+        if len > MAX_MESSAGE_LEN {
+            return Err(AppError::ValidationFailed(format!(
+                "Peer message of {} bytes is too large",
+                len
+            )));
+        }
+
+        // Grow the buffer as data arrives instead of trusting the announced length
+        let mut payload = Vec::new();
+        reader.take(len as u64).read_to_end(&mut payload).await?;
+        if payload.len() != len {
+            return Err(AppError::Other(
+                "Peer closed the connection early".to_string(),
+            ));
+        }
+        Ok(payload)
+    })
+    .await?;
+    Ok(serde_json::from_slice(&payload)?)
+}
+
+async fn with_deadline<T>(future: impl std::future::Future<Output = AppResult<T>>) -> AppResult<T> {
+    tokio::time::timeout(MESSAGE_TIMEOUT, future)
+        .await
+        .map_err(|_| {
+            AppError::Other(format!(
+                "Peer didn't finish its message within {}s",
+                MESSAGE_TIMEOUT.as_secs()
+            ))
+        })?
+}
@@ -0,0 +1,82 @@
+//! Random skin selection for the skin randomizer.
+//!
+//! Recently picked skins are remembered per champion for the lifetime of the app, so the
+//! same skin isn't picked twice in a row while the pool has alternatives.
+
+use crate::commands::get_skin_database_inner;
+use crate::error::{AppError, AppResult};
+use crate::state::SettingsState;
+use rand::seq::IndexedRandom;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Number of recent picks per champion that are excluded from the next pick.
+const HISTORY_LEN: usize = 3;
+
+#[derive(Default)]
+pub struct RandomSkinHistory(Mutex<HashMap<i32, VecDeque<i32>>>);
+
+/// Pick a random skin for `champion_id`, `None` if the randomizer is off, the champion is out
+/// of scope or has a skin binding, or the pool has no skin of the champion.
+pub async fn pick_random_skin(app_handle: &AppHandle, champion_id: i32) -> AppResult<Option<i32>> {
+    let config = {
+        let state = app_handle.state::<SettingsState>();
+        let settings = state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+        if settings.skin_bindings.contains_key(&champion_id) {
+            return Ok(None);
+        }
+        settings.random_skin.clone()
+    };
+    let Some(config) = config.filter(|c| c.champion_scope.contains(champion_id)) else {
+        return Ok(None);
+    };
+
+    let mut candidates: Vec<i32> = if config.pool.is_empty() {
+        // Every skin of the champion except the base skin, which needs no mod
+        get_skin_database_inner(app_handle)
+            .await?
+            .keys()
+            .filter_map(|id| id.parse::<i32>().ok())
+            .filter(|id| id / 1000 == champion_id && id % 1000 != 0)
+            .collect()
+    } else {
+        config
+            .pool
+            .into_iter()
+            .filter(|id| id / 1000 == champion_id)
+            .collect()
+    };
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let history_state = app_handle.state::<RandomSkinHistory>();
+    let mut history = history_state
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?;
+    let recent = history.entry(champion_id).or_default();
+
+    // Keep at least one candidate available, however small the pool is
+    let excluded = recent.len().min(candidates.len().saturating_sub(1));
+    let recent_slice: Vec<i32> = recent.iter().rev().take(excluded).copied().collect();
+    let available: Vec<i32> = candidates
+        .iter()
+        .copied()
+        .filter(|id| !recent_slice.contains(id))
+        .collect();
+
+    let Some(&skin_id) = available.choose(&mut rand::rng()) else {
+        return Ok(None);
+    };
+
+    recent.push_back(skin_id);
+    if recent.len() > HISTORY_LEN {
+        recent.pop_front();
+    }
+
+    Ok(Some(skin_id))
+}
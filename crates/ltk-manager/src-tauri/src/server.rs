@@ -0,0 +1,421 @@
+//! Localhost HTTP API for controlling the manager from other frontends.
+//!
+//! Started with `--server [--port <port>]`, which also keeps the main window hidden, or next
+//! to the window when `httpApiEnabled` is set in the settings. Every
+//! request must carry `Authorization: Bearer <token>`, the token is generated on each start
+//! and written to `server-token` in the app data directory.
+//! Headers must arrive within 10 seconds and bodies are limited to 1 MB, the token is checked
+//! before any body is read.
+//!
+//! Routes mirror the IPC commands and answer with the same `{ "ok": true, "value": ... }` /
+//! `{ "ok": false, "error": ... }` payload:
+//!
+//! | Method | Path                                    | Command                    |
+//! |--------|-----------------------------------------|----------------------------|
+//! | GET    | `/api/info`                             | `get_app_info`             |
+//! | GET    | `/api/settings`                         | `get_settings`             |
+//! | GET    | `/api/jobs`                             | `list_jobs`                |
+//! | POST   | `/api/jobs/{id}/cancel`                 | `cancel_job`               |
+//! | GET    | `/api/champions`                        | `get_champions_with_skins` |
+//! | GET    | `/api/champions/{id}/skins`             | `get_champion_skins`       |
+//! | POST   | `/api/skins/{champion}/{skin}/download` | `download_skin`            |
+//! | POST   | `/api/skins/{champion}/{skin}/run`      | `run_skin`                 |
+//! | POST   | `/api/mods/stop`                        | `stop_all_mods`            |
+//! | GET    | `/api/quick-switch`                     | `list_quick_switches`      |
+//! | POST   | `/api/quick-switch/{name}`              | `trigger_quick_switch`     |
+//! | POST   | `/api/mod-list/apply`                   | `apply_mod_list`           |
+//! | GET    | `/api/patcher`                          | `get_patcher_status`       |
+//! | POST   | `/api/patcher/start`                    | `start_patcher`            |
+//! | POST   | `/api/patcher/stop`                     | `stop_patcher`             |
+
+use crate::commands;
+use crate::error::{AppError, AppErrorResponse, AppResult, ErrorCode, IpcResult};
+use crate::jobs::JobRegistry;
+use crate::patcher::PatcherState;
+use crate::state::SettingsState;
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use hyper_util::service::TowerToHyperService;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::net::TcpListener;
+
+pub const DEFAULT_PORT: u16 = 5310;
+const TOKEN_FILENAME: &str = "server-token";
+/// Time a client has to send the request line and headers.
+const HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+/// Read buffer size, which bounds the request line and headers.
+const MAX_HEADER_LEN: usize = 16 * 1024;
+const MAX_BODY_LEN: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerOptions {
+    pub port: u16,
+    /// Keep the main window hidden.
+    pub headless: bool,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_PORT,
+            headless: false,
+        }
+    }
+}
+
+impl ServerOptions {
+    /// Parse `--server` and `--port <port>` from the command line, `None` without `--server`.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        let mut enabled = false;
+        let mut port = DEFAULT_PORT;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--server" => enabled = true,
+                "--port" => match args.next().and_then(|value| value.parse().ok()) {
+                    Some(value) => port = value,
+                    None => tracing::warn!("Ignoring invalid --port, using {}", DEFAULT_PORT),
+                },
+                _ => {}
+            }
+        }
+
+        enabled.then_some(Self {
+            port,
+            headless: true,
+        })
+    }
+}
+
+/// Write a fresh token and start listening on localhost in the background.
+pub fn start(app_handle: &AppHandle, options: ServerOptions) -> AppResult<()> {
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let token_path = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Other(format!("Failed to get app data directory: {}", e)))?
+        .join(TOKEN_FILENAME);
+    if let Some(parent) = token_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&token_path, &token)?;
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(app_handle, options.port, token).await {
+            tracing::error!("HTTP API stopped: {}", e);
+        }
+    });
+
+    tracing::info!(
+        "HTTP API enabled on port {}, token written to {:?}",
+        options.port,
+        token_path
+    );
+    Ok(())
+}
+
+async fn serve(app_handle: AppHandle, port: u16, token: String) -> std::io::Result<()> {
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port))).await?;
+    let service = TowerToHyperService::new(router(ServerState {
+        app_handle,
+        token: token.into(),
+    }));
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let service = service.clone();
+        tauri::async_runtime::spawn(async move {
+            // Requests are short and infrequent, connections are not kept alive
+            let connection = hyper::server::conn::http1::Builder::new()
+                .timer(TokioTimer::new())
+                .header_read_timeout(HEADER_TIMEOUT)
+                .max_buf_size(MAX_HEADER_LEN)
+                .keep_alive(false)
+                .serve_connection(TokioIo::new(stream), service);
+            if let Err(e) = connection.await {
+                tracing::debug!("HTTP API connection failed: {}", e);
+            }
+        });
+    }
+}
+
+#[derive(Clone)]
+struct ServerState {
+    app_handle: AppHandle,
+    token: Arc<str>,
+}
+
+fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/api/info", get(info))
+        .route("/api/settings", get(settings))
+        .route("/api/jobs", get(jobs))
+        .route("/api/jobs/{id}/cancel", post(cancel_job))
+        .route("/api/champions", get(champions))
+        .route("/api/champions/{id}/skins", get(champion_skins))
+        .route("/api/skins/{champion}/{skin}/download", post(download_skin))
+        .route("/api/skins/{champion}/{skin}/run", post(run_skin))
+        .route("/api/mods/stop", post(stop_mods))
+        .route("/api/quick-switch", get(quick_switches))
+        .route("/api/quick-switch/{name}", post(trigger_quick_switch))
+        .route("/api/mod-list/apply", post(apply_mod_list))
+        .route("/api/patcher", get(patcher_status))
+        .route("/api/patcher/start", post(start_patcher))
+        .route("/api/patcher/stop", post(stop_patcher))
+        .fallback(not_found)
+        .layer(DefaultBodyLimit::max(MAX_BODY_LEN))
+        // Checked before any route runs or the body is read
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+/// A response that never reached a command, sent with a non-200 status.
+struct HttpError {
+    status: StatusCode,
+    message: String,
+}
+
+impl HttpError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for HttpError {
+    fn into_response(self) -> Response {
+        let code = match self.status {
+            StatusCode::INTERNAL_SERVER_ERROR => ErrorCode::Unknown,
+            _ => ErrorCode::ValidationFailed,
+        };
+        let result = IpcResult::<()>::err(AppErrorResponse::new(code, self.message));
+        (self.status, Json(result)).into_response()
+    }
+}
+
+type ApiResult = Result<Response, HttpError>;
+
+async fn require_token(State(state): State<ServerState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| tokens_match(provided, &state.token));
+    if !authorized {
+        return HttpError::new(StatusCode::UNAUTHORIZED, "Missing or invalid token")
+            .into_response();
+    }
+
+    tracing::debug!("HTTP API {} {}", request.method(), request.uri().path());
+    next.run(request).await
+}
+
+fn tokens_match(provided: &str, token: &str) -> bool {
+    // Compare without bailing out early so timing doesn't leak the token. An empty token would
+    // match an empty header, it never authorizes anything.
+    !token.is_empty()
+        && provided.len() == token.len()
+        && provided
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn not_found(request: Request) -> HttpError {
+    HttpError::new(
+        StatusCode::NOT_FOUND,
+        format!("No route for {} {}", request.method(), request.uri().path()),
+    )
+}
+
+async fn info() -> ApiResult {
+    reply(commands::get_app_info())
+}
+
+async fn settings(State(state): State<ServerState>) -> ApiResult {
+    reply(commands::get_settings(
+        state.app_handle.state::<SettingsState>(),
+    ))
+}
+
+async fn jobs(State(state): State<ServerState>) -> ApiResult {
+    reply(commands::list_jobs(state.app_handle.state::<JobRegistry>()))
+}
+
+async fn cancel_job(State(state): State<ServerState>, Path(id): Path<String>) -> ApiResult {
+    reply(commands::cancel_job(
+        state.app_handle.state::<JobRegistry>(),
+        parse_segment(&id)?,
+    ))
+}
+
+async fn champions(State(state): State<ServerState>) -> ApiResult {
+    reply(commands::get_champions_with_skins(state.app_handle).await)
+}
+
+async fn champion_skins(State(state): State<ServerState>, Path(id): Path<String>) -> ApiResult {
+    reply(commands::get_champion_skins(state.app_handle, parse_segment(&id)?, None).await)
+}
+
+async fn download_skin(
+    State(state): State<ServerState>,
+    Path((champion_id, skin_id)): Path<(String, String)>,
+) -> ApiResult {
+    reply(
+        commands::mod_skin::download_skin(
+            state.app_handle,
+            parse_segment(&champion_id)?,
+            parse_segment(&skin_id)?,
+        )
+        .await,
+    )
+}
+
+async fn run_skin(
+    State(state): State<ServerState>,
+    Path((champion_id, skin_id)): Path<(String, String)>,
+) -> ApiResult {
+    reply(
+        commands::mod_skin::run_skin(
+            state.app_handle,
+            parse_segment(&champion_id)?,
+            parse_segment(&skin_id)?,
+        )
+        .await,
+    )
+}
+
+async fn stop_mods(State(state): State<ServerState>) -> ApiResult {
+    reply(commands::mod_skin::stop_all_mods(state.app_handle).await)
+}
+
+async fn quick_switches(State(state): State<ServerState>) -> ApiResult {
+    reply(commands::list_quick_switches(
+        state.app_handle.state::<SettingsState>(),
+    ))
+}
+
+async fn trigger_quick_switch(
+    State(state): State<ServerState>,
+    Path(name): Path<String>,
+) -> ApiResult {
+    reply(commands::trigger_quick_switch(state.app_handle, name).await)
+}
+
+async fn apply_mod_list(State(state): State<ServerState>, body: Bytes) -> ApiResult {
+    reply(commands::apply_mod_list(state.app_handle, parse_body(&body)?).await)
+}
+
+async fn patcher_status(State(state): State<ServerState>) -> ApiResult {
+    reply(commands::get_patcher_status(
+        state.app_handle.state::<PatcherState>(),
+    ))
+}
+
+async fn start_patcher(State(state): State<ServerState>, body: Bytes) -> ApiResult {
+    let app = state.app_handle;
+    reply(commands::start_patcher(
+        parse_body(&body)?,
+        app.clone(),
+        app.state::<PatcherState>(),
+    ))
+}
+
+async fn stop_patcher(State(state): State<ServerState>) -> ApiResult {
+    reply(commands::stop_patcher(
+        state.app_handle.state::<PatcherState>(),
+    ))
+}
+
+fn parse_segment<T: std::str::FromStr>(segment: &str) -> Result<T, HttpError> {
+    segment.parse().map_err(|_| {
+        HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid path segment: {}", segment),
+        )
+    })
+}
+
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T, HttpError> {
+    serde_json::from_slice(body).map_err(|e| {
+        HttpError::new(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid request body: {}", e),
+        )
+    })
+}
+
+fn reply<T: Serialize>(result: IpcResult<T>) -> ApiResult {
+    Ok(Json(result).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("0123abcd", "0123abcd"));
+        assert!(!tokens_match("0123abce", "0123abcd"));
+        assert!(!tokens_match("", "0123abcd"));
+        assert!(!tokens_match("", ""));
+    }
+
+    #[test]
+    fn test_tokens_match_rejects_different_lengths() {
+        assert!(!tokens_match("0123abc", "0123abcd"));
+        assert!(!tokens_match("0123abcd0", "0123abcd"));
+        assert!(!tokens_match("0123abcd", "0123"));
+    }
+
+    #[test]
+    fn test_from_args_requires_server_flag() {
+        assert_eq!(ServerOptions::from_args(args(&[])), None);
+        assert_eq!(ServerOptions::from_args(args(&["--port", "8080"])), None);
+    }
+
+    #[test]
+    fn test_from_args_parses_port() {
+        let options = ServerOptions::from_args(args(&["--server"])).unwrap();
+        assert_eq!(options.port, DEFAULT_PORT);
+        assert!(options.headless);
+
+        let options = ServerOptions::from_args(args(&["--port", "8080", "--server"])).unwrap();
+        assert_eq!(options.port, 8080);
+    }
+
+    #[test]
+    fn test_from_args_ignores_invalid_port() {
+        for port_args in [&["--port", "abc"][..], &["--port", "70000"], &["--port"]] {
+            let mut all = args(&["--server"]);
+            all.extend(args(port_args));
+            assert_eq!(
+                ServerOptions::from_args(all).map(|o| o.port),
+                Some(DEFAULT_PORT),
+                "{:?}",
+                port_args
+            );
+        }
+    }
+}
@@ -1,4 +1,6 @@
+use crate::commands::PluginPermission;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
@@ -93,4 +95,96 @@ pub struct Settings {
     /// Workspace directory for storing skin IDs, cache, and other working files.
     pub workspace_path: Option<PathBuf>,
     pub first_run_complete: bool,
+    /// Number of per-version game chunk manifests kept for the patch history (defaults to 5).
+    #[serde(default)]
+    pub patch_history_limit: Option<usize>,
+    /// Download TFT cosmetic metadata and include TFT WADs when building overlays.
+    #[serde(default)]
+    pub include_tft: bool,
+    /// Imported announcer pack that is added to every overlay.
+    #[serde(default)]
+    pub active_announcer: Option<String>,
+    /// URL of the community mod index used by the mod browser.
+    #[serde(default)]
+    pub mod_index_url: Option<String>,
+    /// URL of a community list of mods known to crash, merged with the bundled list.
+    #[serde(default)]
+    pub compat_list_url: Option<String>,
+    /// Games with overlays that exit with an error within this many seconds count as crashes
+    /// (defaults to 90).
+    #[serde(default)]
+    pub crash_watchdog_seconds: Option<u64>,
+    /// Named skins that can be applied with one trigger, e.g. from a Stream Deck.
+    #[serde(default)]
+    pub quick_switches: Vec<QuickSwitch>,
+    /// Serve the localhost HTTP API while the window is open.
+    #[serde(default)]
+    pub http_api_enabled: bool,
+    /// Run the bound skin when a champion is locked in during champion select.
+    #[serde(default)]
+    pub auto_apply_enabled: bool,
+    /// Preferred skin id per champion id, applied on lock-in and skipped by the randomizer.
+    #[serde(default)]
+    pub skin_bindings: BTreeMap<i32, i32>,
+    /// Apply a random skin instead of a configured one, `None` while the randomizer is off.
+    #[serde(default)]
+    pub random_skin: Option<RandomSkinConfig>,
+    /// Release channel `check_app_update` looks for new versions on.
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    /// Plugin id to the permissions the user granted when enabling it.
+    #[serde(default)]
+    pub enabled_plugins: BTreeMap<String, Vec<PluginPermission>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Pre-releases, published ahead of the stable release.
+    Beta,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomSkinConfig {
+    pub champion_scope: ChampionScope,
+    /// Skin ids to pick from, every skin of the champion if empty.
+    #[serde(default)]
+    pub pool: Vec<i32>,
+}
+
+/// Champions the randomizer applies to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ChampionScope {
+    All,
+    Champions { champion_ids: Vec<i32> },
+}
+
+impl ChampionScope {
+    pub fn contains(&self, champion_id: i32) -> bool {
+        match self {
+            ChampionScope::All => true,
+            ChampionScope::Champions { champion_ids } => champion_ids.contains(&champion_id),
+        }
+    }
+}
+
+/// A skin that is built and run when its quick switch is triggered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickSwitch {
+    /// Name used to trigger the switch, compared case-insensitively.
+    pub name: String,
+    pub champion_id: i32,
+    pub skin_id: i32,
+    /// Global shortcut that triggers the switch, e.g. `Ctrl+Alt+1`.
+    #[serde(default)]
+    pub hotkey: Option<String>,
 }
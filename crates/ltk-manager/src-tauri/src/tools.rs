@@ -0,0 +1,412 @@
+//! External binaries bundled with the app: the mod-tools overlay builder and the patcher DLL.
+//!
+//! Tools are looked up in the app data directory, where [`update_tools`] installs copies
+//! downloaded from the latest cslol-manager release, then in the resource directory of the
+//! installed app, then next to the executable and in the source tree for development builds.
+//! [`tool_status`] additionally checks that a located tool can actually be used.
+//!
+//! The copies bundled with the app carry no version, so [`check_tools_update`] can only compare
+//! against the release once the tools have been downloaded; until then an update is offered.
+
+use crate::error::{AppError, AppResult};
+use crate::patcher::api::{PatcherApi, PATCHER_DLL_NAME};
+use crate::state::get_app_data_dir;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Tool {
+    ModTools,
+    PatcherDll,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 2] = [Tool::ModTools, Tool::PatcherDll];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Tool::ModTools => "mod-tools.exe",
+            Tool::PatcherDll => PATCHER_DLL_NAME,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ToolLocation {
+    /// Downloaded from the latest release into the app data directory.
+    Downloaded,
+    /// The resource directory of the installed app.
+    Bundled,
+    /// Next to the executable or in the source tree, for development builds.
+    Development,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStatus {
+    pub tool: Tool,
+    pub file_name: String,
+    pub path: Option<String>,
+    pub location: Option<ToolLocation>,
+    pub size: Option<u64>,
+    pub modified_at: Option<String>,
+    /// Why the tool can't be used and what to do about it, `None` if it is usable.
+    pub problem: Option<String>,
+}
+
+/// Latest release of cslol-manager, both tools are published with it.
+const TOOLS_RELEASE_API: &str =
+    "https://api.github.com/repos/LeagueToolkit/cslol-manager/releases/latest";
+const TOOLS_DIR_NAME: &str = "tools";
+const VERSION_FILENAME: &str = "version.json";
+
+/// Installed and latest release of the tools.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolsUpdateInfo {
+    /// Release the downloaded tools come from, `None` when the bundled copies are in use.
+    pub installed_version: Option<String>,
+    pub latest_version: String,
+    pub published_at: Option<String>,
+    pub release_url: String,
+    pub update_available: bool,
+}
+
+/// Written next to downloaded tools to remember which release they come from.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstalledTools {
+    version: String,
+    installed_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    published_at: Option<String>,
+    html_url: String,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+fn downloaded_tools_dir(app_handle: &AppHandle) -> Option<PathBuf> {
+    get_app_data_dir(app_handle).map(|dir| dir.join(TOOLS_DIR_NAME))
+}
+
+fn resource_dir(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    app_handle
+        .path()
+        .resource_dir()
+        .map_err(|e| AppError::Other(format!("Failed to get resource directory: {}", e)))
+}
+
+/// Places a tool is looked for, in order of preference.
+fn candidate_paths(app_handle: &AppHandle, tool: Tool) -> Vec<(PathBuf, ToolLocation)> {
+    let file_name = tool.file_name();
+    let mut candidates = Vec::new();
+
+    if let Some(dir) = downloaded_tools_dir(app_handle) {
+        candidates.push((dir.join(file_name), ToolLocation::Downloaded));
+    }
+
+    if let Ok(dir) = resource_dir(app_handle) {
+        candidates.push((dir.join(file_name), ToolLocation::Bundled));
+    }
+
+    if let Some(exe_dir) = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    {
+        candidates.push((exe_dir.join(file_name), ToolLocation::Development));
+    }
+
+    if let Ok(cwd) = std::env::current_dir() {
+        let source_resources = Path::new("src-tauri").join("resources");
+        candidates.push((
+            cwd.join(&source_resources).join(file_name),
+            ToolLocation::Development,
+        ));
+        candidates.push((
+            cwd.join("crates")
+                .join("ltk-manager")
+                .join(source_resources)
+                .join(file_name),
+            ToolLocation::Development,
+        ));
+    }
+
+    candidates
+}
+
+fn locate(app_handle: &AppHandle, tool: Tool) -> Option<(PathBuf, ToolLocation)> {
+    candidate_paths(app_handle, tool)
+        .into_iter()
+        .find(|(path, _)| path.is_file())
+}
+
+fn missing_tool_error(app_handle: &AppHandle, tool: Tool) -> AppError {
+    let expected = resource_dir(app_handle)
+        .map(|dir| dir.join(tool.file_name()).display().to_string())
+        .unwrap_or_else(|_| "the app's resource directory".to_string());
+
+    AppError::Other(format!(
+        "{} was not found. Reinstall LTK Manager or put the file at {}. \
+         Antivirus software sometimes removes it, check its quarantine as well.",
+        tool.file_name(),
+        expected
+    ))
+}
+
+/// Path of a tool, with an error explaining how to restore it if it is missing.
+pub fn resolve_tool(app_handle: &AppHandle, tool: Tool) -> AppResult<PathBuf> {
+    match locate(app_handle, tool) {
+        Some((path, location)) => {
+            if location == ToolLocation::Development {
+                tracing::info!("Using development copy of {}: {:?}", tool.file_name(), path);
+            }
+            Ok(path)
+        }
+        None => {
+            tracing::warn!(
+                "{} not found, looked in {:?}",
+                tool.file_name(),
+                candidate_paths(app_handle, tool)
+            );
+            Err(missing_tool_error(app_handle, tool))
+        }
+    }
+}
+
+/// Check that the file at `path` is a usable copy of `tool`.
+fn check_tool(tool: Tool, path: &Path) -> Result<(), String> {
+    let mut magic = [0u8; 2];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map_err(|e| format!("{} can't be read: {}", tool.file_name(), e))?;
+    if &magic != b"MZ" {
+        return Err(format!(
+            "{} is not a Windows executable, the file is damaged. Reinstall LTK Manager.",
+            tool.file_name()
+        ));
+    }
+
+    // An older DLL misses some of the exports the patcher calls
+    if tool == Tool::PatcherDll {
+        PatcherApi::load(path).map_err(|e| {
+            format!(
+                "{} is outdated or damaged ({}). Reinstall LTK Manager.",
+                tool.file_name(),
+                e
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+pub fn tool_status(app_handle: &AppHandle, tool: Tool) -> ToolStatus {
+    let mut status = ToolStatus {
+        tool,
+        file_name: tool.file_name().to_string(),
+        path: None,
+        location: None,
+        size: None,
+        modified_at: None,
+        problem: None,
+    };
+
+    let Some((path, location)) = locate(app_handle, tool) else {
+        status.problem = Some(missing_tool_error(app_handle, tool).to_string());
+        return status;
+    };
+
+    if let Ok(metadata) = std::fs::metadata(&path) {
+        status.size = Some(metadata.len());
+        status.modified_at = metadata
+            .modified()
+            .ok()
+            .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339());
+    }
+    status.problem = check_tool(tool, &path).err();
+    status.path = Some(path.display().to_string());
+    status.location = Some(location);
+
+    status
+}
+
+/// Release the downloaded tools come from, `None` if the app never downloaded them.
+fn installed_version(app_handle: &AppHandle) -> Option<String> {
+    let path = downloaded_tools_dir(app_handle)?.join(VERSION_FILENAME);
+    let contents = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str::<InstalledTools>(&contents) {
+        Ok(installed) => Some(installed.version),
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable {}: {}", VERSION_FILENAME, e);
+            None
+        }
+    }
+}
+
+async fn fetch_latest_release() -> AppResult<GithubRelease> {
+    // The GitHub API rejects requests without a user agent
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("ltk-manager/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| AppError::Other(format!("Failed to create HTTP client: {}", e)))?;
+
+    let response = client
+        .get(TOOLS_RELEASE_API)
+        .send()
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to check for tool updates: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(AppError::Other(format!(
+            "Failed to check for tool updates: HTTP {}",
+            response.status()
+        )));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to parse tools release: {}", e)))
+}
+
+fn update_info(installed_version: Option<String>, release: &GithubRelease) -> ToolsUpdateInfo {
+    ToolsUpdateInfo {
+        update_available: installed_version.as_deref() != Some(release.tag_name.as_str()),
+        installed_version,
+        latest_version: release.tag_name.clone(),
+        published_at: release.published_at.clone(),
+        release_url: release.html_url.clone(),
+    }
+}
+
+/// Compare the installed tools with the latest cslol-manager release.
+pub async fn check_tools_update(app_handle: &AppHandle) -> AppResult<ToolsUpdateInfo> {
+    let release = fetch_latest_release().await?;
+    Ok(update_info(installed_version(app_handle), &release))
+}
+
+/// Download the tools from the latest release into the app data directory.
+///
+/// The tools are extracted and checked in a `.partial` directory first, so a failed download
+/// leaves the tools in use untouched.
+pub async fn update_tools(app_handle: &AppHandle) -> AppResult<ToolsUpdateInfo> {
+    let release = fetch_latest_release().await?;
+    let asset = release
+        .assets
+        .iter()
+        .find(|asset| asset.name.to_ascii_lowercase().ends_with(".zip"))
+        .ok_or_else(|| {
+            AppError::Other(format!(
+                "Release {} has no zip archive to take the tools from",
+                release.tag_name
+            ))
+        })?;
+
+    let tools_dir = downloaded_tools_dir(app_handle)
+        .ok_or_else(|| AppError::Other("Failed to get app data directory".to_string()))?;
+    tracing::info!(
+        "Downloading tools {} from {}",
+        release.tag_name,
+        asset.browser_download_url
+    );
+
+    let bytes = reqwest::get(&asset.browser_download_url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::Other(format!("Failed to download tools: {}", e)))?
+        .bytes()
+        .await
+        .map_err(|e| AppError::Other(format!("Failed to download tools: {}", e)))?;
+
+    let partial_dir = tools_dir.with_file_name(format!("{}.partial", TOOLS_DIR_NAME));
+    if partial_dir.exists() {
+        std::fs::remove_dir_all(&partial_dir)?;
+    }
+    std::fs::create_dir_all(&partial_dir)?;
+
+    if let Err(e) = extract_tools(&bytes, &partial_dir) {
+        let _ = std::fs::remove_dir_all(&partial_dir);
+        return Err(e);
+    }
+
+    let installed = InstalledTools {
+        version: release.tag_name.clone(),
+        installed_at: chrono::Utc::now().to_rfc3339(),
+    };
+    std::fs::write(
+        partial_dir.join(VERSION_FILENAME),
+        serde_json::to_string_pretty(&installed)?,
+    )?;
+
+    // The patcher keeps the DLL loaded, it can't be replaced while it runs
+    let patcher_running = app_handle
+        .state::<crate::patcher::PatcherState>()
+        .0
+        .lock()
+        .map_err(|e| AppError::InternalState(e.to_string()))?
+        .is_running();
+    if patcher_running {
+        let _ = std::fs::remove_dir_all(&partial_dir);
+        return Err(AppError::Other(
+            "Stop the patcher before updating the tools".to_string(),
+        ));
+    }
+
+    if tools_dir.exists() {
+        std::fs::remove_dir_all(&tools_dir)?;
+    }
+    std::fs::rename(&partial_dir, &tools_dir)?;
+    tracing::info!("Installed tools {} to {:?}", release.tag_name, tools_dir);
+
+    Ok(update_info(Some(release.tag_name.clone()), &release))
+}
+
+/// Extract every tool from the release archive into `dir` and check it can be used.
+fn extract_tools(archive: &[u8], dir: &Path) -> AppResult<()> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(archive))
+        .map_err(|e| AppError::Other(format!("Failed to open tools archive: {}", e)))?;
+
+    for tool in Tool::ALL {
+        // The tools sit in a subfolder that has changed between releases, match on the file name
+        let index = (0..archive.len())
+            .find(|&i| {
+                archive.by_index(i).is_ok_and(|entry| {
+                    entry
+                        .enclosed_name()
+                        .and_then(|path| path.file_name().map(|name| name.to_os_string()))
+                        .is_some_and(|name| name.eq_ignore_ascii_case(tool.file_name()))
+                })
+            })
+            .ok_or_else(|| {
+                AppError::Other(format!(
+                    "{} is missing from the tools archive",
+                    tool.file_name()
+                ))
+            })?;
+
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| AppError::Other(format!("Failed to read tools archive: {}", e)))?;
+        let path = dir.join(tool.file_name());
+        let mut file = std::fs::File::create(&path)?;
+        std::io::copy(&mut entry, &mut file)?;
+        drop(file);
+
+        check_tool(tool, &path).map_err(AppError::Other)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Watching a process the app didn't start, e.g. the game client.
+
+pub use imp::GameProcess;
+
+#[cfg(windows)]
+mod imp {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    /// Exit code `GetExitCodeProcess` reports while the process is running.
+    const STILL_ACTIVE: u32 = 259;
+
+    /// An open handle to a running process, kept so its exit code can be read after it exits.
+    pub struct GameProcess {
+        pub pid: u32,
+        handle: HANDLE,
+    }
+
+    // The handle is only used through Win32 calls that are safe from any thread
+    unsafe impl Send for GameProcess {}
+
+    impl GameProcess {
+        /// Open the first running process with the executable name `exe_name`.
+        pub fn find(exe_name: &str) -> Option<Self> {
+            let pid = find_pid(exe_name)?;
+            // SAFETY: plain Win32 call, the handle is closed in Drop
+            let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+            if handle.is_null() {
+                return None;
+            }
+            Some(Self { pid, handle })
+        }
+
+        /// The exit code once the process has exited, `None` while it runs.
+        pub fn exit_code(&self) -> Option<u32> {
+            let mut code = 0u32;
+            // SAFETY: the handle stays valid until Drop
+            let ok = unsafe { GetExitCodeProcess(self.handle, &mut code) };
+            (ok != 0 && code != STILL_ACTIVE).then_some(code)
+        }
+    }
+
+    impl Drop for GameProcess {
+        fn drop(&mut self) {
+            // SAFETY: the handle came from OpenProcess and is closed once
+            unsafe { CloseHandle(self.handle) };
+        }
+    }
+
+    fn find_pid(exe_name: &str) -> Option<u32> {
+        // SAFETY: plain Win32 calls on a snapshot handle that is closed before returning
+        unsafe {
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+            if snapshot == INVALID_HANDLE_VALUE {
+                return None;
+            }
+
+            let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+            entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+
+            let mut pid = None;
+            let mut found = Process32FirstW(snapshot, &mut entry) != 0;
+            while found {
+                let len = entry
+                    .szExeFile
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                let name = String::from_utf16_lossy(&entry.szExeFile[..len]);
+                if name.eq_ignore_ascii_case(exe_name) {
+                    pid = Some(entry.th32ProcessID);
+                    break;
+                }
+                found = Process32NextW(snapshot, &mut entry) != 0;
+            }
+
+            CloseHandle(snapshot);
+            pid
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod imp {
+    /// The game only runs on Windows, so no process is ever found elsewhere.
+    pub struct GameProcess {
+        pub pid: u32,
+    }
+
+    impl GameProcess {
+        pub fn find(_exe_name: &str) -> Option<Self> {
+            None
+        }
+
+        pub fn exit_code(&self) -> Option<u32> {
+            None
+        }
+    }
+}
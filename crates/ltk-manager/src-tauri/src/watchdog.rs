@@ -0,0 +1,225 @@
+//! Crash watchdog for modded games.
+//!
+//! While an overlay is running, [`spawn_watchdog`] polls for the game process. If the game
+//! exits with an error within `crashWatchdogSeconds` of starting (the overlay hooks it right
+//! at start), the mods of the overlay are kept as a [`CrashSuspect`] and a `gameCrashed` event
+//! is sent. The user can then play the next game without mods through `run_vanilla_next_game`,
+//! which also keeps champion select auto-apply from starting a new overlay for that game.
+//! While a bisect runs, game outcomes go to [`crate::bisect`] instead. Any other modded game
+//! that doesn't crash marks its overlay as the last known-good one for rollbacks.
+
+use crate::commands::mod_skin::OverlayInputs;
+use crate::commands::{record_crash_inner, remember_last_good};
+use crate::error::{AppError, AppResult};
+use crate::events::{emit, AppEvent};
+use crate::patcher::PatcherState;
+use crate::state::SettingsState;
+use crate::utils::process::GameProcess;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+const GAME_EXE_NAME: &str = "League of Legends.exe";
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_CRASH_WINDOW_SECS: u64 = 90;
+
+/// Mods that were running when the game crashed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashSuspect {
+    /// Mod folder names of the overlay, e.g. skin ids.
+    pub mods: Vec<String>,
+    pub exit_code: u32,
+    /// Seconds between the game starting and exiting.
+    pub runtime_secs: u64,
+    pub detected_at: String,
+}
+
+/// The overlay that is currently running.
+#[derive(Clone)]
+struct ActiveOverlay {
+    /// Mod folder names of the overlay, without the announcer pack.
+    mods: Vec<String>,
+    inputs: OverlayInputs,
+}
+
+#[derive(Default)]
+struct WatchdogInner {
+    active_overlay: Option<ActiveOverlay>,
+    suspect: Option<CrashSuspect>,
+    vanilla_next_game: bool,
+}
+
+#[derive(Default)]
+pub struct WatchdogState(Mutex<WatchdogInner>);
+
+impl WatchdogState {
+    fn lock(&self) -> AppResult<std::sync::MutexGuard<'_, WatchdogInner>> {
+        self.0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))
+    }
+}
+
+/// Remember the mods and build inputs of the overlay that was just started.
+pub fn set_active_overlay(app_handle: &AppHandle, inputs: OverlayInputs) {
+    let state = app_handle.state::<WatchdogState>();
+    let guard = state.lock();
+    if let Ok(mut inner) = guard {
+        inner.active_overlay = Some(ActiveOverlay {
+            mods: inputs.mod_ids(),
+            inputs,
+        });
+    }
+}
+
+/// The mods of the last crashed game, until dismissed.
+pub fn suspect(app_handle: &AppHandle) -> AppResult<Option<CrashSuspect>> {
+    Ok(app_handle.state::<WatchdogState>().lock()?.suspect.clone())
+}
+
+pub fn dismiss_suspect(app_handle: &AppHandle) -> AppResult<()> {
+    app_handle.state::<WatchdogState>().lock()?.suspect = None;
+    Ok(())
+}
+
+/// Skip mods for the next game. Cleared once that game has started.
+pub fn set_vanilla_next_game(app_handle: &AppHandle, enabled: bool) -> AppResult<()> {
+    app_handle
+        .state::<WatchdogState>()
+        .lock()?
+        .vanilla_next_game = enabled;
+    Ok(())
+}
+
+pub fn vanilla_next_game(app_handle: &AppHandle) -> bool {
+    let state = app_handle.state::<WatchdogState>();
+    let guard = state.lock();
+    guard.is_ok_and(|inner| inner.vanilla_next_game)
+}
+
+struct WatchedGame {
+    process: GameProcess,
+    started: Instant,
+    /// The overlay running at game start, `None` for an unmodded game.
+    overlay: Option<ActiveOverlay>,
+}
+
+/// Watch game starts and exits in the background for the lifetime of the app.
+pub fn spawn_watchdog(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut watched: Option<WatchedGame> = None;
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let Some(game) = &watched else {
+                watched = GameProcess::find(GAME_EXE_NAME).map(|process| {
+                    let overlay = game_started(&app_handle);
+                    tracing::info!(
+                        "Game started (pid {}), watching {} mod(s)",
+                        process.pid,
+                        overlay.as_ref().map_or(0, |o| o.mods.len())
+                    );
+                    WatchedGame {
+                        process,
+                        started: Instant::now(),
+                        overlay,
+                    }
+                });
+                continue;
+            };
+
+            let Some(exit_code) = game.process.exit_code() else {
+                continue;
+            };
+            let runtime = game.started.elapsed();
+            tracing::info!(
+                "Game exited with code {} after {}s",
+                exit_code,
+                runtime.as_secs()
+            );
+
+            if let Some(overlay) = watched.take().and_then(|game| game.overlay) {
+                let crashed = exit_code != 0 && runtime < crash_window(&app_handle);
+                // A bisect round runs staged mod folders, the bisect records the culprit itself
+                if crate::bisect::is_running(&app_handle) {
+                    if let Err(e) = crate::bisect::game_finished(&app_handle, crashed).await {
+                        tracing::warn!("Failed to continue bisecting: {}", e);
+                    }
+                } else if crashed {
+                    game_crashed(&app_handle, overlay.mods, exit_code, runtime).await;
+                } else if let Err(e) = remember_last_good(&app_handle, &overlay.inputs).await {
+                    tracing::warn!("Failed to remember the last good overlay: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// The overlay running in the game that just started, clearing the vanilla game request.
+fn game_started(app_handle: &AppHandle) -> Option<ActiveOverlay> {
+    let overlay_running = {
+        let state = app_handle.state::<PatcherState>();
+        let guard = state.0.lock();
+        guard.is_ok_and(|patcher| patcher.child_process.is_some() || patcher.is_running())
+    };
+
+    let state = app_handle.state::<WatchdogState>();
+    let mut inner = state.lock().ok()?;
+    inner.vanilla_next_game = false;
+    inner.active_overlay.clone().filter(|_| overlay_running)
+}
+
+fn crash_window(app_handle: &AppHandle) -> Duration {
+    let state = app_handle.state::<SettingsState>();
+    let seconds = state
+        .0
+        .lock()
+        .ok()
+        .and_then(|settings| settings.crash_watchdog_seconds)
+        .unwrap_or(DEFAULT_CRASH_WINDOW_SECS);
+    Duration::from_secs(seconds)
+}
+
+async fn game_crashed(
+    app_handle: &AppHandle,
+    mods: Vec<String>,
+    exit_code: u32,
+    runtime: Duration,
+) {
+    tracing::warn!(
+        "Game crashed {}s after starting with mods {:?}",
+        runtime.as_secs(),
+        mods
+    );
+
+    // A single mod is the only possible culprit, larger sets need bisecting first
+    if let [mod_id] = mods.as_slice() {
+        let reason = format!(
+            "Game exited with code {} after {}s",
+            exit_code,
+            runtime.as_secs()
+        );
+        if let Err(e) = record_crash_inner(app_handle, mod_id.clone(), None, Some(reason)).await {
+            tracing::warn!("Failed to record crash of mod {}: {}", mod_id, e);
+        }
+    }
+
+    let suspect = CrashSuspect {
+        mods,
+        exit_code,
+        runtime_secs: runtime.as_secs(),
+        detected_at: chrono::Utc::now().to_rfc3339(),
+    };
+    {
+        let state = app_handle.state::<WatchdogState>();
+        let guard = state.lock();
+        if let Ok(mut inner) = guard {
+            inner.suspect = Some(suspect.clone());
+        }
+    }
+    emit(app_handle, AppEvent::GameCrashed { suspect });
+}
@@ -0,0 +1,312 @@
+//! Layout of the workspace directory.
+//!
+//! All paths below the workspace are built by [`WorkspaceLayout`]. Ids and names coming from
+//! the frontend, the mod index or manifests are validated before they are joined, so a built
+//! path always stays under the workspace root.
+
+use crate::commands::CosmeticCategory;
+use crate::error::{AppError, AppResult};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const DATA_DIRNAME: &str = "data";
+const IMAGES_DIRNAME: &str = "images";
+const TFT_DIRNAME: &str = "tft";
+const BROWSER_DIRNAME: &str = "browser";
+const ANNOUNCERS_DIRNAME: &str = "announcers";
+const IMPORTED_DIRNAME: &str = "imported";
+const OVERLAY_DIRNAME: &str = "overlay";
+const OVERLAY_STAGING_DIRNAME: &str = "overlay_mods";
+const BISECT_STAGING_DIRNAME: &str = "bisect_mods";
+const PATCH_HISTORY_DIRNAME: &str = "patches";
+const PLUGINS_DIRNAME: &str = "plugins";
+
+const CHAMPION_METADATA_FILENAME: &str = "metadata.json";
+const SKIN_IDS_FILENAME: &str = "skin_ids.json";
+const SKIN_CATALOG_FILENAME: &str = "skin_catalog.bin";
+const CHAMPIONS_FILENAME: &str = "champions_with_skins.json";
+const VERSION_FILENAME: &str = "version.json";
+const ANNOTATIONS_FILENAME: &str = "annotations.json";
+const JOB_HISTORY_FILENAME: &str = "jobs.json";
+const COMPAT_CACHE_FILENAME: &str = "compat.json";
+const CRASH_REPORTS_FILENAME: &str = "crash_reports.json";
+const LAST_GOOD_OVERLAY_FILENAME: &str = "last_good_overlay.json";
+const CHAMPION_SUMMARY_FILENAME: &str = "champion_summary.json";
+const FREE_ROTATION_FILENAME: &str = "free_rotation.json";
+
+/// Upper bound for champion, skin and cosmetic ids. Skin ids are `champion_id * 1000 + n`,
+/// so anything above this can't come from the game data.
+const MAX_ID: i32 = 10_000_000;
+
+#[derive(Debug, Clone)]
+pub struct WorkspaceLayout {
+    root: PathBuf,
+}
+
+impl WorkspaceLayout {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Layout of the workspace configured in the settings.
+    pub fn from_app(app_handle: &AppHandle) -> AppResult<Self> {
+        let settings_state = app_handle.state::<crate::state::SettingsState>();
+        let settings = settings_state
+            .0
+            .lock()
+            .map_err(|e| AppError::InternalState(e.to_string()))?;
+
+        match &settings.workspace_path {
+            Some(path) => Ok(Self::new(path)),
+            None => Err(AppError::Other(
+                "Workspace path not configured. Please set it in Settings.".to_string(),
+            )),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn skin_ids_file(&self) -> PathBuf {
+        self.root.join(SKIN_IDS_FILENAME)
+    }
+
+    /// Binary catalog of champions and skins, derived from `skin_ids.json`.
+    pub fn skin_catalog_file(&self) -> PathBuf {
+        self.root.join(SKIN_CATALOG_FILENAME)
+    }
+
+    pub fn champions_file(&self) -> PathBuf {
+        self.root.join(CHAMPIONS_FILENAME)
+    }
+
+    pub fn version_file(&self) -> PathBuf {
+        self.root.join(VERSION_FILENAME)
+    }
+
+    pub fn annotations_file(&self) -> PathBuf {
+        self.root.join(ANNOTATIONS_FILENAME)
+    }
+
+    pub fn job_history_file(&self) -> PathBuf {
+        self.root.join(JOB_HISTORY_FILENAME)
+    }
+
+    /// Cached copy of the remote known-crashes list.
+    pub fn compat_cache_file(&self) -> PathBuf {
+        self.root.join(COMPAT_CACHE_FILENAME)
+    }
+
+    /// Crashes the user recorded for mods on this machine.
+    pub fn crash_reports_file(&self) -> PathBuf {
+        self.root.join(CRASH_REPORTS_FILENAME)
+    }
+
+    /// Build inputs of the last overlay a game ran with without crashing.
+    pub fn last_good_overlay_file(&self) -> PathBuf {
+        self.root.join(LAST_GOOD_OVERLAY_FILENAME)
+    }
+
+    /// Roles of every champion, downloaded with the skin database.
+    pub fn champion_summary_file(&self) -> PathBuf {
+        self.root.join(CHAMPION_SUMMARY_FILENAME)
+    }
+
+    /// Last free champion rotation read from the League client.
+    pub fn free_rotation_file(&self) -> PathBuf {
+        self.root.join(FREE_ROTATION_FILENAME)
+    }
+
+    pub fn patch_history_dir(&self) -> PathBuf {
+        self.root.join(PATCH_HISTORY_DIRNAME)
+    }
+
+    /// Executable plugins, one folder per plugin id.
+    pub fn plugins_dir(&self) -> PathBuf {
+        self.root.join(PLUGINS_DIRNAME)
+    }
+
+    pub fn plugin_dir(&self, plugin_id: &str) -> AppResult<PathBuf> {
+        validate_name(plugin_id, "plugin id")?;
+        Ok(self.plugins_dir().join(plugin_id))
+    }
+
+    /// `data/`, holding one folder per champion and the other mod categories.
+    pub fn data_dir(&self) -> PathBuf {
+        self.root.join(DATA_DIRNAME)
+    }
+
+    /// `data/{champion_id}`, the champion metadata and its downloaded skins.
+    pub fn champion_dir(&self, champion_id: i32) -> AppResult<PathBuf> {
+        validate_id(champion_id, "champion")?;
+        Ok(self.data_dir().join(champion_id.to_string()))
+    }
+
+    pub fn champion_metadata_file(&self, champion_id: i32) -> AppResult<PathBuf> {
+        Ok(self
+            .champion_dir(champion_id)?
+            .join(CHAMPION_METADATA_FILENAME))
+    }
+
+    pub fn champion_images_dir(&self, champion_id: i32) -> AppResult<PathBuf> {
+        Ok(self.champion_dir(champion_id)?.join(IMAGES_DIRNAME))
+    }
+
+    /// `data/{champion_id}/{skin_id}`, the extracted skin mod.
+    pub fn skin_dir(&self, champion_id: i32, skin_id: i32) -> AppResult<PathBuf> {
+        validate_id(skin_id, "skin")?;
+        Ok(self.champion_dir(champion_id)?.join(skin_id.to_string()))
+    }
+
+    pub fn cosmetic_dir(&self, category: CosmeticCategory) -> PathBuf {
+        self.data_dir().join(category.dir_name())
+    }
+
+    pub fn cosmetic_mod_dir(&self, category: CosmeticCategory, item_id: i32) -> AppResult<PathBuf> {
+        validate_id(item_id, "item")?;
+        Ok(self.cosmetic_dir(category).join(item_id.to_string()))
+    }
+
+    pub fn tft_dir(&self) -> PathBuf {
+        self.data_dir().join(TFT_DIRNAME)
+    }
+
+    pub fn browser_dir(&self) -> PathBuf {
+        self.data_dir().join(BROWSER_DIRNAME)
+    }
+
+    pub fn browser_mods_dir(&self) -> PathBuf {
+        self.browser_dir().join("mods")
+    }
+
+    pub fn browser_mod_dir(&self, mod_id: &str) -> AppResult<PathBuf> {
+        validate_name(mod_id, "mod id")?;
+        Ok(self.browser_mods_dir().join(mod_id))
+    }
+
+    pub fn announcers_dir(&self) -> PathBuf {
+        self.data_dir().join(ANNOUNCERS_DIRNAME)
+    }
+
+    pub fn announcer_dir(&self, name: &str) -> AppResult<PathBuf> {
+        validate_name(name, "announcer pack name")?;
+        Ok(self.announcers_dir().join(name))
+    }
+
+    /// Mods imported from other mod managers.
+    pub fn imported_dir(&self) -> PathBuf {
+        self.data_dir().join(IMPORTED_DIRNAME)
+    }
+
+    pub fn imported_mod_dir(&self, name: &str) -> AppResult<PathBuf> {
+        validate_name(name, "mod name")?;
+        Ok(self.imported_dir().join(name))
+    }
+
+    /// Where mod-tools writes the overlay it runs.
+    pub fn overlay_dir(&self) -> PathBuf {
+        self.data_dir().join(OVERLAY_DIRNAME)
+    }
+
+    /// Mods directory assembled for an overlay that combines several mods.
+    pub fn overlay_staging_dir(&self) -> PathBuf {
+        self.data_dir().join(OVERLAY_STAGING_DIRNAME)
+    }
+
+    /// Mods directory assembled for the mods a bisect round tests.
+    pub fn bisect_staging_dir(&self) -> PathBuf {
+        self.data_dir().join(BISECT_STAGING_DIRNAME)
+    }
+}
+
+/// Reject ids that can't be a game or catalog id.
+pub fn validate_id(id: i32, what: &str) -> AppResult<()> {
+    if !(1..=MAX_ID).contains(&id) {
+        return Err(AppError::ValidationFailed(format!(
+            "Invalid {} id: {}",
+            what, id
+        )));
+    }
+    Ok(())
+}
+
+/// Reject names that aren't a single plain path component, e.g. `..`, `a/b` or `.hidden`, and
+/// names Windows reserves for devices, e.g. `con` or `nul.txt`.
+pub fn validate_name(name: &str, what: &str) -> AppResult<()> {
+    let mut components = Path::new(name).components();
+    let is_single_normal =
+        matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none();
+
+    if !is_single_normal
+        || name.starts_with('.')
+        || name.contains(['/', '\\', ':'])
+        || is_reserved_name(name)
+    {
+        return Err(AppError::ValidationFailed(format!(
+            "Invalid {}: {:?}",
+            what, name
+        )));
+    }
+    Ok(())
+}
+
+/// Whether Windows treats `name` as a device, with or without an extension.
+fn is_reserved_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    let stem = stem.to_ascii_uppercase();
+    matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || ["COM", "LPT"].iter().any(|prefix| {
+            stem.strip_prefix(prefix)
+                .is_some_and(|n| n.len() == 1 && n.as_bytes()[0].is_ascii_digit() && n != "0")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_id_bounds() {
+        assert!(validate_id(1, "skin").is_ok());
+        assert!(validate_id(MAX_ID, "skin").is_ok());
+        assert!(validate_id(0, "skin").is_err());
+        assert!(validate_id(-1, "skin").is_err());
+        assert!(validate_id(MAX_ID + 1, "skin").is_err());
+        assert!(validate_id(i32::MAX, "skin").is_err());
+    }
+
+    #[test]
+    fn test_validate_name_accepts_plain_names() {
+        for name in ["my-mod", "Mod_1.2", "announcer pack", "console"] {
+            assert!(validate_name(name, "mod id").is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_validate_name_rejects_traversal_and_separators() {
+        for name in [
+            "", ".", "..", "../mod", "a/b", "a\\b", "/abs", "C:", "C:mod", ".hidden",
+        ] {
+            assert!(validate_name(name, "mod id").is_err(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_validate_name_rejects_reserved_names() {
+        for name in [
+            "con",
+            "CON",
+            "nul.txt",
+            "Aux",
+            "prn.tar.gz",
+            "com1",
+            "LPT9.zip",
+        ] {
+            assert!(validate_name(name, "mod id").is_err(), "{:?}", name);
+        }
+        for name in ["com0", "com10", "lpt", "nullable"] {
+            assert!(validate_name(name, "mod id").is_ok(), "{:?}", name);
+        }
+    }
+}
@@ -23,8 +23,36 @@ export interface Settings {
   workspacePath: string | null;
   theme: "light" | "dark" | "system";
   firstRunComplete: boolean;
+  /** Number of per-version game chunk manifests kept for the patch history */
+  patchHistoryLimit?: number | null;
+  /** Download TFT cosmetic metadata and include TFT WADs when building overlays */
+  includeTft?: boolean;
+  /** Imported announcer pack that is added to every overlay */
+  activeAnnouncer?: string | null;
+  /** URL of the community mod index used by the mod browser */
+  modIndexUrl?: string | null;
+  /** URL of a community list of mods known to crash, merged with the bundled list */
+  compatListUrl?: string | null;
+  /** Games with overlays that exit with an error within this many seconds count as crashes */
+  crashWatchdogSeconds?: number | null;
+  /** Named skins that can be applied with one trigger, e.g. from a Stream Deck */
+  quickSwitches?: QuickSwitch[];
+  /** Serve the localhost HTTP API while the window is open */
+  httpApiEnabled?: boolean;
+  /** Run the bound skin when a champion is locked in during champion select */
+  autoApplyEnabled?: boolean;
+  /** Preferred skin id per champion id, applied on lock-in and skipped by the randomizer */
+  skinBindings?: Record<number, number>;
+  /** Apply a random skin instead of a configured one, null while the randomizer is off */
+  randomSkin?: RandomSkinConfig | null;
+  /** Release channel `checkAppUpdate` looks for new versions on */
+  updateChannel?: UpdateChannel;
+  /** Plugin id to the permissions granted when it was enabled */
+  enabledPlugins?: Record<string, PluginPermission[]>;
 }
 
+export type UpdateChannel = "stable" | "beta";
+
 export interface InstalledMod {
   id: string;
   name: string;
@@ -96,6 +124,8 @@ export interface ExtractResult {
 // API functions
 export const api = {
   getAppInfo: () => invokeResult<AppInfo>("get_app_info"),
+  checkAppUpdate: () => invokeResult<AppUpdateInfo | null>("check_app_update"),
+  installAppUpdate: () => invokeResult<void>("install_app_update"),
 
   // Settings
   getSettings: () => invokeResult<Settings>("get_settings"),
@@ -111,6 +141,21 @@ export const api = {
   toggleMod: (modId: string, enabled: boolean) =>
     invokeResult<void>("toggle_mod", { modId, enabled }),
 
+  // Jobs
+  listJobs: () => invokeResult<Job[]>("list_jobs"),
+  getJob: (id: number) => invokeResult<Job | null>("get_job", { id }),
+  cancelJob: (id: number) => invokeResult<boolean>("cancel_job", { id }),
+
+  // Tools
+  getToolsStatus: () => invokeResult<ToolStatus[]>("get_tools_status"),
+  checkToolsUpdate: () => invokeResult<ToolsUpdateInfo>("check_tools_update"),
+  updateTools: () => invokeResult<ToolsUpdateInfo>("update_tools"),
+  runSelfTest: () => invokeResult<SelfTestReport>("run_self_test"),
+
+  // Logs
+  subscribeLogs: (level: LogLevel | null) => invokeResult<void>("subscribe_logs", { level }),
+  getLogFilePath: () => invokeResult<string | null>("get_log_file_path"),
+
   // Inspector
   inspectModpkg: (filePath: string) => invokeResult<ModpkgInfo>("inspect_modpkg", { filePath }),
 
@@ -122,11 +167,147 @@ export const api = {
   getChampionsWithSkins: () => invokeResult<ChampionWithSkins[]>("get_champions_with_skins"),
 
 
-  getChampionSkins: (championId: number) =>
-    invokeResult<SkinData[]>("get_champion_skins", { championId }),
+  getChampionSkins: (championId: number, filter?: SkinFilter) =>
+    invokeResult<SkinData[]>("get_champion_skins", { championId, filter }),
+  getSkinInfo: (skinId: number) => invokeResult<SkinInfo | null>("get_skin_info", { skinId }),
+  searchSkins: (query: string, limit?: number) =>
+    invokeResult<SkinInfo[]>("search_skins", { query, limit }),
   getSkinImage: (championId: number, skinId: number) =>
     invokeResult<string>("get_skin_image", { championId, skinId }),
   checkAndUpdateDatabase: () => invokeResult<UpdateResult>("check_and_update_database"),
+  verifyDatabase: () => invokeResult<VerifyReport>("verify_database"),
+  getChampionInfo: (championId: number) =>
+    invokeResult<ChampionInfo>("get_champion_info", { championId }),
+  getFreeRotation: () => invokeResult<FreeRotation | null>("get_free_rotation"),
+
+  // Patch History
+  listPatchSnapshots: () => invokeResult<PatchSnapshotInfo[]>("list_patch_snapshots"),
+  diffPatchSnapshots: (fromVersion: string, toVersion: string, wad?: string) =>
+    invokeResult<PatchDiff>("diff_patch_snapshots", { fromVersion, toVersion, wad }),
+
+  // Mod List
+  exportModList: (format: ModListFormat) => invokeResult<string>("export_mod_list", { format }),
+  applyModList: (manifest: ModListManifest) =>
+    invokeResult<ApplyModListReport>("apply_mod_list", { manifest }),
+
+  // Mod Annotations
+  getModAnnotations: () => invokeResult<Record<string, ModAnnotation>>("get_mod_annotations"),
+  getModAnnotation: (modId: string) =>
+    invokeResult<ModAnnotation | null>("get_mod_annotation", { modId }),
+  setModAnnotation: (modId: string, annotation: ModAnnotation) =>
+    invokeResult<ModAnnotation>("set_mod_annotation", { modId, annotation }),
+  deleteModAnnotation: (modId: string) => invokeResult<void>("delete_mod_annotation", { modId }),
+
+  // Known Crashes
+  refreshCompatList: () => invokeResult<CompatListStatus>("refresh_compat_list"),
+  checkModCompat: (modId: string, championId: number | null) =>
+    invokeResult<CompatWarning[]>("check_mod_compat", { modId, championId }),
+  recordCrash: (modId: string, championId: number | null, reason: string | null) =>
+    invokeResult<CompatEntry>("record_crash", { modId, championId, reason }),
+  listCrashReports: () => invokeResult<CompatEntry[]>("list_crash_reports"),
+  deleteCrashReport: (modId: string, championId: number | null) =>
+    invokeResult<void>("delete_crash_report", { modId, championId }),
+
+  // Crash Watchdog
+  getCrashSuspect: () => invokeResult<CrashSuspect | null>("get_crash_suspect"),
+  dismissCrashSuspect: () => invokeResult<void>("dismiss_crash_suspect"),
+  runVanillaNextGame: () => invokeResult<void>("run_vanilla_next_game"),
+  cancelVanillaNextGame: () => invokeResult<void>("cancel_vanilla_next_game"),
+
+  // Rollback
+  getLastGoodOverlay: () => invokeResult<LastGoodOverlay | null>("get_last_good_overlay"),
+  rollbackToLastGood: () => invokeResult<LastGoodOverlay>("rollback_to_last_good"),
+
+  // Bisect
+  startBisect: (mods: ModListEntry[]) => invokeResult<BisectStatus>("start_bisect", { mods }),
+  getBisectStatus: () => invokeResult<BisectStatus | null>("get_bisect_status"),
+  reportBisectResult: (crashed: boolean) =>
+    invokeResult<void>("report_bisect_result", { crashed }),
+  cancelBisect: () => invokeResult<void>("cancel_bisect"),
+
+  // Import & Export
+  detectImportSources: () => invokeResult<string[]>("detect_import_sources"),
+  scanImportSource: (path: string) =>
+    invokeResult<ImportCandidate[]>("scan_import_source", { path }),
+  importMods: (path: string, names: string[], link: boolean) =>
+    invokeResult<ImportReport>("import_mods", { path, names, link }),
+  exportToCslol: (
+    path: string,
+    mods: ModListEntry[],
+    profileName: string | null,
+    link: boolean,
+  ) => invokeResult<CslolExportReport>("export_to_cslol", { path, mods, profileName, link }),
+
+  // Backup
+  backupTo: (path: string) => invokeResult<BackupSummary>("backup_to", { path }),
+  restoreFrom: (path: string) => invokeResult<RestoreReport>("restore_from", { path }),
+
+  // Champion Select
+  getCurrentChampion: () => invokeResult<CurrentChampion | null>("get_current_champion"),
+
+  // Skin Bindings
+  getSkinBindings: () => invokeResult<Record<number, number>>("get_skin_bindings"),
+  setSkinBinding: (championId: number, skinId: number) =>
+    invokeResult<void>("set_skin_binding", { championId, skinId }),
+  clearSkinBinding: (championId: number) =>
+    invokeResult<void>("clear_skin_binding", { championId }),
+
+  // Randomizer
+  enableRandomSkin: (championScope: ChampionScope, pool: number[]) =>
+    invokeResult<void>("enable_random_skin", { championScope, pool }),
+  disableRandomSkin: () => invokeResult<void>("disable_random_skin"),
+  runRandomSkin: (championId: number) =>
+    invokeResult<string>("run_random_skin", { championId }),
+
+  // Peer Sync
+  startPeerSync: (name: string) => invokeResult<void>("start_peer_sync", { name }),
+  stopPeerSync: () => invokeResult<void>("stop_peer_sync"),
+  listPeers: () => invokeResult<PeerInfo[]>("list_peers"),
+  sendProfileToPeer: (address: string, name: string) =>
+    invokeResult<void>("send_profile_to_peer", { address, name }),
+  respondToPeerOffer: (id: number, accept: boolean) =>
+    invokeResult<ApplyModListReport | null>("respond_to_peer_offer", { id, accept }),
+
+  // Plugins
+  listPlugins: () => invokeResult<PluginInfo[]>("list_plugins"),
+  setPluginEnabled: (pluginId: string, enabled: boolean) =>
+    invokeResult<PluginInfo>("set_plugin_enabled", { pluginId, enabled }),
+  runPluginCommand: (pluginId: string, command: string, args?: unknown) =>
+    invokeResult<unknown>("run_plugin_command", { pluginId, command, args }),
+
+  // Quick Switch
+  listQuickSwitches: () => invokeResult<QuickSwitch[]>("list_quick_switches"),
+  triggerQuickSwitch: (name: string) => invokeResult<string>("trigger_quick_switch", { name }),
+  createQuickSwitch: (quickSwitch: QuickSwitch) =>
+    invokeResult<QuickSwitch[]>("create_quick_switch", { quickSwitch }),
+  updateQuickSwitch: (name: string, quickSwitch: QuickSwitch) =>
+    invokeResult<QuickSwitch[]>("update_quick_switch", { name, quickSwitch }),
+  deleteQuickSwitch: (name: string) => invokeResult<QuickSwitch[]>("delete_quick_switch", { name }),
+
+  // Mod Browser
+  refreshModIndex: () => invokeResult<ModIndexStatus>("refresh_mod_index"),
+  searchMods: (query: ModSearchQuery) => invokeResult<ModSearchPage>("search_mods", { query }),
+  downloadBrowserMod: (modId: string) => invokeResult<string>("download_browser_mod", { modId }),
+
+  // Announcer Packs
+  importAnnouncerPack: (sourceDir: string, name: string) =>
+    invokeResult<AnnouncerImportReport>("import_announcer_pack", { sourceDir, name }),
+  listAnnouncerPacks: () => invokeResult<AnnouncerPackInfo[]>("list_announcer_packs"),
+  deleteAnnouncerPack: (name: string) => invokeResult<void>("delete_announcer_pack", { name }),
+  setActiveAnnouncer: (name: string | null) =>
+    invokeResult<void>("set_active_announcer", { name }),
+
+  // Ward Skins & Emotes
+  getWardSkins: () => invokeResult<CosmeticItem[]>("get_ward_skins"),
+  getEmotes: () => invokeResult<CosmeticItem[]>("get_emotes"),
+  downloadWardSkin: (category: CosmeticCategory, itemId: number) =>
+    invokeResult<string>("download_ward_skin", { category, itemId }),
+  runWardSkin: (category: CosmeticCategory, itemId: number) =>
+    invokeResult<string>("run_ward_skin", { category, itemId }),
+
+  // TFT
+  getTftItems: (category: TftCategory) => invokeResult<TftItem[]>("get_tft_items", { category }),
+  getTftWads: () => invokeResult<string[]>("get_tft_wads"),
 
 };
 
@@ -149,7 +330,18 @@ export interface SkinData {
   tilePath: string;
   rarity: string;
   isBase: boolean;
+  /** Vaulted skins that are only sold during special events */
+  isLegacy: boolean;
+  availability?: string;
   chromas: ChromaData[];
+  /** The user's rating and notes for this skin, if any */
+  annotation?: ModAnnotation;
+}
+
+export interface SkinFilter {
+  /** Only legacy skins if true, only skins that are not legacy if false */
+  legacy?: boolean;
+  availability?: string;
 }
 
 export interface ChromaData {
@@ -157,3 +349,450 @@ export interface ChromaData {
   name: string;
   tilePath: string;
 }
+
+export interface PatchSnapshotInfo {
+  version: string;
+  createdAt: string;
+  wadCount: number;
+  chunkCount: number;
+}
+
+export interface ChunkChange {
+  kind: "added" | "removed" | "modified";
+  pathHash: string;
+  oldSize: number | null;
+  newSize: number | null;
+}
+
+export interface WadDiff {
+  wad: string;
+  added: number;
+  removed: number;
+  modified: number;
+  sizeDelta: number;
+  changes?: ChunkChange[];
+}
+
+export interface PatchDiff {
+  fromVersion: string;
+  toVersion: string;
+  added: number;
+  removed: number;
+  modified: number;
+  sizeDelta: number;
+  wads: WadDiff[];
+}
+
+export type TftCategory = "companions" | "mapSkins" | "damageSkins";
+
+export interface TftItem {
+  itemId: number;
+  contentId: string;
+  name: string;
+  loadoutsIcon: string | null;
+  rarity: string | null;
+}
+
+export type CosmeticCategory = "wardSkin" | "emote";
+
+export interface CosmeticItem {
+  id: number;
+  name: string;
+  imagePath: string | null;
+  /** Whether the mod for this item has been downloaded to the workspace */
+  downloaded: boolean;
+}
+
+export interface AnnouncerPackInfo {
+  name: string;
+  fileCount: number;
+  /** Game WADs the pack overrides files in */
+  wads: string[];
+  active: boolean;
+}
+
+export interface AnnouncerImportReport {
+  pack: AnnouncerPackInfo;
+  /** Non-fatal validation findings, e.g. banks missing events of the original */
+  warnings: string[];
+}
+
+export interface ModIndexEntry {
+  id: string;
+  name: string;
+  author: string;
+  category: string;
+  championId: number | null;
+  skinId: number | null;
+  description: string;
+  downloadUrl: string;
+  tags: string[];
+  updatedAt: string | null;
+}
+
+export interface ModSearchQuery {
+  text?: string;
+  championId?: number;
+  category?: string;
+  author?: string;
+  /** Zero-based page number */
+  page?: number;
+  pageSize?: number;
+}
+
+export interface ModSearchPage {
+  items: ModIndexEntry[];
+  total: number;
+  page: number;
+  pageSize: number;
+  fetchedAt: string;
+}
+
+export interface ModIndexStatus {
+  sourceUrl: string;
+  fetchedAt: string;
+  count: number;
+}
+
+export interface ModAnnotation {
+  /** 1 to 5 stars */
+  rating?: number | null;
+  notes?: string;
+  tags?: string[];
+  updatedAt?: string;
+}
+
+export type ModListFormat = "text" | "json";
+
+export type ModListKind = "skin" | "wardSkin" | "emote" | "browser" | "announcer" | "imported";
+
+export interface ModListEntry {
+  kind: ModListKind;
+  /** Skin id, item id, index id or announcer pack name depending on kind */
+  id: string;
+  championId?: number | null;
+  name: string;
+  version?: string | null;
+}
+
+export interface ModListManifest {
+  formatVersion: number;
+  generatedAt: string;
+  gameVersion?: string | null;
+  activeAnnouncer?: string | null;
+  mods: ModListEntry[];
+}
+
+export interface ModListFailure {
+  entry: ModListEntry;
+  error: string;
+}
+
+export interface ApplyModListReport {
+  installed: ModListEntry[];
+  alreadyInstalled: number;
+  failed: ModListFailure[];
+  /** Announcer pack of the list that was made active */
+  activeAnnouncer: string | null;
+}
+
+/** Name of the Tauri event every backend {@link AppEvent} is emitted on */
+export const APP_EVENT = "app-event";
+
+export type EventSource =
+  | "skinDatabase"
+  | "metadata"
+  | "patchSnapshot"
+  | "download"
+  | "overlay"
+  | "modIndex"
+  | "champSelect"
+  | "appUpdate";
+
+export type AppEvent =
+  | { type: "progress"; source: EventSource; processed: number; total: number; message: string }
+  | { type: "status"; source: EventSource; message: string }
+  | { type: "error"; source: EventSource; message: string }
+  | { type: "completed"; source: EventSource; message: string }
+  | { type: "job"; job: Job }
+  | { type: "compatWarnings"; modId: string; warnings: CompatWarning[] }
+  | { type: "gameCrashed"; suspect: CrashSuspect }
+  | { type: "bisect"; status: BisectStatus }
+  | { type: "peerOffer"; offer: PeerOffer }
+  | { type: "log"; level: LogLevel; target: string; message: string; timestamp: string };
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+export type JobKind = "databaseRefresh" | "download" | "overlay" | "modIndex";
+
+export type JobState = "running" | "completed" | "failed" | "cancelled" | "interrupted";
+
+/** How an interrupted download is restarted on the next app start */
+export type JobResume =
+  | { type: "skin"; championId: number; skinId: number }
+  | { type: "cosmetic"; category: CosmeticCategory; itemId: number }
+  | { type: "browserMod"; modId: string };
+
+export interface Job {
+  id: number;
+  kind: JobKind;
+  label: string;
+  state: JobState;
+  progress: { processed: number; total: number } | null;
+  /** Latest status message, or the error of a failed job */
+  message: string | null;
+  startedAt: string;
+  finishedAt: string | null;
+  resume?: JobResume | null;
+}
+
+export type Tool = "modTools" | "patcherDll";
+
+export interface ToolStatus {
+  tool: Tool;
+  fileName: string;
+  path: string | null;
+  /**
+   * "downloaded" for tools installed from the latest release, "bundled" for the installed app,
+   * "development" for dev builds
+   */
+  location: "downloaded" | "bundled" | "development" | null;
+  size: number | null;
+  modifiedAt: string | null;
+  /** Why the tool can't be used and what to do about it, null if it is usable */
+  problem: string | null;
+}
+
+export interface ToolsUpdateInfo {
+  /** Release the downloaded tools come from, null when the bundled copies are in use */
+  installedVersion: string | null;
+  latestVersion: string;
+  publishedAt: string | null;
+  releaseUrl: string;
+  updateAvailable: boolean;
+}
+
+export interface SelfTestStage {
+  name: string;
+  /** "skipped" when an earlier stage failed */
+  outcome: "passed" | "failed" | "skipped";
+  message: string | null;
+  durationMs: number;
+}
+
+export interface SelfTestReport {
+  passed: boolean;
+  stages: SelfTestStage[];
+}
+
+export interface QuickSwitch {
+  /** Name used to trigger the switch, compared case-insensitively */
+  name: string;
+  championId: number;
+  skinId: number;
+  /** Global shortcut that triggers the switch, e.g. "Ctrl+Alt+1" */
+  hotkey?: string | null;
+}
+
+export interface CurrentChampion {
+  championId: number;
+  /** Whether the champion is locked in rather than hovered */
+  locked: boolean;
+}
+
+export type ChampionScope = { type: "all" } | { type: "champions"; championIds: number[] };
+
+export interface RandomSkinConfig {
+  championScope: ChampionScope;
+  /** Skin ids to pick from, every skin of the champion if empty */
+  pool: number[];
+}
+
+export interface ProfileBundle {
+  modList: ModListManifest;
+  skinBindings: Record<number, number>;
+  quickSwitches: QuickSwitch[];
+}
+
+export interface PeerInfo {
+  name: string;
+  /** `ip:port` to send a profile to */
+  address: string;
+}
+
+export interface PeerOffer {
+  id: number;
+  fromName: string;
+  fromAddress: string;
+  bundle: ProfileBundle;
+}
+
+export interface BackupSummary {
+  path: string;
+  createdAt: string;
+  /** Number of mods in the backed up mod list */
+  modCount: number;
+}
+
+export interface RestoreReport {
+  restoredFrom: string;
+  /** Backup of the replaced state, can be passed to `restoreFrom` to undo */
+  safetyBackup: string;
+  annotationsMerged: number;
+  modList: ApplyModListReport | null;
+}
+
+export type ImportSourceKind = "cslolManager" | "fantome";
+
+export interface ImportedModInfo {
+  Name: string;
+  Author: string;
+  Version: string;
+  Description: string;
+}
+
+export interface ImportCandidate {
+  /** Folder name in the workspace, used to select the mod in `importMods` */
+  name: string;
+  kind: ImportSourceKind;
+  source: string;
+  info: ImportedModInfo;
+  alreadyImported: boolean;
+}
+
+export interface ImportFailure {
+  name: string;
+  error: string;
+}
+
+export interface ImportReport {
+  imported: string[];
+  skipped: string[];
+  failed: ImportFailure[];
+}
+
+export interface CslolExportFailure {
+  entry: ModListEntry;
+  error: string;
+}
+
+export interface CslolExportReport {
+  /** Folder names in cslol-manager's `installed` directory */
+  exported: string[];
+  failed: CslolExportFailure[];
+  profile: string | null;
+}
+
+export interface CompatEntry {
+  /** Skin id for repository skins, index id for browser mods */
+  modId: string;
+  /** Any champion if unset */
+  championId?: number | null;
+  /** Game version prefix, every version if unset */
+  gameVersion?: string | null;
+  reason: string;
+  reportedAt?: string | null;
+}
+
+export type CompatSource = "bundled" | "remote" | "local";
+
+export interface CompatWarning {
+  source: CompatSource;
+  entry: CompatEntry;
+}
+
+export interface CompatListStatus {
+  sourceUrl: string;
+  fetchedAt: string;
+  count: number;
+}
+
+export interface CrashSuspect {
+  /** Mod folder names of the overlay, e.g. skin ids */
+  mods: string[];
+  exitCode: number;
+  runtimeSecs: number;
+  detectedAt: string;
+}
+
+export interface BisectStatus {
+  /** Mods that may still cause the crash */
+  candidates: ModListEntry[];
+  /** Mods of the overlay for the current round, empty once finished */
+  testing: ModListEntry[];
+  round: number;
+  culprit?: ModListEntry | null;
+}
+
+export interface LastGoodOverlay {
+  modsBaseDir: string;
+  /** Mod folder names of `modsBaseDir`, joined by `/` */
+  modName: string;
+  announcer?: string | null;
+  /** When the game that ran with this overlay exited */
+  playedAt: string;
+}
+
+export interface SkinInfo {
+  id: number;
+  championId: number;
+  championName?: string | null;
+  name: string;
+  /** Whether this is the champion's default skin */
+  isBase: boolean;
+}
+
+export type MetadataProblem = "missing" | "unreadable" | "noSkins";
+
+export interface BrokenMetadata {
+  championId: number;
+  championName: string;
+  problem: MetadataProblem;
+  repaired: boolean;
+}
+
+export interface VerifyReport {
+  checked: number;
+  broken: BrokenMetadata[];
+}
+
+export interface ChampionInfo {
+  id: number;
+  name: string | null;
+  alias: string | null;
+  roles: string[];
+  freeToPlay: boolean | null;
+}
+
+export interface FreeRotation {
+  championIds: number[];
+  fetchedAt: string;
+}
+
+export interface AppUpdateInfo {
+  channel: UpdateChannel;
+  currentVersion: string;
+  version: string;
+  /** Release notes */
+  body: string | null;
+  date: string | null;
+}
+
+export type PluginPermission = "workspace" | "leaguePath" | "network";
+
+export interface PluginCommand {
+  name: string;
+  description: string | null;
+}
+
+export interface PluginInfo {
+  id: string;
+  name: string;
+  version: string;
+  description: string | null;
+  author: string | null;
+  executable: string;
+  permissions: PluginPermission[];
+  commands: PluginCommand[];
+  enabled: boolean;
+}
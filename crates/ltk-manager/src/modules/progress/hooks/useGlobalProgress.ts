@@ -1,20 +1,9 @@
 import { listen } from "@tauri-apps/api/event";
 import { useEffect } from "react";
 
-import { useGlobalProgressContext, type ProgressState } from "../context";
-
-interface ProgressPayload {
-    processed: number;
-    total: number;
-    message: string;
-}
+import { APP_EVENT, type AppEvent } from "@/lib/tauri";
 
-// List of progress event names from backend
-const PROGRESS_EVENTS = [
-    "image-download-progress",
-    "database-update-progress",
-    "metadata-download-progress",
-] as const;
+import { useGlobalProgressContext, type ProgressState } from "../context";
 
 /**
  * Hook to listen to backend progress events and update global progress state.
@@ -24,39 +13,39 @@ export function useProgressListener() {
     const { setProgress } = useGlobalProgressContext();
 
     useEffect(() => {
-        const unlistenPromises = PROGRESS_EVENTS.map((eventName) =>
-            listen<ProgressPayload>(eventName, (event) => {
-                const { processed, total, message } = event.payload;
+        const unlistenPromise = listen<AppEvent>(APP_EVENT, (event) => {
+            const payload = event.payload;
+            if (payload.type !== "progress") {
+                return;
+            }
+
+            const { processed, total, message } = payload;
 
-                // If completed (processed >= total), clear progress after a short delay
-                if (processed >= total && total > 0) {
-                    setProgress({
-                        isActive: true,
-                        message: message || "Completed!",
-                        processed,
-                        total,
-                    });
+            // If completed (processed >= total), clear progress after a short delay
+            if (processed >= total && total > 0) {
+                setProgress({
+                    isActive: true,
+                    message: message || "Completed!",
+                    processed,
+                    total,
+                });
 
-                    // Clear after 1.5 seconds
-                    setTimeout(() => {
-                        setProgress(null);
-                    }, 1500);
-                } else {
-                    setProgress({
-                        isActive: true,
-                        message,
-                        processed,
-                        total,
-                    });
-                }
-            })
-        );
+                // Clear after 1.5 seconds
+                setTimeout(() => {
+                    setProgress(null);
+                }, 1500);
+            } else {
+                setProgress({
+                    isActive: true,
+                    message,
+                    processed,
+                    total,
+                });
+            }
+        });
 
         return () => {
-            // Cleanup all listeners
-            Promise.all(unlistenPromises).then((unlistenFns) => {
-                unlistenFns.forEach((fn) => fn());
-            });
+            unlistenPromise.then((unlisten) => unlisten());
         };
     }, [setProgress]);
 }
@@ -13,9 +13,9 @@ export function useSaveSettings() {
 
   return useMutation<void, AppError, Settings>({
     mutationFn: mutationFn(api.saveSettings),
-    onSuccess: (_data, settings) => {
-      // Update the settings cache with the new values
-      queryClient.setQueryData(settingsKeys.settings(), settings);
+    onSuccess: () => {
+      // The backend keeps fields that other commands own, refetch instead of caching the input
+      queryClient.invalidateQueries({ queryKey: settingsKeys.settings() });
       // Invalidate setup required query as it may have changed
       queryClient.invalidateQueries({ queryKey: settingsKeys.setupRequired() });
     },
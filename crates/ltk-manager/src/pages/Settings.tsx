@@ -4,7 +4,12 @@ import { useEffect, useState } from "react";
 import { LuCircleAlert, LuCircleCheck, LuFolderOpen, LuInfo, LuLoader, LuSparkles } from "react-icons/lu";
 
 import { Button, IconButton } from "@/components/Button";
-import { api, type Settings as SettingsType } from "@/lib/tauri";
+import {
+  api,
+  type Settings as SettingsType,
+  type ToolStatus,
+  type ToolsUpdateInfo,
+} from "@/lib/tauri";
 import { useAppInfo, useSaveSettings, useSettings } from "@/modules/settings";
 import { unwrapForQuery } from "@/utils/query";
 import { useGlobalProgress, useSetGlobalProgress } from "@/modules/progress";
@@ -280,6 +285,8 @@ export function Settings() {
           </div>
         </section>
 
+        <ToolsSection />
+
         {/* About */}
         <section>
           <h3 className="mb-4 text-lg font-medium text-surface-100">About</h3>
@@ -318,3 +325,113 @@ export function Settings() {
     </div>
   );
 }
+
+const TOOL_LABELS: Record<ToolStatus["tool"], string> = {
+  modTools: "Overlay builder",
+  patcherDll: "Patcher",
+};
+
+function ToolsSection() {
+  const [tools, setTools] = useState<ToolStatus[]>([]);
+  const [update, setUpdate] = useState<ToolsUpdateInfo | null>(null);
+  const [isChecking, setIsChecking] = useState(false);
+  const [isInstalling, setIsInstalling] = useState(false);
+
+  async function loadStatus() {
+    try {
+      setTools(unwrapForQuery(await api.getToolsStatus()));
+    } catch (error) {
+      console.error("Failed to get tools status:", error);
+    }
+  }
+
+  useEffect(() => {
+    loadStatus();
+  }, []);
+
+  async function checkForUpdate() {
+    try {
+      setIsChecking(true);
+      setUpdate(unwrapForQuery(await api.checkToolsUpdate()));
+    } catch (error) {
+      console.error("Failed to check for tool updates:", error);
+      alert(`Failed to check for tool updates: ${error instanceof Error ? error.message : error}`);
+    } finally {
+      setIsChecking(false);
+    }
+  }
+
+  async function installLatest() {
+    try {
+      setIsInstalling(true);
+      setUpdate(unwrapForQuery(await api.updateTools()));
+      await loadStatus();
+    } catch (error) {
+      console.error("Failed to update tools:", error);
+      alert(`Failed to update tools: ${error instanceof Error ? error.message : error}`);
+    } finally {
+      setIsInstalling(false);
+    }
+  }
+
+  const installedLabel = update?.installedVersion ?? "bundled with the app";
+
+  return (
+    <section>
+      <h3 className="mb-4 text-lg font-medium text-surface-100">Tools</h3>
+      <div className="rounded-lg border border-surface-800 bg-surface-900 p-4">
+        <div className="flex items-center justify-between gap-4">
+          <div>
+            <h4 className="font-medium text-surface-100">cslol Tools</h4>
+            <p className="text-sm text-surface-500">
+              {update
+                ? `Installed: ${installedLabel} · Latest: ${update.latestVersion}`
+                : "The overlay builder and patcher come from the cslol-manager releases."}
+            </p>
+          </div>
+          <div className="flex shrink-0 gap-2">
+            <Button
+              variant="outline"
+              size="sm"
+              className="whitespace-nowrap"
+              disabled={isChecking || isInstalling}
+              onClick={checkForUpdate}
+            >
+              {isChecking ? "Checking..." : "Check for Updates"}
+            </Button>
+            {update?.updateAvailable && (
+              <Button
+                variant="filled"
+                size="sm"
+                className="whitespace-nowrap"
+                disabled={isChecking || isInstalling}
+                onClick={installLatest}
+              >
+                {isInstalling ? "Installing..." : `Install ${update.latestVersion}`}
+              </Button>
+            )}
+          </div>
+        </div>
+        <ul className="mt-3 space-y-2 border-t border-surface-800 pt-3">
+          {tools.map((status) => (
+            <li key={status.tool} className="flex items-start gap-2 text-sm">
+              {status.problem ? (
+                <LuCircleAlert className="mt-0.5 h-4 w-4 shrink-0 text-red-500" />
+              ) : (
+                <LuCircleCheck className="mt-0.5 h-4 w-4 shrink-0 text-green-500" />
+              )}
+              <div className="min-w-0">
+                <span className="text-surface-200">{TOOL_LABELS[status.tool]}</span>
+                <span className="ml-2 text-surface-500">
+                  {status.fileName}
+                  {status.location && ` (${status.location})`}
+                </span>
+                {status.problem && <p className="text-red-400">{status.problem}</p>}
+              </div>
+            </li>
+          ))}
+        </ul>
+      </div>
+    </section>
+  );
+}
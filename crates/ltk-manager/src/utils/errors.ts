@@ -11,8 +11,11 @@ export type ErrorCode =
   | "LEAGUE_NOT_FOUND"
   | "INVALID_PATH"
   | "MOD_NOT_FOUND"
+  | "CHAMPION_NOT_FOUND"
+  | "SKIN_NOT_FOUND"
   | "VALIDATION_FAILED"
   | "INTERNAL_STATE"
+  | "CANCELLED"
   | "UNKNOWN";
 
 /**
@@ -62,6 +65,31 @@ const ModNotFoundContextSchema = z.object({
  */
 export type ModNotFoundContext = z.infer<typeof ModNotFoundContextSchema>;
 
+/**
+ * Schema for CHAMPION_NOT_FOUND error context
+ */
+const ChampionNotFoundContextSchema = z.object({
+  championId: z.number(),
+});
+
+/**
+ * Context type for CHAMPION_NOT_FOUND errors
+ */
+export type ChampionNotFoundContext = z.infer<typeof ChampionNotFoundContextSchema>;
+
+/**
+ * Schema for SKIN_NOT_FOUND error context
+ */
+const SkinNotFoundContextSchema = z.object({
+  championId: z.number(),
+  skinId: z.number(),
+});
+
+/**
+ * Context type for SKIN_NOT_FOUND errors
+ */
+export type SkinNotFoundContext = z.infer<typeof SkinNotFoundContextSchema>;
+
 /**
  * Get typed and validated context from an INVALID_PATH error.
  * Returns undefined if the error code doesn't match or context validation fails.
@@ -85,3 +113,27 @@ export function getModNotFoundContext(error: AppError): ModNotFoundContext | und
   const result = ModNotFoundContextSchema.safeParse(error.context);
   return result.success ? result.data : undefined;
 }
+
+/**
+ * Get typed and validated context from a CHAMPION_NOT_FOUND error.
+ * Returns undefined if the error code doesn't match or context validation fails.
+ */
+export function getChampionNotFoundContext(error: AppError): ChampionNotFoundContext | undefined {
+  if (error.code !== "CHAMPION_NOT_FOUND" || !error.context) {
+    return undefined;
+  }
+  const result = ChampionNotFoundContextSchema.safeParse(error.context);
+  return result.success ? result.data : undefined;
+}
+
+/**
+ * Get typed and validated context from a SKIN_NOT_FOUND error.
+ * Returns undefined if the error code doesn't match or context validation fails.
+ */
+export function getSkinNotFoundContext(error: AppError): SkinNotFoundContext | undefined {
+  if (error.code !== "SKIN_NOT_FOUND" || !error.context) {
+    return undefined;
+  }
+  const result = SkinNotFoundContextSchema.safeParse(error.context);
+  return result.success ? result.data : undefined;
+}
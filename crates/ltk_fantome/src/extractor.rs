@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::{Cursor, Read, Seek, Write};
 use std::path::Path;
@@ -68,6 +69,45 @@ impl<R: Read + Seek> FantomeExtractor<R> {
         Ok(())
     }
 
+    /// Total uncompressed size of the archive's files, in bytes.
+    ///
+    /// Packed WADs are counted at their archived size, so extracting their (possibly
+    /// compressed) chunks can take more than this.
+    pub fn uncompressed_size(&mut self) -> Result<u64, FantomeExtractError> {
+        let mut total = 0;
+        for i in 0..self.archive.len() {
+            total += self.archive.by_index(i)?.size();
+        }
+        Ok(total)
+    }
+
+    /// Path hashes of the chunks in the archive's packed WADs.
+    ///
+    /// Pass them to [`WadHashtable::with_hash_filter`] to only load the paths extraction
+    /// needs. WAD folders hold their files by path already and add no hashes.
+    pub fn chunk_path_hashes(&mut self) -> Result<HashSet<u64>, FantomeExtractError> {
+        let mut path_hashes = HashSet::new();
+        for i in 0..self.archive.len() {
+            let mut file = self.archive.by_index(i)?;
+            let is_packed_wad = file
+                .name()
+                .strip_prefix("WAD/")
+                .is_some_and(|relative_path| {
+                    !relative_path.contains('/') && is_wad_file_name(relative_path)
+                });
+            if file.is_dir() || !is_packed_wad {
+                continue;
+            }
+
+            let mut wad_data = Vec::new();
+            file.read_to_end(&mut wad_data)?;
+            let wad = Wad::mount(Cursor::new(wad_data))?;
+            path_hashes.extend(wad.chunks().keys().copied());
+        }
+
+        Ok(path_hashes)
+    }
+
     /// Read the metadata from the Fantome package.
     pub fn read_metadata(&mut self) -> Result<FantomeInfo, FantomeExtractError> {
         let mut info_file = self
@@ -110,6 +150,7 @@ impl<R: Read + Seek> FantomeExtractor<R> {
             transformers: vec![],
             layers: default_layers(),
             thumbnail: None,
+            wad_ignore: vec![],
         };
 
         if !output_dir.exists() {
@@ -258,10 +299,12 @@ mod tests {
         let cursor = Cursor::new(fantome_data);
 
         let mut extractor = FantomeExtractor::new(cursor).unwrap();
+        assert!(extractor.uncompressed_size().unwrap() > b"test content".len() as u64);
 
         let temp_dir = tempdir().unwrap();
         let result = extractor.extract_to(temp_dir.path()).unwrap();
 
+        assert!(extractor.chunk_path_hashes().unwrap().is_empty());
         assert_eq!(result.mod_project.display_name, "Test Mod");
         assert_eq!(result.mod_project.version, "1.0.0");
 
@@ -303,4 +346,26 @@ mod tests {
         let result = extractor.validate();
         assert!(matches!(result, Err(FantomeExtractError::RawUnsupported)));
     }
+
+    #[test]
+    fn test_chunk_path_hashes_of_packed_wads() {
+        let mut wad = Cursor::new(Vec::new());
+        ltk_wad::WadBuilder::default()
+            .with_chunk(ltk_wad::WadChunkBuilder::default().with_path("assets/one.bin"))
+            .with_chunk(ltk_wad::WadChunkBuilder::default().with_path("assets/two.bin"))
+            .build_to_writer(&mut wad, |_, cursor| {
+                cursor.write_all(b"data")?;
+                Ok(())
+            })
+            .unwrap();
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("WAD/test.wad.client", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(wad.get_ref()).unwrap();
+        let buffer = zip.finish().unwrap().into_inner();
+
+        let mut extractor = FantomeExtractor::new(Cursor::new(buffer)).unwrap();
+        assert_eq!(extractor.chunk_path_hashes().unwrap().len(), 2);
+    }
 }
@@ -1,39 +1,113 @@
 //! WAD hashtable for resolving path hashes to human-readable paths.
 
-use camino::Utf8Path;
+use camino::{Utf8Path, Utf8PathBuf};
 use ltk_wad::PathResolver;
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Read},
+    path::Path,
+    sync::OnceLock,
 };
 use walkdir::WalkDir;
 
 use crate::error::FantomeExtractError;
+use crate::hashtable_cache::{self, CompiledHashlist};
 
 /// Formats a chunk path hash as a hexadecimal string.
 pub fn format_chunk_path_hash(path_hash: u64) -> String {
     format!("{:016x}", path_hash)
 }
 
+/// Two hashtable entries that map the same path hash to different paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WadHashtableConflict {
+    /// The path hash both entries share.
+    pub path_hash: u64,
+    /// The path that was loaded first and got replaced.
+    pub previous_path: String,
+    /// The path that is now used for the hash.
+    pub path: String,
+}
+
+/// Location of a path in [`WadHashtable`]'s path buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PathSpan {
+    pub(crate) start: usize,
+    pub(crate) len: usize,
+}
+
 /// A hashtable that maps WAD path hashes to their original paths.
 ///
 /// WAD files store file paths as 64-bit hashes. This hashtable allows
 /// resolving those hashes back to human-readable paths during extraction.
+///
+/// The game hashlists hold millions of paths, so they are stored back to back in a
+/// single buffer instead of one allocation per path.
+///
+/// When several loaded files disagree about the path of a hash, the last one
+/// wins and the disagreement is recorded in [`WadHashtable::conflicts`].
 #[derive(Debug, Clone, Default)]
 pub struct WadHashtable {
-    items: HashMap<u64, String>,
+    paths: String,
+    items: HashMap<u64, PathSpan>,
+    conflicts: Vec<WadHashtableConflict>,
+    path_filter: Option<String>,
+    hash_filter: Option<HashSet<u64>>,
+    cache_dir: Option<Utf8PathBuf>,
+    /// Owned copy of the entries for [`WadHashtable::items`], built on first use.
+    items_map: OnceLock<HashMap<u64, String>>,
 }
 
 impl WadHashtable {
     /// Creates a new empty hashtable.
     pub fn new() -> Self {
         WadHashtable {
+            paths: String::new(),
             items: HashMap::default(),
+            conflicts: Vec::new(),
+            path_filter: None,
+            hash_filter: None,
+            cache_dir: None,
+            items_map: OnceLock::new(),
         }
     }
 
+    /// Only load entries whose path contains `filter`, e.g. `characters/ahri`.
+    ///
+    /// Loading still reads every line, but the other paths are never stored, which keeps
+    /// memory low when a command only needs part of the game. Hashlist paths are lowercase,
+    /// so the filter is matched in lowercase. Entries added with [`WadHashtable::insert`]
+    /// are not filtered.
+    pub fn with_path_filter(mut self, filter: impl Into<String>) -> Self {
+        self.path_filter = Some(filter.into().to_lowercase());
+        self
+    }
+
+    /// Only load entries for `path_hashes`, e.g. the chunks of the WADs being extracted.
+    ///
+    /// Files are then read line by line instead of at once, so memory stays proportional to
+    /// the matching entries instead of the size of the hashlist. Compiled copies from
+    /// [`WadHashtable::with_cache_dir`] are filtered while they are read the same way, only
+    /// compiling a missing or outdated copy holds the whole file once. Entries added with
+    /// [`WadHashtable::insert`] are not filtered.
+    pub fn with_hash_filter(mut self, path_hashes: impl IntoIterator<Item = u64>) -> Self {
+        self.hash_filter = Some(path_hashes.into_iter().collect());
+        self
+    }
+
+    /// Keep a compiled copy of every file loaded with [`WadHashtable::add_from_dir`] in
+    /// `cache_dir`.
+    ///
+    /// The first load parses the text file and writes the copy. Later loads read the copy
+    /// instead, until the size or modification time of the text file changes. The copy holds
+    /// every entry, so the same cache serves loads with any path filter.
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<Utf8PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
     /// Creates a hashtable by loading all files from a directory recursively.
     pub fn from_directory(dir: impl AsRef<Utf8Path>) -> Result<Self, FantomeExtractError> {
         let mut hashtable = Self::new();
@@ -41,15 +115,21 @@ impl WadHashtable {
         Ok(hashtable)
     }
 
+    /// Returns the path of a hash, if it is known.
+    pub fn get(&self, path_hash: u64) -> Option<&str> {
+        self.items.get(&path_hash).map(|span| self.path(*span))
+    }
+
     /// Resolves a path hash to its original path, or returns a hex string if not found.
     pub fn resolve_path(&self, path_hash: u64) -> Cow<'_, str> {
-        self.items
-            .get(&path_hash)
-            .map(|s| Cow::Borrowed(s.as_str()))
+        self.get(path_hash)
+            .map(Cow::Borrowed)
             .unwrap_or_else(|| Cow::Owned(format_chunk_path_hash(path_hash)))
     }
 
     /// Loads hashtable entries from all files in a directory recursively.
+    ///
+    /// Files are loaded in file name order, so which path wins a conflict is stable.
     pub fn add_from_dir(&mut self, dir: impl AsRef<Utf8Path>) -> Result<(), FantomeExtractError> {
         let dir_path = dir.as_ref();
         if !dir_path.exists() {
@@ -57,6 +137,7 @@ impl WadHashtable {
         }
 
         for entry in WalkDir::new(dir_path.as_std_path())
+            .sort_by_file_name()
             .into_iter()
             .filter_map(|x| x.ok())
         {
@@ -64,44 +145,187 @@ impl WadHashtable {
                 continue;
             }
 
-            self.add_from_file(&File::open(entry.path())?)?;
+            match self.cache_dir.clone() {
+                // The cache may live inside the hashtable directory
+                Some(cache_dir) if entry.path().starts_with(&cache_dir) => continue,
+                Some(cache_dir) => self.add_from_cache(entry.path(), &cache_dir)?,
+                None => self.add_from_file(&File::open(entry.path())?)?,
+            }
         }
 
         Ok(())
     }
 
+    /// Loads `source` from its compiled copy in `cache_dir`, compiling it first if needed.
+    fn add_from_cache(
+        &mut self,
+        source: &Path,
+        cache_dir: &Utf8Path,
+    ) -> Result<(), FantomeExtractError> {
+        let metadata = std::fs::metadata(source)?;
+        let cache_path = hashtable_cache::cache_path(cache_dir.as_std_path(), source);
+
+        let hash_filter = self.hash_filter.as_ref();
+        let compiled = match hashtable_cache::read(&cache_path, &metadata, hash_filter) {
+            Some(compiled) => compiled,
+            None => {
+                let content = std::fs::read_to_string(source)?;
+                let compiled = CompiledHashlist::compile(&content);
+                // The copy only speeds up later loads, failing to write it isn't an error
+                let _ = hashtable_cache::write(&cache_path, &metadata, &compiled);
+                compiled
+            }
+        };
+
+        self.add_compiled(compiled);
+        Ok(())
+    }
+
+    fn add_compiled(&mut self, compiled: CompiledHashlist) {
+        let CompiledHashlist {
+            paths,
+            entries,
+            conflicts,
+        } = compiled;
+
+        let path_filter = self.path_filter.clone();
+        let hash_filter = self.hash_filter.take();
+        let keep = |path_hash: u64, path: &str| {
+            hash_filter
+                .as_ref()
+                .is_none_or(|hashes| hashes.contains(&path_hash))
+                && path_filter
+                    .as_deref()
+                    .is_none_or(|filter| path.contains(filter))
+        };
+        self.conflicts.extend(
+            conflicts
+                .into_iter()
+                .filter(|c| keep(c.path_hash, &c.previous_path) && keep(c.path_hash, &c.path)),
+        );
+
+        // With nothing loaded yet there is nothing to conflict with, take the buffer as is
+        if self.items.is_empty() && path_filter.is_none() && hash_filter.is_none() {
+            self.items_map = OnceLock::new();
+            self.paths = paths;
+            self.items = entries.into_iter().collect();
+            return;
+        }
+
+        for (path_hash, span) in entries {
+            let path = &paths[span.start..span.start + span.len];
+            if keep(path_hash, path) {
+                self.insert(path_hash, path);
+            }
+        }
+        self.hash_filter = hash_filter;
+    }
+
     /// Loads hashtable entries from a single file.
     ///
     /// File format: Each line contains a hex hash followed by a space and the path.
     /// Example: `0123456789abcdef assets/characters/aatrox/skin0.bin`
-    pub fn add_from_file(&mut self, file: &File) -> Result<(), FantomeExtractError> {
-        let reader = BufReader::new(file);
-        let lines = reader.lines();
+    ///
+    /// The file is read at once and large files are parsed on several threads. Entries are
+    /// still inserted in file order, so conflicts resolve the same as a sequential load.
+    /// With a hash filter the file is streamed instead, see [`WadHashtable::with_hash_filter`].
+    pub fn add_from_file(&mut self, mut file: &File) -> Result<(), FantomeExtractError> {
+        if let Some(hash_filter) = self.hash_filter.take() {
+            let result = self.add_streamed(file, &hash_filter);
+            self.hash_filter = Some(hash_filter);
+            return result;
+        }
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
 
-        for line in lines {
-            let line = line?;
-            let mut components = line.split(' ');
+        let parsed = parse_content(&content, self.path_filter.as_deref());
 
-            let Some(hash_str) = components.next() else {
-                continue; // Skip empty lines
-            };
+        // Without a filter the paths make up most of the file, reserve for them up front
+        if self.path_filter.is_none() {
+            self.paths.reserve(content.len());
+        }
+        self.items.reserve(parsed.iter().map(Vec::len).sum());
+        for (hash, path) in parsed.into_iter().flatten() {
+            self.insert(hash, path);
+        }
 
-            let Ok(hash) = u64::from_str_radix(hash_str, 16) else {
-                continue; // Skip invalid hashes
-            };
+        Ok(())
+    }
 
-            let path: String = itertools::join(components, " ");
-            if !path.is_empty() {
-                self.items.insert(hash, path);
+    /// Loads the entries of `file` whose hash is in `hash_filter`, one line at a time.
+    fn add_streamed(
+        &mut self,
+        file: &File,
+        hash_filter: &HashSet<u64>,
+    ) -> Result<(), FantomeExtractError> {
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            let entry = parse_line(
+                line.trim_end_matches(['\r', '\n']),
+                self.path_filter.as_deref(),
+            )
+            .filter(|(path_hash, _)| hash_filter.contains(path_hash));
+            if let Some((path_hash, path)) = entry {
+                self.insert(path_hash, path);
             }
+            line.clear();
         }
 
         Ok(())
     }
 
-    /// Returns a reference to the internal hashmap.
+    /// Inserts a single entry, recording a conflict if the hash already maps to a different path.
+    pub fn insert(&mut self, path_hash: u64, path: impl AsRef<str>) {
+        let path = path.as_ref();
+        if let Some(previous_path) = self.get(path_hash) {
+            if previous_path == path {
+                return;
+            }
+
+            self.conflicts.push(WadHashtableConflict {
+                path_hash,
+                previous_path: previous_path.to_string(),
+                path: path.to_string(),
+            });
+        }
+
+        let span = PathSpan {
+            start: self.paths.len(),
+            len: path.len(),
+        };
+        self.paths.push_str(path);
+        self.items.insert(path_hash, span);
+        self.items_map = OnceLock::new();
+    }
+
+    /// Returns the entries that mapped an already known hash to a different path.
+    pub fn conflicts(&self) -> &[WadHashtableConflict] {
+        &self.conflicts
+    }
+
+    /// Iterates over all hashes and their paths, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &str)> + '_ {
+        self.items
+            .iter()
+            .map(|(path_hash, span)| (*path_hash, self.path(*span)))
+    }
+
+    /// Returns all entries as a map of hashes to paths.
+    ///
+    /// Paths are stored in a single buffer, so the map is an extra copy of every path. It is
+    /// built on the first call after the hashtable changed.
+    #[deprecated(
+        since = "0.1.5",
+        note = "use `iter` or `get`, which don't copy the paths"
+    )]
     pub fn items(&self) -> &HashMap<u64, String> {
-        &self.items
+        self.items_map.get_or_init(|| {
+            self.iter()
+                .map(|(path_hash, path)| (path_hash, path.to_string()))
+                .collect()
+        })
     }
 
     /// Returns the number of entries in the hashtable.
@@ -113,13 +337,244 @@ impl WadHashtable {
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
     }
+
+    fn path(&self, span: PathSpan) -> &str {
+        &self.paths[span.start..span.start + span.len]
+    }
+}
+
+/// Parse the entries of a hashlist file in file order, on several threads for large files.
+///
+/// Returns one list of entries per part of the file that was parsed on its own.
+pub(crate) fn parse_content<'a>(
+    content: &'a str,
+    path_filter: Option<&str>,
+) -> Vec<Vec<(u64, &'a str)>> {
+    let chunks = split_lines(content, parse_threads(content.len()));
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(move || parse_lines(chunk, path_filter)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("hashtable parser thread panicked"))
+            .collect()
+    })
+}
+
+/// Files below this size are parsed on the calling thread.
+const MIN_PARALLEL_PARSE_LEN: usize = 1 << 20;
+
+fn parse_threads(len: usize) -> usize {
+    if len < MIN_PARALLEL_PARSE_LEN {
+        return 1;
+    }
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Split `content` into up to `count` parts of roughly equal size, each ending at a line break.
+fn split_lines(content: &str, count: usize) -> Vec<&str> {
+    let target_len = content.len().div_ceil(count.max(1));
+    let mut chunks = Vec::with_capacity(count);
+    let mut rest = content;
+
+    while !rest.is_empty() {
+        let end = match rest.get(target_len..).and_then(|tail| tail.find('\n')) {
+            Some(offset) => target_len + offset + 1,
+            None => rest.len(),
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+
+    chunks
+}
+
+fn parse_lines<'a>(chunk: &'a str, path_filter: Option<&str>) -> Vec<(u64, &'a str)> {
+    chunk
+        .lines()
+        .filter_map(|line| parse_line(line, path_filter))
+        .collect()
+}
+
+/// Parse a `{hash} {path}` line, `None` for malformed lines and paths the filter rejects.
+fn parse_line<'a>(line: &'a str, path_filter: Option<&str>) -> Option<(u64, &'a str)> {
+    let (hash_str, path) = line.split_once(' ')?;
+    if path.is_empty() || path_filter.is_some_and(|filter| !path.contains(filter)) {
+        return None;
+    }
+    let hash = u64::from_str_radix(hash_str, 16).ok()?;
+    Some((hash, path))
 }
 
 impl PathResolver for WadHashtable {
     fn resolve(&self, path_hash: u64) -> Cow<'_, str> {
-        self.items
-            .get(&path_hash)
-            .map(|s| Cow::Borrowed(s.as_str()))
-            .unwrap_or_else(|| Cow::Owned(format_chunk_path_hash(path_hash)))
+        self.resolve_path(path_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_conflicting_entries_are_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut first = File::create(dir.path().join("a.txt")).unwrap();
+        writeln!(first, "0000000000000001 assets/one.bin").unwrap();
+        writeln!(first, "0000000000000002 assets/two.bin").unwrap();
+        let mut second = File::create(dir.path().join("b.txt")).unwrap();
+        writeln!(second, "0000000000000001 assets/one.bin").unwrap();
+        writeln!(second, "0000000000000002 assets/other.bin").unwrap();
+
+        let dir = Utf8Path::from_path(dir.path()).unwrap();
+        let hashtable = WadHashtable::from_directory(dir).unwrap();
+
+        assert_eq!(hashtable.len(), 2);
+        assert_eq!(hashtable.resolve_path(2), "assets/other.bin");
+        assert_eq!(hashtable.get(1), Some("assets/one.bin"));
+        assert_eq!(
+            hashtable.conflicts(),
+            &[WadHashtableConflict {
+                path_hash: 2,
+                previous_path: "assets/two.bin".to_string(),
+                path: "assets/other.bin".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_paths_keep_spaces_and_drop_line_endings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hashes.txt");
+        std::fs::write(
+            &path,
+            "0000000000000001 assets/with space.bin\r\n\nnot-a-hash x\n0000000000000002 b.bin",
+        )
+        .unwrap();
+
+        let mut hashtable = WadHashtable::new();
+        hashtable
+            .add_from_file(&File::open(&path).unwrap())
+            .unwrap();
+
+        let mut entries: Vec<_> = hashtable.iter().collect();
+        entries.sort();
+        assert_eq!(entries, [(1, "assets/with space.bin"), (2, "b.bin")]);
+        assert_eq!(hashtable.resolve_path(3), "0000000000000003");
+    }
+
+    #[test]
+    fn test_split_lines_keeps_whole_lines() {
+        let content = "1 a\n2 bb\n3 ccc\n4 dddd";
+        for count in 1..6 {
+            let chunks = split_lines(content, count);
+            assert_eq!(chunks.concat(), content);
+            assert!(chunks[..chunks.len() - 1].iter().all(|c| c.ends_with('\n')));
+        }
+        assert!(split_lines("", 4).is_empty());
+    }
+
+    #[test]
+    fn test_path_filter_skips_other_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hashes.txt");
+        std::fs::write(
+            &path,
+            "0000000000000001 data/characters/ahri/skins/skin0.bin\n\
+             0000000000000002 data/characters/annie/skins/skin0.bin\n",
+        )
+        .unwrap();
+
+        let mut hashtable = WadHashtable::new().with_path_filter("Characters/Ahri");
+        hashtable
+            .add_from_file(&File::open(&path).unwrap())
+            .unwrap();
+
+        assert_eq!(hashtable.len(), 1);
+        assert_eq!(
+            hashtable.get(1),
+            Some("data/characters/ahri/skins/skin0.bin")
+        );
+        assert_eq!(hashtable.get(2), None);
+    }
+
+    #[test]
+    fn test_cached_load_matches_text_load() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("a.txt"),
+            "0000000000000001 assets/one.bin\n0000000000000002 assets/two.bin\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("b.txt"),
+            "0000000000000002 assets/other.bin\n0000000000000003 assets/three.bin\n",
+        )
+        .unwrap();
+
+        let dir = Utf8Path::from_path(dir.path()).unwrap();
+        let cache_dir = dir.join("cache");
+        let uncached = WadHashtable::from_directory(dir).unwrap();
+        // The first load writes the copies into the hashtable directory, the second reads them
+        for _ in 0..2 {
+            let mut cached = WadHashtable::new().with_cache_dir(&cache_dir);
+            cached.add_from_dir(dir).unwrap();
+
+            let mut entries: Vec<_> = cached.iter().collect();
+            entries.sort();
+            let mut expected: Vec<_> = uncached.iter().collect();
+            expected.sort();
+            assert_eq!(entries, expected);
+            assert_eq!(cached.conflicts(), uncached.conflicts());
+        }
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_hash_filter_keeps_only_listed_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hashes.txt");
+        std::fs::write(
+            &path,
+            "0000000000000001 assets/one.bin\r\n0000000000000002 assets/two.bin\n\
+             0000000000000003 assets/three.bin\n0000000000000001 assets/uno.bin",
+        )
+        .unwrap();
+
+        let mut hashtable = WadHashtable::new().with_hash_filter([1, 3]);
+        hashtable
+            .add_from_file(&File::open(&path).unwrap())
+            .unwrap();
+
+        let mut entries: Vec<_> = hashtable.iter().collect();
+        entries.sort();
+        assert_eq!(entries, [(1, "assets/uno.bin"), (3, "assets/three.bin")]);
+        assert_eq!(hashtable.conflicts().len(), 1);
+
+        // Compiled copies are filtered the same way
+        let dir = Utf8Path::from_path(dir.path()).unwrap();
+        let mut cached = WadHashtable::new()
+            .with_hash_filter([2])
+            .with_cache_dir(dir.join("cache"));
+        cached.add_from_dir(dir).unwrap();
+        assert_eq!(cached.iter().collect::<Vec<_>>(), [(2, "assets/two.bin")]);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_items_follows_inserts() {
+        let mut hashtable = WadHashtable::new();
+        hashtable.insert(1, "assets/one.bin");
+        assert_eq!(
+            hashtable.items().get(&1).map(String::as_str),
+            Some("assets/one.bin")
+        );
+
+        hashtable.insert(2, "assets/two.bin");
+        assert_eq!(hashtable.items().len(), 2);
     }
 }
@@ -0,0 +1,413 @@
+//! Compiled copies of hashlist files for [`WadHashtable::with_cache_dir`].
+//!
+//! A copy holds the parsed entries of one text file with duplicates already resolved, so
+//! loading it skips splitting lines, parsing hex and looking up conflicts. The header records
+//! the size and modification time of the text file, a copy that doesn't match is compiled
+//! again. It also holds a checksum of the rest of the copy, so a copy that was cut short or
+//! damaged is compiled again too.
+//!
+//! [`WadHashtable::with_cache_dir`]: crate::WadHashtable::with_cache_dir
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, Metadata};
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::hashtable::{PathSpan, WadHashtableConflict, parse_content};
+
+const MAGIC: &[u8; 8] = b"LTKHASH\0";
+/// Bumped whenever the layout below changes, older copies are compiled again.
+const FORMAT_VERSION: u32 = 2;
+
+/// The entries of one hashlist file.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct CompiledHashlist {
+    pub(crate) paths: String,
+    /// One entry per hash, with the path of its last line.
+    pub(crate) entries: Vec<(u64, PathSpan)>,
+    /// Lines of the file that changed the path of a hash it already listed.
+    pub(crate) conflicts: Vec<WadHashtableConflict>,
+}
+
+impl CompiledHashlist {
+    pub(crate) fn compile(content: &str) -> Self {
+        let mut compiled = Self::default();
+        compiled.paths.reserve(content.len());
+        let mut index: HashMap<u64, usize> = HashMap::new();
+
+        for (path_hash, path) in parse_content(content, None).into_iter().flatten() {
+            let existing = index.get(&path_hash).copied();
+            if let Some(existing) = existing {
+                let previous_path = compiled.path(compiled.entries[existing].1);
+                if previous_path == path {
+                    continue;
+                }
+                compiled.conflicts.push(WadHashtableConflict {
+                    path_hash,
+                    previous_path: previous_path.to_string(),
+                    path: path.to_string(),
+                });
+            }
+
+            let span = PathSpan {
+                start: compiled.paths.len(),
+                len: path.len(),
+            };
+            compiled.paths.push_str(path);
+            match existing {
+                Some(existing) => compiled.entries[existing].1 = span,
+                None => {
+                    index.insert(path_hash, compiled.entries.len());
+                    compiled.entries.push((path_hash, span));
+                }
+            }
+        }
+
+        compiled
+    }
+
+    fn path(&self, span: PathSpan) -> &str {
+        &self.paths[span.start..span.start + span.len]
+    }
+}
+
+/// Where the copy of `source` is stored in `cache_dir`.
+///
+/// The name keeps the file name for readability and adds a hash of the full path, so files
+/// with the same name in different directories get their own copies.
+pub(crate) fn cache_path(cache_dir: &Path, source: &Path) -> PathBuf {
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let path_hash = fnv1a(source.as_os_str().as_encoded_bytes());
+    cache_dir.join(format!("{}-{:016x}.bin", file_name, path_hash))
+}
+
+/// Read the copy at `cache_path`, `None` if it is missing, damaged or outdated.
+///
+/// With `hash_filter` only the entries and conflicts of those hashes are kept. The copy is
+/// still read front to back to check it, but the other paths are never held in memory.
+pub(crate) fn read(
+    cache_path: &Path,
+    source: &Metadata,
+    hash_filter: Option<&HashSet<u64>>,
+) -> Option<CompiledHashlist> {
+    let mut reader = Reader::new(BufReader::new(File::open(cache_path).ok()?));
+
+    if reader.array::<8>()? != *MAGIC
+        || reader.u32()? != FORMAT_VERSION
+        || reader.u64()? != source.len()
+        || reader.u64()? != modified_nanos(source)
+    {
+        return None;
+    }
+    let checksum = reader.u64()?;
+    reader.checksum = Fnv1a::default();
+
+    let entry_count = reader.len()?;
+    let conflict_count = reader.len()?;
+    let paths_len = reader.len()?;
+
+    let keep = |path_hash: u64| hash_filter.is_none_or(|hashes| hashes.contains(&path_hash));
+
+    // Counts come from the file, grow the lists as entries are read instead of trusting them
+    let mut entries = Vec::new();
+    for _ in 0..entry_count {
+        let path_hash = reader.u64()?;
+        let start = reader.u32()? as usize;
+        let len = reader.u32()? as usize;
+        if keep(path_hash) {
+            entries.push((path_hash, PathSpan { start, len }));
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    for _ in 0..conflict_count {
+        let path_hash = reader.u64()?;
+        let previous_path = reader.string()?;
+        let path = reader.string()?;
+        if keep(path_hash) {
+            conflicts.push(WadHashtableConflict {
+                path_hash,
+                previous_path,
+                path,
+            });
+        }
+    }
+
+    let paths = match hash_filter {
+        None => {
+            let paths = String::from_utf8(reader.bytes(paths_len)?).ok()?;
+            let spans_valid = entries
+                .iter()
+                .all(|(_, span)| paths.get(span.start..span.start + span.len).is_some());
+            spans_valid.then_some(paths)?
+        }
+        Some(_) => read_spans(&mut reader, paths_len, &mut entries)?,
+    };
+    if reader.checksum.0 != checksum {
+        return None;
+    }
+
+    Some(CompiledHashlist {
+        paths,
+        entries,
+        conflicts,
+    })
+}
+
+/// Read only the paths of `entries` from a path buffer of `paths_len` bytes, pointing the
+/// entries at their place in the returned buffer.
+fn read_spans<R: Read>(
+    reader: &mut Reader<R>,
+    paths_len: usize,
+    entries: &mut [(u64, PathSpan)],
+) -> Option<String> {
+    entries.sort_unstable_by_key(|(_, span)| span.start);
+
+    let mut paths = String::new();
+    let mut position = 0;
+    for (_, span) in entries.iter_mut() {
+        // Compiled spans never overlap, overlapping ones mean the copy is damaged
+        if span.start < position || span.start + span.len > paths_len {
+            return None;
+        }
+        reader.skip(span.start - position)?;
+        let path = String::from_utf8(reader.bytes(span.len)?).ok()?;
+        position = span.start + span.len;
+
+        span.start = paths.len();
+        paths.push_str(&path);
+    }
+    reader.skip(paths_len - position)?;
+
+    Some(paths)
+}
+
+/// Write the copy of `compiled` for the text file described by `source`.
+pub(crate) fn write(
+    cache_path: &Path,
+    source: &Metadata,
+    compiled: &CompiledHashlist,
+) -> io::Result<()> {
+    // Spans are stored as 32 bit, a path buffer that doesn't fit is not worth caching
+    if u32::try_from(compiled.paths.len()).is_err() {
+        return Ok(());
+    }
+
+    let mut payload = Vec::with_capacity(24 + compiled.entries.len() * 16 + compiled.paths.len());
+    payload.extend_from_slice(&(compiled.entries.len() as u64).to_le_bytes());
+    payload.extend_from_slice(&(compiled.conflicts.len() as u64).to_le_bytes());
+    payload.extend_from_slice(&(compiled.paths.len() as u64).to_le_bytes());
+    for (path_hash, span) in &compiled.entries {
+        payload.extend_from_slice(&path_hash.to_le_bytes());
+        payload.extend_from_slice(&(span.start as u32).to_le_bytes());
+        payload.extend_from_slice(&(span.len as u32).to_le_bytes());
+    }
+    for conflict in &compiled.conflicts {
+        payload.extend_from_slice(&conflict.path_hash.to_le_bytes());
+        for path in [&conflict.previous_path, &conflict.path] {
+            payload.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            payload.extend_from_slice(path.as_bytes());
+        }
+    }
+    payload.extend_from_slice(compiled.paths.as_bytes());
+
+    let mut header = Vec::with_capacity(36);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    header.extend_from_slice(&source.len().to_le_bytes());
+    header.extend_from_slice(&modified_nanos(source).to_le_bytes());
+    header.extend_from_slice(&fnv1a(&payload).to_le_bytes());
+
+    let cache_dir = cache_path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(cache_dir)?;
+    // Write to a temporary file of our own first, so neither a concurrent load nor another
+    // process compiling the same file ever sees half a copy
+    let mut temp_file = tempfile::NamedTempFile::new_in(cache_dir)?;
+    temp_file.write_all(&header)?;
+    temp_file.write_all(&payload)?;
+    temp_file.persist(cache_path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+fn modified_nanos(metadata: &Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |since_epoch| since_epoch.as_nanos() as u64)
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hasher = Fnv1a::default();
+    hasher.update(bytes);
+    hasher.0
+}
+
+/// 64 bit FNV-1a, enough to notice a damaged copy.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv1a {
+    fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+impl Write for Fnv1a {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Little-endian reader over a copy that hashes everything it reads, every read fails once
+/// the copy runs out.
+struct Reader<R> {
+    inner: R,
+    checksum: Fnv1a,
+}
+
+impl<R: Read> Reader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            checksum: Fnv1a::default(),
+        }
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let mut bytes = [0; N];
+        self.inner.read_exact(&mut bytes).ok()?;
+        self.checksum.update(&bytes);
+        Some(bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        usize::try_from(self.u64()?).ok()
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        (&mut self.inner)
+            .take(len as u64)
+            .read_to_end(&mut bytes)
+            .ok()?;
+        if bytes.len() != len {
+            return None;
+        }
+        self.checksum.update(&bytes);
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        let skipped = io::copy(&mut (&mut self.inner).take(len as u64), &mut self.checksum).ok()?;
+        (skipped == len as u64).then_some(())
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.bytes(len)?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_keeps_last_path_and_records_conflicts() {
+        let compiled = CompiledHashlist::compile(
+            "0000000000000001 a.bin\n0000000000000002 b.bin\n0000000000000001 c.bin\n0000000000000002 b.bin\n",
+        );
+
+        let entries: Vec<_> = compiled
+            .entries
+            .iter()
+            .map(|(path_hash, span)| (*path_hash, compiled.path(*span)))
+            .collect();
+        assert_eq!(entries, [(1, "c.bin"), (2, "b.bin")]);
+        assert_eq!(
+            compiled.conflicts,
+            [WadHashtableConflict {
+                path_hash: 1,
+                previous_path: "a.bin".to_string(),
+                path: "c.bin".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_copy_round_trips_and_goes_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("hashes.txt");
+        std::fs::write(&source, "0000000000000001 a.bin\n0000000000000001 b.bin\n").unwrap();
+        let metadata = std::fs::metadata(&source).unwrap();
+
+        let compiled = CompiledHashlist::compile(&std::fs::read_to_string(&source).unwrap());
+        let cache_path = cache_path(&dir.path().join("cache"), &source);
+        write(&cache_path, &metadata, &compiled).unwrap();
+        assert_eq!(read(&cache_path, &metadata, None), Some(compiled));
+
+        // A damaged copy is rejected even though the source is unchanged
+        let mut bytes = std::fs::read(&cache_path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        std::fs::write(&cache_path, bytes).unwrap();
+        assert_eq!(read(&cache_path, &metadata, None), None);
+
+        std::fs::write(&source, "0000000000000001 a.bin\n").unwrap();
+        assert_eq!(
+            read(&cache_path, &std::fs::metadata(&source).unwrap(), None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_filtered_read_keeps_only_listed_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("hashes.txt");
+        std::fs::write(
+            &source,
+            "0000000000000001 a.bin\n0000000000000002 b.bin\n0000000000000003 c.bin\n",
+        )
+        .unwrap();
+        let metadata = std::fs::metadata(&source).unwrap();
+
+        let compiled = CompiledHashlist::compile(&std::fs::read_to_string(&source).unwrap());
+        let cache_path = cache_path(&dir.path().join("cache"), &source);
+        write(&cache_path, &metadata, &compiled).unwrap();
+
+        let filtered = read(&cache_path, &metadata, Some(&HashSet::from([3, 1]))).unwrap();
+        let mut entries: Vec<_> = filtered
+            .entries
+            .iter()
+            .map(|(path_hash, span)| (*path_hash, filtered.path(*span)))
+            .collect();
+        entries.sort();
+        assert_eq!(entries, [(1, "a.bin"), (3, "c.bin")]);
+        assert_eq!(filtered.paths, "a.binc.bin");
+    }
+}
@@ -1,7 +1,10 @@
+use camino::Utf8Path;
 use eyre::Result;
 use image::ImageFormat;
-use ltk_mod_project::{ModProject, ModProjectAuthor, ModProjectLayer};
+use ltk_mod_project::{ModProject, ModProjectAuthor, ModProjectLayer, WadIgnore};
+use ltk_modpkg::project::apply_bin_patches;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{File, read_dir};
 use std::io::Write;
 use std::path::Path;
@@ -10,10 +13,11 @@ use zip::{ZipWriter, write::SimpleFileOptions};
 pub mod error;
 mod extractor;
 mod hashtable;
+mod hashtable_cache;
 
 pub use error::FantomeExtractError;
 pub use extractor::{FantomeExtractResult, FantomeExtractor};
-pub use hashtable::{WadHashtable, format_chunk_path_hash};
+pub use hashtable::{WadHashtable, WadHashtableConflict, format_chunk_path_hash};
 
 /// Fantome metadata structure that goes into info.json
 #[derive(Serialize, Deserialize, Debug)]
@@ -65,18 +69,25 @@ pub fn has_unsupported_layers(mod_project: &ModProject) -> bool {
 }
 
 /// Pack a mod project into a Fantome .zip format
+///
+/// Files matching the project's ignore rules are skipped, see [`WadIgnore`]. Bin patches of
+/// the base layer are applied like when packing to modpkg.
 pub fn pack_to_fantome<W: Write + std::io::Seek>(
     writer: W,
     mod_project: &ModProject,
     project_root: &Path,
 ) -> Result<()> {
     let mut zip = ZipWriter::new(writer);
+    // A fixed timestamp keeps the archive identical when the same project is packed again
     let options = SimpleFileOptions::default()
         .compression_method(zip::CompressionMethod::Deflated)
+        .last_modified_time(zip::DateTime::default())
         .unix_permissions(0o755);
 
     // Pack base layer WAD files
-    pack_base_layer(&mut zip, project_root, &options)?;
+    let wad_ignore = WadIgnore::load(project_root, mod_project)?;
+    let patched_bins = patched_base_bins(project_root, mod_project)?;
+    pack_base_layer(&mut zip, project_root, &wad_ignore, &patched_bins, &options)?;
 
     // Pack metadata
     pack_metadata(&mut zip, mod_project, project_root, &options)?;
@@ -85,9 +96,35 @@ pub fn pack_to_fantome<W: Write + std::io::Seek>(
     Ok(())
 }
 
+/// Patched bin files of the base layer, keyed by their lowercase path in the layer.
+fn patched_base_bins(
+    project_root: &Path,
+    mod_project: &ModProject,
+) -> Result<HashMap<String, Vec<u8>>> {
+    let project_root = Utf8Path::from_path(project_root).ok_or_else(|| {
+        eyre::eyre!(
+            "Project path is not valid UTF-8: {}",
+            project_root.display()
+        )
+    })?;
+
+    Ok(apply_bin_patches(project_root, mod_project)?
+        .into_iter()
+        .filter(|bin| bin.layer == "base")
+        .map(|bin| (layer_path_key(bin.path.as_str()), bin.data))
+        .collect())
+}
+
+/// Game paths are case-insensitive, the same file may be spelled differently in a patch path.
+fn layer_path_key(path: &str) -> String {
+    path.replace('\\', "/").to_lowercase()
+}
+
 fn pack_base_layer<W: Write + std::io::Seek>(
     zip: &mut ZipWriter<W>,
     project_root: &Path,
+    wad_ignore: &WadIgnore,
+    patched_bins: &HashMap<String, Vec<u8>>,
     options: &SimpleFileOptions,
 ) -> Result<()> {
     let base_layer_path = project_root.join("content").join("base");
@@ -99,11 +136,14 @@ fn pack_base_layer<W: Write + std::io::Seek>(
         ));
     }
 
-    // Iterate through all .wad.client directories in the base layer
-    for entry in read_dir(&base_layer_path)? {
-        let entry = entry?;
-        let path = entry.path();
+    // Iterate through all .wad.client directories in the base layer, sorted so
+    // the archive layout doesn't depend on filesystem enumeration order
+    let mut paths = read_dir(&base_layer_path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    paths.sort();
 
+    for path in paths {
         if path.is_dir()
             && path
                 .file_name()
@@ -112,7 +152,15 @@ fn pack_base_layer<W: Write + std::io::Seek>(
                 .ends_with(".wad.client")
         {
             let wad_name = path.file_name().unwrap().to_string_lossy();
-            pack_wad_directory(zip, &path, &format!("WAD/{}", wad_name), options)?;
+            pack_wad_directory(
+                zip,
+                &base_layer_path,
+                &path,
+                &format!("WAD/{}", wad_name),
+                wad_ignore,
+                patched_bins,
+                options,
+            )?;
         }
     }
 
@@ -121,25 +169,41 @@ fn pack_base_layer<W: Write + std::io::Seek>(
 
 fn pack_wad_directory<W: Write + std::io::Seek>(
     zip: &mut ZipWriter<W>,
+    layer_dir: &Path,
     wad_dir: &Path,
     zip_prefix: &str,
+    wad_ignore: &WadIgnore,
+    patched_bins: &HashMap<String, Vec<u8>>,
     options: &SimpleFileOptions,
 ) -> Result<()> {
-    for entry in walkdir::WalkDir::new(wad_dir).into_iter() {
+    for entry in walkdir::WalkDir::new(wad_dir).sort_by_file_name() {
         let entry = entry.map_err(|e| eyre::eyre!("Failed to walk directory: {}", e))?;
         let path = entry.path();
 
-        if path.is_file() {
-            let relative_path = path.strip_prefix(wad_dir)?;
-            let zip_path = format!(
-                "{}/{}",
-                zip_prefix,
-                relative_path.to_string_lossy().replace('\\', "/")
-            );
-
-            zip.start_file(zip_path, *options)?;
-            let mut file = File::open(path)?;
-            std::io::copy(&mut file, zip)?;
+        if !path.is_file() {
+            continue;
+        }
+
+        // Ignore rules match paths from the layer directory, like for modpkg layers
+        let layer_path = path.strip_prefix(layer_dir)?.to_string_lossy();
+        if wad_ignore.is_ignored(&layer_path) {
+            continue;
+        }
+
+        let relative_path = path.strip_prefix(wad_dir)?;
+        let zip_path = format!(
+            "{}/{}",
+            zip_prefix,
+            relative_path.to_string_lossy().replace('\\', "/")
+        );
+
+        zip.start_file(zip_path, *options)?;
+        match patched_bins.get(&layer_path_key(&layer_path)) {
+            Some(data) => zip.write_all(data)?,
+            None => {
+                let mut file = File::open(path)?;
+                std::io::copy(&mut file, zip)?;
+            }
         }
     }
 
@@ -213,3 +277,106 @@ fn format_authors(authors: &[ModProjectAuthor]) -> String {
 
     author_names.join(", ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use tempfile::tempdir;
+
+    fn create_test_project(root: &Path) -> ModProject {
+        let wad_dir = root.join("content/base/aatrox.wad.client/data");
+        std::fs::create_dir_all(&wad_dir).unwrap();
+        std::fs::write(wad_dir.join("skin0.bin"), b"skin0").unwrap();
+        std::fs::write(wad_dir.join("skin1.bin"), b"skin1").unwrap();
+        std::fs::write(root.join("README.md"), "# Test").unwrap();
+
+        serde_json::from_str(
+            r#"{"name": "test", "display_name": "Test", "version": "1.0.0", "description": "", "authors": ["Author"]}"#,
+        )
+        .unwrap()
+    }
+
+    fn pack(mod_project: &ModProject, root: &Path) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        pack_to_fantome(&mut buffer, mod_project, root).unwrap();
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn test_pack_applies_bin_patches() {
+        use ltk_meta::value::F32Value;
+        use ltk_meta::{BinProperty, BinTree, BinTreeObject, PropertyValueEnum};
+        use ltk_modpkg::bin_patch::hash_bin_name;
+        use std::io::Read;
+
+        let object_hash = hash_bin_name("Characters/Test/Skins/Skin0");
+        let name_hash = hash_bin_name("skinScale");
+        let mut object = BinTreeObject::new(object_hash, hash_bin_name("SkinData"));
+        object.properties.insert(
+            name_hash,
+            BinProperty {
+                name_hash,
+                value: PropertyValueEnum::F32(F32Value(1.0)),
+            },
+        );
+        let mut bin = Cursor::new(Vec::new());
+        BinTree::new([object], Vec::<String>::new())
+            .to_writer(&mut bin)
+            .unwrap();
+
+        let dir = tempdir().unwrap();
+        let mod_project = create_test_project(dir.path());
+        let bin_path = dir
+            .path()
+            .join("content/base/aatrox.wad.client/data/skin0.bin");
+        std::fs::write(&bin_path, bin.get_ref()).unwrap();
+        let patch_dir = dir.path().join("patches/base/aatrox.wad.client/data");
+        std::fs::create_dir_all(&patch_dir).unwrap();
+        std::fs::write(
+            patch_dir.join("skin0.bin.json"),
+            r#"{"edits": [{"object": "Characters/Test/Skins/Skin0", "field": "skinScale", "value": 0.5}]}"#,
+        )
+        .unwrap();
+
+        let mut archive =
+            zip::ZipArchive::new(Cursor::new(pack(&mod_project, dir.path()))).unwrap();
+        let mut data = Vec::new();
+        archive
+            .by_name("WAD/aatrox.wad.client/data/skin0.bin")
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+
+        let tree = BinTree::from_reader(&mut Cursor::new(data)).unwrap();
+        let object = tree.get_object(object_hash).unwrap();
+        assert_eq!(
+            object.properties[&name_hash].value,
+            PropertyValueEnum::F32(F32Value(0.5))
+        );
+        // Files without a patch are packed as they are
+        let mut data = Vec::new();
+        archive
+            .by_name("WAD/aatrox.wad.client/data/skin1.bin")
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        assert_eq!(data, b"skin1");
+    }
+
+    #[test]
+    fn test_pack_is_reproducible() {
+        let dir = tempdir().unwrap();
+        let mod_project = create_test_project(dir.path());
+
+        let first = pack(&mod_project, dir.path());
+        assert_eq!(first, pack(&mod_project, dir.path()));
+
+        // Entries don't carry the packing time, so packs at different times match too
+        let mut archive = zip::ZipArchive::new(Cursor::new(first)).unwrap();
+        for i in 0..archive.len() {
+            let file = archive.by_index(i).unwrap();
+            assert_eq!(file.last_modified(), Some(zip::DateTime::default()));
+        }
+    }
+}
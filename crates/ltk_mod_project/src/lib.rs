@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+mod wad_ignore;
+
+pub use wad_ignore::{WadIgnore, WadIgnoreError, WAD_IGNORE_FILENAME};
+
 /// Describes a mod project configuration file
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct ModProject {
@@ -49,6 +53,13 @@ pub struct ModProject {
     /// Example: `thumbnail.webp`
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub thumbnail: Option<String>,
+
+    /// Gitignore-style patterns of files in the content directory that are never packed
+    /// Applied on top of the patterns in the project's `.wadignore` file
+    ///
+    /// Example: `["*.psd", "**/backup/"]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wad_ignore: Vec<String>,
 }
 
 /// Represents a layer in a mod project
@@ -167,6 +178,7 @@ mod tests {
                 },
             ],
             thumbnail: None,
+            wad_ignore: vec![],
         }
     }
 
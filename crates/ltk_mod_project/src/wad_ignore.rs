@@ -0,0 +1,200 @@
+//! Gitignore-style rules for files that are never packed into a mod.
+//!
+//! Rules come from the `.wadignore` file in the project root and from the `wad_ignore` field
+//! of the project config. Paths are matched relative to the layer directory, e.g.
+//! `aatrox.wad.client/assets/skin.psd` for a file in `content/base`.
+//!
+//! The supported syntax follows gitignore:
+//! - Blank lines and lines starting with `#` are skipped
+//! - `!` in front of a pattern includes files again that an earlier pattern ignored
+//! - A trailing `/` only matches directories, everything inside them is ignored
+//! - Patterns without a `/` match the file or directory name at any depth, other patterns
+//!   match the whole path from the layer directory
+//! - `*`, `?`, `[...]` and `**` work as in glob patterns, matching is case-insensitive
+
+use std::path::Path;
+
+use glob::{MatchOptions, Pattern};
+
+use crate::ModProject;
+
+/// Name of the ignore file in the project root.
+pub const WAD_IGNORE_FILENAME: &str = ".wadignore";
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WadIgnoreError {
+    #[error("Failed to read {WAD_IGNORE_FILENAME}: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid ignore pattern {pattern:?}: {source}")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: glob::PatternError,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct WadIgnoreRule {
+    pattern: Pattern,
+    negated: bool,
+    dir_only: bool,
+    /// Whether the pattern matches the whole path instead of the name.
+    anchored: bool,
+}
+
+/// A set of ignore rules, see the [module docs](self) for the syntax.
+#[derive(Debug, Clone, Default)]
+pub struct WadIgnore {
+    rules: Vec<WadIgnoreRule>,
+}
+
+impl WadIgnore {
+    /// Creates a set from pattern lines, later lines take precedence.
+    pub fn new<I, S>(lines: I) -> Result<Self, WadIgnoreError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut rules = Vec::new();
+        for line in lines {
+            let line = line.as_ref().trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (negated, pattern) = match line.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, line),
+            };
+            let (dir_only, pattern) = match pattern.strip_suffix('/') {
+                Some(pattern) => (true, pattern),
+                None => (false, pattern),
+            };
+            let anchored = pattern.contains('/');
+            let pattern = pattern.trim_start_matches('/');
+
+            rules.push(WadIgnoreRule {
+                pattern: Pattern::new(pattern).map_err(|source| {
+                    WadIgnoreError::InvalidPattern {
+                        pattern: line.to_string(),
+                        source,
+                    }
+                })?,
+                negated,
+                dir_only,
+                anchored,
+            });
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Loads the `.wadignore` file of `project_root`, if any, and the patterns of `mod_project`.
+    pub fn load(project_root: &Path, mod_project: &ModProject) -> Result<Self, WadIgnoreError> {
+        let content = match std::fs::read_to_string(project_root.join(WAD_IGNORE_FILENAME)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Self::new(
+            content
+                .lines()
+                .chain(mod_project.wad_ignore.iter().map(String::as_str)),
+        )
+    }
+
+    /// Returns `true` if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns whether the file at `relative_path` is ignored.
+    ///
+    /// `relative_path` is relative to the layer directory and may use `/` or `\` separators.
+    /// A file in an ignored directory is ignored, even if a later rule includes it again.
+    pub fn is_ignored(&self, relative_path: impl AsRef<str>) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+
+        let normalized = relative_path.as_ref().replace('\\', "/");
+        let components: Vec<&str> = normalized.split('/').filter(|c| !c.is_empty()).collect();
+        (1..=components.len()).any(|end| {
+            let is_dir = end < components.len();
+            self.matches(&components[..end].join("/"), components[end - 1], is_dir)
+        })
+    }
+
+    fn matches(&self, path: &str, name: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+
+            let candidate = if rule.anchored { path } else { name };
+            if rule.pattern.matches_with(candidate, MATCH_OPTIONS) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_patterns_match_at_any_depth() {
+        let ignore = WadIgnore::new(["# sources", "*.psd", "Thumbs.db", ""]).unwrap();
+
+        assert!(ignore.is_ignored("aatrox.wad.client/assets/skin.psd"));
+        assert!(ignore.is_ignored("aatrox.wad.client\\assets\\thumbs.db"));
+        assert!(!ignore.is_ignored("aatrox.wad.client/assets/skin.dds"));
+    }
+
+    #[test]
+    fn test_directory_and_anchored_patterns() {
+        let ignore = WadIgnore::new(["backup/", "/aatrox.wad.client/data/*.bin"]).unwrap();
+
+        assert!(ignore.is_ignored("aatrox.wad.client/backup/skin.dds"));
+        assert!(!ignore.is_ignored("aatrox.wad.client/assets/backup"));
+        assert!(ignore.is_ignored("aatrox.wad.client/data/skin0.bin"));
+        assert!(!ignore.is_ignored("aatrox.wad.client/data/skins/skin0.bin"));
+        assert!(!ignore.is_ignored("ahri.wad.client/data/skin0.bin"));
+    }
+
+    #[test]
+    fn test_negation() {
+        let ignore = WadIgnore::new(["*.bak", "!keep.bak", "old/", "!old/keep.dds"]).unwrap();
+
+        assert!(ignore.is_ignored("a.wad.client/skin.bak"));
+        assert!(!ignore.is_ignored("a.wad.client/keep.bak"));
+        assert!(ignore.is_ignored("a.wad.client/old/keep.dds"));
+    }
+
+    #[test]
+    fn test_load_combines_file_and_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(WAD_IGNORE_FILENAME), "*.psd\n").unwrap();
+        let mut project: ModProject = serde_json::from_str(
+            r#"{"name": "m", "display_name": "M", "version": "1.0.0", "description": "", "authors": []}"#,
+        )
+        .unwrap();
+        project.wad_ignore = vec!["*.tmp".to_string()];
+
+        let ignore = WadIgnore::load(dir.path(), &project).unwrap();
+        assert!(ignore.is_ignored("a.wad.client/skin.psd"));
+        assert!(ignore.is_ignored("a.wad.client/skin.tmp"));
+        assert!(!ignore.is_ignored("a.wad.client/skin.dds"));
+    }
+}
@@ -0,0 +1,442 @@
+//! Declarative edits to `.bin` files, applied when a mod project is packed.
+//!
+//! This module requires the `project` feature to be enabled.
+//!
+//! A patch file sits at `patches/{layer}/{path}.json` and edits the bin file at
+//! `content/{layer}/{path}`. Each edit names an object, a field and the new value:
+//!
+//! ```json
+//! {
+//!   "edits": [
+//!     {
+//!       "object": "Characters/Aatrox/Skins/Skin0",
+//!       "field": "skinMeshProperties.selfIllumination",
+//!       "value": 0.7
+//!     },
+//!     {
+//!       "object": "Characters/Aatrox/Skins/Skin0",
+//!       "field": "skinMeshProperties.emissiveColor",
+//!       "value": [255, 64, 0, 255]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Object paths and field names are hashed the way the game hashes them. A name that is
+//! only known by its hash can be written as hex, e.g. `"0x3c6468f4"`. Nested fields of
+//! struct and embedded values are separated by dots. The new value must fit the type of the
+//! existing property, only primitive, vector, color, string and hash properties can be edited.
+
+use glam::{Vec2, Vec3, Vec4};
+use ltk_meta::value::*;
+use ltk_meta::{BinProperty, BinTree, PropertyValueEnum};
+use ltk_primitives::Color;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+/// Extension of bin patch files.
+pub const BIN_PATCH_EXTENSION: &str = "json";
+
+/// Error type for bin patch operations.
+#[derive(Debug, thiserror::Error)]
+pub enum BinPatchError {
+    #[error("Invalid patch file: {0}")]
+    InvalidPatch(#[from] serde_json::Error),
+
+    #[error("Failed to read bin file: {0}")]
+    InvalidBin(#[from] ltk_meta::Error),
+
+    #[error("Failed to write bin file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Object not found: {0}")]
+    ObjectNotFound(String),
+
+    #[error("Field {field} not found in object {object}")]
+    FieldNotFound { object: String, field: String },
+
+    #[error("Field {field} of object {object} is not a {expected} ({reason})")]
+    InvalidValue {
+        object: String,
+        field: String,
+        expected: String,
+        reason: String,
+    },
+
+    #[error("Field {field} of object {object} has unsupported type {kind}")]
+    UnsupportedKind {
+        object: String,
+        field: String,
+        kind: String,
+    },
+}
+
+/// The contents of a bin patch file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BinPatch {
+    pub edits: Vec<BinPatchEdit>,
+}
+
+/// A single field assignment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BinPatchEdit {
+    /// Path of the object, or its hash in hex.
+    pub object: String,
+
+    /// Dot separated field names leading to the edited property.
+    pub field: String,
+
+    /// The new value of the property.
+    pub value: serde_json::Value,
+}
+
+impl BinPatch {
+    /// Parse a patch from the contents of a patch file.
+    pub fn from_json(json: &str) -> Result<Self, BinPatchError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Apply the edits to an in-memory bin tree, in order.
+    pub fn apply(&self, tree: &mut BinTree) -> Result<(), BinPatchError> {
+        for edit in &self.edits {
+            edit.apply(tree)?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply the edits to the bytes of a bin file and return the patched file.
+    pub fn apply_to_bytes(&self, data: &[u8]) -> Result<Vec<u8>, BinPatchError> {
+        let mut tree = BinTree::from_reader(&mut Cursor::new(data))?;
+        self.apply(&mut tree)?;
+
+        let mut output = Cursor::new(Vec::with_capacity(data.len()));
+        tree.to_writer(&mut output)?;
+        Ok(output.into_inner())
+    }
+}
+
+impl BinPatchEdit {
+    fn apply(&self, tree: &mut BinTree) -> Result<(), BinPatchError> {
+        let object = tree
+            .get_object_mut(hash_bin_name(&self.object))
+            .ok_or_else(|| BinPatchError::ObjectNotFound(self.object.clone()))?;
+
+        let mut names = self.field.split('.');
+        let mut properties = &mut object.properties;
+        let mut property: &mut BinProperty;
+        loop {
+            let name = names.next().unwrap_or_default();
+            property = properties
+                .get_mut(&hash_bin_name(name))
+                .ok_or_else(|| self.field_not_found())?;
+
+            if names.clone().next().is_none() {
+                break;
+            }
+            properties = match &mut property.value {
+                PropertyValueEnum::Struct(value) => &mut value.properties,
+                PropertyValueEnum::Embedded(EmbeddedValue(value)) => &mut value.properties,
+                _ => return Err(self.field_not_found()),
+            };
+        }
+
+        property.value = self.convert_value(&property.value)?;
+        Ok(())
+    }
+
+    /// Convert the JSON value to a property value of the same kind as `current`.
+    fn convert_value(
+        &self,
+        current: &PropertyValueEnum,
+    ) -> Result<PropertyValueEnum, BinPatchError> {
+        let value = &self.value;
+        Ok(match current {
+            PropertyValueEnum::Bool(_) => PropertyValueEnum::Bool(BoolValue(self.as_bool()?)),
+            PropertyValueEnum::BitBool(_) => {
+                PropertyValueEnum::BitBool(BitBoolValue(self.as_bool()?))
+            }
+            PropertyValueEnum::I8(_) => PropertyValueEnum::I8(I8Value(self.as_int("i8")?)),
+            PropertyValueEnum::U8(_) => PropertyValueEnum::U8(U8Value(self.as_int("u8")?)),
+            PropertyValueEnum::I16(_) => PropertyValueEnum::I16(I16Value(self.as_int("i16")?)),
+            PropertyValueEnum::U16(_) => PropertyValueEnum::U16(U16Value(self.as_int("u16")?)),
+            PropertyValueEnum::I32(_) => PropertyValueEnum::I32(I32Value(self.as_int("i32")?)),
+            PropertyValueEnum::U32(_) => PropertyValueEnum::U32(U32Value(self.as_int("u32")?)),
+            PropertyValueEnum::I64(_) => PropertyValueEnum::I64(I64Value(self.as_int("i64")?)),
+            PropertyValueEnum::U64(_) => PropertyValueEnum::U64(U64Value(self.as_int("u64")?)),
+            PropertyValueEnum::F32(_) => PropertyValueEnum::F32(F32Value(self.as_f32()?)),
+            PropertyValueEnum::Vector2(_) => {
+                let [x, y] = self.as_floats("vector2")?;
+                PropertyValueEnum::Vector2(Vector2Value(Vec2::new(x, y)))
+            }
+            PropertyValueEnum::Vector3(_) => {
+                let [x, y, z] = self.as_floats("vector3")?;
+                PropertyValueEnum::Vector3(Vector3Value(Vec3::new(x, y, z)))
+            }
+            PropertyValueEnum::Vector4(_) => {
+                let [x, y, z, w] = self.as_floats("vector4")?;
+                PropertyValueEnum::Vector4(Vector4Value(Vec4::new(x, y, z, w)))
+            }
+            PropertyValueEnum::Color(_) => {
+                let [r, g, b, a] = self.as_color()?;
+                PropertyValueEnum::Color(ColorValue(Color::new(r, g, b, a)))
+            }
+            PropertyValueEnum::String(_) => PropertyValueEnum::String(StringValue(
+                value
+                    .as_str()
+                    .ok_or_else(|| self.invalid_value("string", "expected a string"))?
+                    .to_string(),
+            )),
+            PropertyValueEnum::Hash(_) => {
+                PropertyValueEnum::Hash(HashValue(hash_bin_name(self.as_str("hash")?)))
+            }
+            PropertyValueEnum::ObjectLink(_) => PropertyValueEnum::ObjectLink(ObjectLinkValue(
+                hash_bin_name(self.as_str("object link")?),
+            )),
+            PropertyValueEnum::WadChunkLink(_) => PropertyValueEnum::WadChunkLink(
+                WadChunkLinkValue(hash_wad_chunk_path(self.as_str("wad chunk link")?)),
+            ),
+            other => {
+                return Err(BinPatchError::UnsupportedKind {
+                    object: self.object.clone(),
+                    field: self.field.clone(),
+                    kind: format!("{:?}", other.kind()),
+                })
+            }
+        })
+    }
+
+    fn as_bool(&self) -> Result<bool, BinPatchError> {
+        self.value
+            .as_bool()
+            .ok_or_else(|| self.invalid_value("bool", "expected true or false"))
+    }
+
+    fn as_int<T: TryFrom<i64> + TryFrom<u64>>(&self, expected: &str) -> Result<T, BinPatchError> {
+        let converted = match (self.value.as_i64(), self.value.as_u64()) {
+            (Some(value), _) => T::try_from(value).ok(),
+            (None, Some(value)) => T::try_from(value).ok(),
+            (None, None) => {
+                return Err(self.invalid_value(expected, "expected an integer"));
+            }
+        };
+
+        converted.ok_or_else(|| self.invalid_value(expected, "out of range"))
+    }
+
+    fn as_f32(&self) -> Result<f32, BinPatchError> {
+        self.value
+            .as_f64()
+            .map(|value| value as f32)
+            .ok_or_else(|| self.invalid_value("f32", "expected a number"))
+    }
+
+    fn as_floats<const N: usize>(&self, expected: &str) -> Result<[f32; N], BinPatchError> {
+        let reason = format!("expected an array of {} numbers", N);
+        let items = self
+            .value
+            .as_array()
+            .filter(|items| items.len() == N)
+            .ok_or_else(|| self.invalid_value(expected, &reason))?;
+
+        let mut floats = [0.0; N];
+        for (float, item) in floats.iter_mut().zip(items) {
+            *float = item
+                .as_f64()
+                .ok_or_else(|| self.invalid_value(expected, &reason))? as f32;
+        }
+        Ok(floats)
+    }
+
+    fn as_color(&self) -> Result<[u8; 4], BinPatchError> {
+        let reason = "expected an array of 4 integers from 0 to 255";
+        let items = self
+            .value
+            .as_array()
+            .filter(|items| items.len() == 4)
+            .ok_or_else(|| self.invalid_value("color", reason))?;
+
+        let mut color = [0; 4];
+        for (channel, item) in color.iter_mut().zip(items) {
+            *channel = item
+                .as_u64()
+                .and_then(|value| u8::try_from(value).ok())
+                .ok_or_else(|| self.invalid_value("color", reason))?;
+        }
+        Ok(color)
+    }
+
+    fn as_str(&self, expected: &str) -> Result<&str, BinPatchError> {
+        self.value
+            .as_str()
+            .ok_or_else(|| self.invalid_value(expected, "expected a name or a hex hash"))
+    }
+
+    fn field_not_found(&self) -> BinPatchError {
+        BinPatchError::FieldNotFound {
+            object: self.object.clone(),
+            field: self.field.clone(),
+        }
+    }
+
+    fn invalid_value(&self, expected: &str, reason: &str) -> BinPatchError {
+        BinPatchError::InvalidValue {
+            object: self.object.clone(),
+            field: self.field.clone(),
+            expected: expected.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Hash an object path, field name or hash value, accepting `0x` prefixed hex hashes as is.
+pub fn hash_bin_name(name: &str) -> u32 {
+    name.strip_prefix("0x")
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .unwrap_or_else(|| ltk_hash::fnv1a::hash_lower(name))
+}
+
+/// Hash a WAD chunk path, accepting `0x` prefixed hex hashes as is.
+fn hash_wad_chunk_path(path: &str) -> u64 {
+    path.strip_prefix("0x")
+        .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+        .unwrap_or_else(|| crate::utils::hash_chunk_name(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+    use ltk_meta::BinTreeObject;
+
+    const OBJECT_PATH: &str = "Characters/Test/Skins/Skin0";
+
+    fn property(name: &str, value: PropertyValueEnum) -> (u32, BinProperty) {
+        let name_hash = hash_bin_name(name);
+        (name_hash, BinProperty { name_hash, value })
+    }
+
+    fn test_tree() -> BinTree {
+        let mut object = BinTreeObject::new(hash_bin_name(OBJECT_PATH), hash_bin_name("SkinData"));
+        object.properties = IndexMap::from([
+            property("skinScale", PropertyValueEnum::F32(F32Value(1.0))),
+            property(
+                "skinMeshProperties",
+                PropertyValueEnum::Embedded(EmbeddedValue(StructValue {
+                    class_hash: hash_bin_name("SkinMeshDataProperties"),
+                    properties: IndexMap::from([property(
+                        "emissiveColor",
+                        PropertyValueEnum::Color(ColorValue(Color::new(0, 0, 0, 255))),
+                    )]),
+                })),
+            ),
+        ]);
+
+        BinTree::new([object], Vec::<String>::new())
+    }
+
+    fn patch(json: &str) -> BinPatch {
+        BinPatch::from_json(json).unwrap()
+    }
+
+    fn get_value<'a>(tree: &'a BinTree, field: &str) -> &'a PropertyValueEnum {
+        let object = tree.get_object(hash_bin_name(OBJECT_PATH)).unwrap();
+        let mut names = field.split('.');
+        let mut value = &object.properties[&hash_bin_name(names.next().unwrap())].value;
+        for name in names {
+            let PropertyValueEnum::Embedded(EmbeddedValue(inner)) = value else {
+                panic!("{} is not embedded", field);
+            };
+            value = &inner.properties[&hash_bin_name(name)].value;
+        }
+        value
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let mut tree = test_tree();
+        patch(
+            r#"{"edits": [
+                {"object": "characters/test/skins/skin0", "field": "skinScale", "value": 1.5},
+                {"object": "Characters/Test/Skins/Skin0", "field": "skinMeshProperties.emissiveColor", "value": [255, 64, 0, 255]}
+            ]}"#,
+        )
+        .apply(&mut tree)
+        .unwrap();
+
+        assert_eq!(
+            get_value(&tree, "skinScale"),
+            &PropertyValueEnum::F32(F32Value(1.5))
+        );
+        assert_eq!(
+            get_value(&tree, "skinMeshProperties.emissiveColor"),
+            &PropertyValueEnum::Color(ColorValue(Color::new(255, 64, 0, 255)))
+        );
+    }
+
+    #[test]
+    fn test_apply_hex_names() {
+        let mut tree = test_tree();
+        let json = format!(
+            r#"{{"edits": [{{"object": "0x{:08x}", "field": "0x{:08x}", "value": 2}}]}}"#,
+            hash_bin_name(OBJECT_PATH),
+            hash_bin_name("skinScale")
+        );
+        patch(&json).apply(&mut tree).unwrap();
+
+        assert_eq!(
+            get_value(&tree, "skinScale"),
+            &PropertyValueEnum::F32(F32Value(2.0))
+        );
+    }
+
+    #[test]
+    fn test_apply_errors() {
+        let mut tree = test_tree();
+
+        let result =
+            patch(r#"{"edits": [{"object": "Missing", "field": "skinScale", "value": 1}]}"#)
+                .apply(&mut tree);
+        assert!(matches!(result, Err(BinPatchError::ObjectNotFound(_))));
+
+        let result = patch(&format!(
+            r#"{{"edits": [{{"object": "{}", "field": "skinScale.inner", "value": 1}}]}}"#,
+            OBJECT_PATH
+        ))
+        .apply(&mut tree);
+        assert!(matches!(result, Err(BinPatchError::FieldNotFound { .. })));
+
+        let result = patch(&format!(
+            r#"{{"edits": [{{"object": "{}", "field": "skinMeshProperties.emissiveColor", "value": [256, 0, 0, 0]}}]}}"#,
+            OBJECT_PATH
+        ))
+        .apply(&mut tree);
+        assert!(matches!(result, Err(BinPatchError::InvalidValue { .. })));
+
+        let result = patch(&format!(
+            r#"{{"edits": [{{"object": "{}", "field": "skinMeshProperties", "value": 1}}]}}"#,
+            OBJECT_PATH
+        ))
+        .apply(&mut tree);
+        assert!(matches!(result, Err(BinPatchError::UnsupportedKind { .. })));
+    }
+
+    #[test]
+    fn test_apply_to_bytes_roundtrip() {
+        let mut data = Cursor::new(Vec::new());
+        test_tree().to_writer(&mut data).unwrap();
+
+        let patched = patch(&format!(
+            r#"{{"edits": [{{"object": "{}", "field": "skinScale", "value": 0.5}}]}}"#,
+            OBJECT_PATH
+        ))
+        .apply_to_bytes(data.get_ref())
+        .unwrap();
+
+        let tree = BinTree::from_reader(&mut Cursor::new(patched)).unwrap();
+        assert_eq!(
+            get_value(&tree, "skinScale"),
+            &PropertyValueEnum::F32(F32Value(0.5))
+        );
+    }
+}
@@ -244,9 +244,8 @@ impl ModpkgBuilder {
 
         // Then process all non-meta chunks via the generic pipeline
         let regular_chunks: Vec<_> = self
-            .chunks
-            .values()
-            .chain(self.meta_chunks.values())
+            .ordered_chunks()
+            .into_iter()
             .filter(|chunk| !meta_path_hashes.contains(&chunk.path_hash))
             .collect();
 
@@ -374,10 +373,29 @@ impl ModpkgBuilder {
         Ok((compressed_data, compression))
     }
 
+    /// Returns the regular chunks followed by the meta chunks, each group sorted by chunk key.
+    ///
+    /// The chunk maps are hash maps, so iterating them directly would make the path, layer and
+    /// wad tables as well as the chunk data order differ between runs. Everything that is
+    /// written to the output goes through this so that identical inputs produce identical files.
+    fn ordered_chunks(&self) -> Vec<&ModpkgChunkBuilder> {
+        let mut chunks = self.chunks.iter().collect::<Vec<_>>();
+        chunks.sort_unstable_by_key(|(key, _)| **key);
+
+        let mut meta_chunks = self.meta_chunks.iter().collect::<Vec<_>>();
+        meta_chunks.sort_unstable_by_key(|(key, _)| **key);
+
+        chunks
+            .into_iter()
+            .chain(meta_chunks)
+            .map(|(_, chunk)| chunk)
+            .collect()
+    }
+
     fn collect_unique_layers(&self) -> (Vec<String>, HashMap<u64, u32>) {
         let mut layers = Vec::new();
         let mut layer_indices = HashMap::new();
-        for chunk in self.chunks.values().chain(self.meta_chunks.values()) {
+        for chunk in self.ordered_chunks() {
             // Skip empty layer names (they represent chunks with no layer)
             if chunk.layer.is_empty() {
                 continue;
@@ -398,7 +416,7 @@ impl ModpkgBuilder {
         let mut path_indices = HashMap::new();
 
         // Collect paths from both regular chunks and meta chunks
-        for chunk in self.chunks.values().chain(self.meta_chunks.values()) {
+        for chunk in self.ordered_chunks() {
             path_indices.entry(chunk.path_hash).or_insert_with(|| {
                 let index = paths.len();
                 paths.push(chunk.path.clone());
@@ -412,7 +430,7 @@ impl ModpkgBuilder {
     fn collect_unique_wads(&self) -> (Vec<String>, HashMap<u64, u32>) {
         let mut wads = Vec::new();
         let mut wad_indices = HashMap::new();
-        for chunk in self.chunks.values().chain(self.meta_chunks.values()) {
+        for chunk in self.ordered_chunks() {
             // Skip empty wad names (they represent chunks with no wad)
             if chunk.wad.is_empty() {
                 continue;
@@ -743,6 +761,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_modpkg_builder_is_deterministic() {
+        let build = || {
+            let mut builder = ModpkgBuilder::default()
+                .with_layer(ModpkgLayerBuilder::base())
+                .with_layer(ModpkgLayerBuilder::new("chroma").with_priority(1))
+                .with_readme("readme")
+                .unwrap();
+            for i in 0..32 {
+                for layer in ["base", "chroma"] {
+                    let mut chunk = ModpkgChunkBuilder::new()
+                        .with_path(&format!("assets/file_{i}.bin"))
+                        .unwrap()
+                        .with_layer(layer);
+                    chunk.wad = format!("wad_{}.wad.client", i % 4);
+                    builder = builder.with_chunk(chunk);
+                }
+            }
+
+            let mut cursor = Cursor::new(Vec::new());
+            builder
+                .build_to_writer(&mut cursor, |chunk, cursor| {
+                    cursor.write_all(chunk.path.as_bytes())?;
+                    Ok(())
+                })
+                .expect("Failed to build Modpkg");
+            cursor.into_inner()
+        };
+
+        // Each builder gets freshly seeded hash maps, so any dependence on map
+        // iteration order shows up as differing output.
+        let first = build();
+        for _ in 0..4 {
+            assert_eq!(build(), first);
+        }
+    }
+
     #[test]
     fn test_with_hashed_chunk_name() {
         // Test with an extension
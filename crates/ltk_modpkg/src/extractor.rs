@@ -7,6 +7,9 @@ use std::{
 
 use crate::{chunk::ModpkgChunk, error::ModpkgError, Modpkg};
 
+/// Files at least this large get their full size allocated before writing.
+const PREALLOCATE_THRESHOLD: u64 = 1024 * 1024;
+
 /// Extractor for ModPkg archives.
 ///
 /// This struct provides functionality to extract chunks from a ModPkg archive
@@ -21,6 +24,16 @@ impl<'modpkg, TSource: Read + Seek> ModpkgExtractor<'modpkg, TSource> {
         Self { modpkg }
     }
 
+    /// Total uncompressed size of the chunks [`Self::extract_all`] writes, in bytes.
+    pub fn required_space(&self) -> u64 {
+        self.modpkg
+            .chunks
+            .iter()
+            .filter(|((_, layer_hash), _)| self.modpkg.layers.contains_key(layer_hash))
+            .map(|(_, chunk)| chunk.uncompressed_size)
+            .sum()
+    }
+
     /// Extract all chunks from the ModPkg to the specified output directory.
     ///
     /// Chunks will be organized by layer, with each layer having its own subdirectory.
@@ -81,8 +94,12 @@ impl<'modpkg, TSource: Read + Seek> ModpkgExtractor<'modpkg, TSource> {
         // Load and decompress the chunk data
         let data = self.modpkg.decoder().load_chunk_decompressed(chunk)?;
 
-        // Write the data to the output file
+        // Write the data to the output file, allocating large files up front to keep them
+        // contiguous on disk
         let mut file = File::create(&output_path)?;
+        if data.len() as u64 >= PREALLOCATE_THRESHOLD {
+            file.set_len(data.len() as u64)?;
+        }
         file.write_all(&data)?;
 
         Ok(output_path)
@@ -224,4 +241,47 @@ mod tests {
         assert_eq!(extracted_base_data, base_data);
         assert_eq!(extracted_custom_data, custom_data);
     }
+
+    #[test]
+    fn test_required_space() {
+        let mut cursor = Cursor::new(Vec::new());
+
+        let builder = ModpkgBuilder::default()
+            .with_layer(ModpkgLayerBuilder::base())
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("small.bin")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::Zstd),
+            )
+            .with_chunk(
+                ModpkgChunkBuilder::new()
+                    .with_path("large.bin")
+                    .unwrap()
+                    .with_compression(ModpkgCompression::Zstd),
+            );
+
+        builder
+            .build_to_writer(&mut cursor, |chunk, cursor| {
+                if chunk.path == "small.bin" {
+                    cursor.write_all(&[0xAA; 100])?;
+                } else {
+                    cursor.write_all(&vec![0xBB; PREALLOCATE_THRESHOLD as usize * 2])?;
+                }
+                Ok(())
+            })
+            .expect("Failed to build Modpkg");
+
+        cursor.set_position(0);
+        let mut modpkg = Modpkg::mount_from_reader(cursor).unwrap();
+        let mut extractor = ModpkgExtractor::new(&mut modpkg);
+        assert_eq!(extractor.required_space(), 100 + PREALLOCATE_THRESHOLD * 2);
+
+        let temp_dir = tempdir().unwrap();
+        extractor.extract_all(temp_dir.path()).unwrap();
+
+        let large = fs::read(temp_dir.path().join("base").join("large.bin")).unwrap();
+        assert_eq!(large.len() as u64, PREALLOCATE_THRESHOLD * 2);
+        assert!(large.iter().all(|&b| b == 0xBB));
+    }
 }
@@ -20,6 +20,8 @@ mod readme;
 mod thumbnail;
 pub mod utils;
 
+#[cfg(feature = "project")]
+pub mod bin_patch;
 #[cfg(feature = "project")]
 pub mod project;
 
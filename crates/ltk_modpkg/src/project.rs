@@ -15,13 +15,16 @@
 //! ```
 
 use crate::{
+    bin_patch::{BinPatch, BinPatchError, BIN_PATCH_EXTENSION},
     builder::{ModpkgBuilder, ModpkgBuilderError, ModpkgChunkBuilder, ModpkgLayerBuilder},
-    utils::hash_layer_name,
+    utils::{hash_chunk_name, hash_layer_name},
     ModpkgCompression, ModpkgLayerMetadata, ModpkgMetadata,
 };
 use camino::{Utf8Path, Utf8PathBuf};
 use image::ImageFormat;
-use ltk_mod_project::{ModProject, ModProjectAuthor, ModProjectLayer, ModProjectLicense};
+use ltk_mod_project::{
+    ModProject, ModProjectAuthor, ModProjectLayer, ModProjectLicense, WadIgnore, WadIgnoreError,
+};
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{self, BufWriter, Cursor, Read, Write};
@@ -56,8 +59,42 @@ pub enum PackError {
     #[error("Glob pattern error: {0}")]
     GlobError(#[from] glob::PatternError),
 
+    #[error("Invalid ignore rules: {0}")]
+    WadIgnore(#[from] WadIgnoreError),
+
     #[error("Invalid UTF-8 path: {0}")]
     InvalidUtf8Path(String),
+
+    #[error("Files {first} and {second} map to the same chunk path hash {path_hash:016x} in layer {layer}")]
+    ChunkPathCollision {
+        layer: String,
+        path_hash: u64,
+        first: Utf8PathBuf,
+        second: Utf8PathBuf,
+    },
+
+    #[error("Bin patch {patch} has no matching file at {target}")]
+    BinPatchTargetMissing {
+        patch: Utf8PathBuf,
+        target: Utf8PathBuf,
+    },
+
+    #[error("Failed to apply bin patch {patch}: {source}")]
+    BinPatch {
+        patch: Utf8PathBuf,
+        #[source]
+        source: BinPatchError,
+    },
+}
+
+/// A bin file of a mod project with its patch applied.
+#[derive(Debug, Clone)]
+pub struct PatchedBin {
+    /// Name of the layer the file belongs to.
+    pub layer: String,
+    /// Path relative to the layer directory, e.g. `aatrox.wad.client/data/skin0.bin`.
+    pub path: Utf8PathBuf,
+    pub data: Vec<u8>,
 }
 
 /// Options for packing a mod project.
@@ -95,6 +132,10 @@ pub fn create_file_name(mod_project: &ModProject, custom_name: Option<String>) -
 
 /// Pack a mod project to a `.modpkg` file.
 ///
+/// Bin files with a patch in the `patches` directory are packed with the patch applied,
+/// see [`crate::bin_patch`]. Files matching the project's ignore rules are skipped, see
+/// [`WadIgnore`].
+///
 /// # Arguments
 ///
 /// * `project_root` - Path to the mod project directory (containing `mod.config.json` or `mod.config.toml`)
@@ -114,6 +155,8 @@ pub fn pack_from_project(
     // Validate layers
     validate_layers(mod_project, project_root)?;
 
+    let wad_ignore = WadIgnore::load(project_root.as_std_path(), mod_project)?;
+
     // Build the modpkg
     let mut builder = ModpkgBuilder::default().with_layer(ModpkgLayerBuilder::base());
     let mut chunk_filepaths: HashMap<(u64, u64), Utf8PathBuf> = HashMap::new();
@@ -122,7 +165,26 @@ pub fn pack_from_project(
     builder = build_metadata(builder, mod_project)?;
 
     // Add layers and their content
-    builder = build_layers(builder, &content_dir, mod_project, &mut chunk_filepaths)?;
+    builder = build_layers(
+        builder,
+        &content_dir,
+        mod_project,
+        &wad_ignore,
+        &mut chunk_filepaths,
+    )?;
+
+    // Apply bin patches up front so patch errors aren't reported as write errors
+    let patched_chunks: HashMap<(u64, u64), Vec<u8>> =
+        apply_bin_patches(project_root, mod_project)?
+            .into_iter()
+            .map(|bin| {
+                let key = (
+                    hash_chunk_name(bin.path.as_str()),
+                    hash_layer_name(&bin.layer),
+                );
+                (key, bin.data)
+            })
+            .collect();
 
     // Add meta chunks (README, thumbnail)
     builder = add_meta_chunks(builder, project_root, mod_project)?;
@@ -139,7 +201,7 @@ pub fn pack_from_project(
 
     builder
         .build_to_writer(&mut writer, |chunk_builder, cursor| {
-            write_chunk_payload(chunk_builder, cursor, &chunk_filepaths)
+            write_chunk_payload(chunk_builder, cursor, &chunk_filepaths, &patched_chunks)
                 .map_err(ModpkgBuilderError::from)
         })
         .map_err(PackError::Builder)?;
@@ -272,6 +334,7 @@ fn build_layers(
     mut builder: ModpkgBuilder,
     content_dir: &Utf8Path,
     mod_project: &ModProject,
+    wad_ignore: &WadIgnore,
     chunk_filepaths: &mut HashMap<(u64, u64), Utf8PathBuf>,
 ) -> Result<ModpkgBuilder, PackError> {
     // Process base layer
@@ -279,6 +342,7 @@ fn build_layers(
         builder,
         content_dir,
         &ModProjectLayer::base(),
+        wad_ignore,
         chunk_filepaths,
     )?;
 
@@ -290,7 +354,7 @@ fn build_layers(
 
         builder =
             builder.with_layer(ModpkgLayerBuilder::new(&layer.name).with_priority(layer.priority));
-        builder = build_layer_from_dir(builder, content_dir, layer, chunk_filepaths)?;
+        builder = build_layer_from_dir(builder, content_dir, layer, wad_ignore, chunk_filepaths)?;
     }
 
     Ok(builder)
@@ -300,6 +364,7 @@ fn build_layer_from_dir(
     mut builder: ModpkgBuilder,
     content_dir: &Utf8Path,
     layer: &ModProjectLayer,
+    wad_ignore: &WadIgnore,
     chunk_filepaths: &mut HashMap<(u64, u64), Utf8PathBuf>,
 ) -> Result<ModpkgBuilder, PackError> {
     let layer_dir = content_dir.join(&layer.name);
@@ -311,13 +376,27 @@ fn build_layer_from_dir(
     {
         let entry = Utf8PathBuf::from_path_buf(entry)
             .map_err(|p| PackError::InvalidUtf8Path(p.display().to_string()))?;
+        if entry
+            .strip_prefix(&layer_dir)
+            .is_ok_and(|relative_path| wad_ignore.is_ignored(relative_path))
+        {
+            continue;
+        }
 
         let layer_hash = hash_layer_name(&layer.name);
         let (new_builder, path_hash) = build_chunk_from_file(builder, layer, &entry, &layer_dir)?;
 
-        chunk_filepaths
-            .entry((path_hash, layer_hash))
-            .or_insert(entry);
+        // Chunk paths are lowercased before hashing, so files that only differ in
+        // case would otherwise silently replace each other
+        if let Some(first) = chunk_filepaths.get(&(path_hash, layer_hash)) {
+            return Err(PackError::ChunkPathCollision {
+                layer: layer.name.clone(),
+                path_hash,
+                first: first.clone(),
+                second: entry,
+            });
+        }
+        chunk_filepaths.insert((path_hash, layer_hash), entry);
 
         builder = new_builder;
     }
@@ -345,6 +424,70 @@ fn build_chunk_from_file(
     Ok((builder.with_chunk(chunk_builder), path_hash))
 }
 
+/// Apply every patch in `patches/{layer}` to the matching bin file in `content/{layer}`.
+///
+/// The project files are left untouched, packers use the returned contents in place of them.
+pub fn apply_bin_patches(
+    project_root: &Utf8Path,
+    mod_project: &ModProject,
+) -> Result<Vec<PatchedBin>, PackError> {
+    let mut patched_bins = Vec::new();
+    let patches_dir = project_root.join("patches");
+    if !patches_dir.exists() {
+        return Ok(patched_bins);
+    }
+
+    let layers = std::iter::once(ModProjectLayer::base()).chain(
+        mod_project
+            .layers
+            .iter()
+            .filter(|l| l.name != "base")
+            .cloned(),
+    );
+    for layer in layers {
+        let layer_patches_dir = patches_dir.join(&layer.name);
+        let pattern = layer_patches_dir.join(format!("**/*.{}", BIN_PATCH_EXTENSION));
+
+        for entry in glob::glob(pattern.as_str())?
+            .filter_map(Result::ok)
+            .filter(|e| e.is_file())
+        {
+            let patch_path = Utf8PathBuf::from_path_buf(entry)
+                .map_err(|p| PackError::InvalidUtf8Path(p.display().to_string()))?;
+            let relative_path = patch_path
+                .strip_prefix(&layer_patches_dir)
+                .map_err(|e| PackError::Io(io::Error::other(e.to_string())))?
+                .with_extension("");
+
+            let target = project_root
+                .join("content")
+                .join(&layer.name)
+                .join(&relative_path);
+            if !target.is_file() {
+                return Err(PackError::BinPatchTargetMissing {
+                    patch: patch_path,
+                    target,
+                });
+            }
+
+            let patched = BinPatch::from_json(&fs::read_to_string(&patch_path)?)
+                .and_then(|patch| patch.apply_to_bytes(&fs::read(&target)?))
+                .map_err(|source| PackError::BinPatch {
+                    patch: patch_path.clone(),
+                    source,
+                })?;
+
+            patched_bins.push(PatchedBin {
+                layer: layer.name.clone(),
+                path: relative_path,
+                data: patched,
+            });
+        }
+    }
+
+    Ok(patched_bins)
+}
+
 fn add_meta_chunks(
     mut builder: ModpkgBuilder,
     project_root: &Utf8Path,
@@ -399,12 +542,18 @@ fn write_chunk_payload(
     chunk_builder: &ModpkgChunkBuilder,
     cursor: &mut Cursor<Vec<u8>>,
     chunk_filepaths: &HashMap<(u64, u64), Utf8PathBuf>,
+    patched_chunks: &HashMap<(u64, u64), Vec<u8>>,
 ) -> io::Result<()> {
-    // Content chunks - look up file path from the map
     let key = (
         chunk_builder.path_hash(),
         hash_layer_name(chunk_builder.layer()),
     );
+    if let Some(data) = patched_chunks.get(&key) {
+        cursor.write_all(data)?;
+        return Ok(());
+    }
+
+    // Content chunks - look up file path from the map
     if let Some(file_path) = chunk_filepaths.get(&key) {
         let mut file = File::open(file_path)?;
         let mut buffer = Vec::new();
@@ -426,6 +575,36 @@ fn write_chunk_payload(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bin_patch::hash_bin_name;
+    use crate::Modpkg;
+    use ltk_meta::value::F32Value;
+    use ltk_meta::{BinProperty, BinTree, BinTreeObject, PropertyValueEnum};
+
+    const OBJECT_PATH: &str = "Characters/Test/Skins/Skin0";
+
+    fn test_bin() -> Vec<u8> {
+        let mut object = BinTreeObject::new(hash_bin_name(OBJECT_PATH), hash_bin_name("SkinData"));
+        let name_hash = hash_bin_name("skinScale");
+        object.properties.insert(
+            name_hash,
+            BinProperty {
+                name_hash,
+                value: PropertyValueEnum::F32(F32Value(1.0)),
+            },
+        );
+
+        let mut data = Cursor::new(Vec::new());
+        BinTree::new([object], Vec::<String>::new())
+            .to_writer(&mut data)
+            .unwrap();
+        data.into_inner()
+    }
+
+    fn skin_scale(data: &[u8]) -> PropertyValueEnum {
+        let tree = BinTree::from_reader(&mut Cursor::new(data)).unwrap();
+        let object = tree.get_object(hash_bin_name(OBJECT_PATH)).unwrap();
+        object.properties[&hash_bin_name("skinScale")].value.clone()
+    }
 
     #[test]
     fn test_create_file_name() {
@@ -439,6 +618,7 @@ mod tests {
             thumbnail: None,
             layers: vec![],
             transformers: vec![],
+            wad_ignore: vec![],
         };
 
         assert_eq!(create_file_name(&project, None), "my-mod_1.2.3.modpkg");
@@ -463,4 +643,38 @@ mod tests {
         assert!(!is_valid_slug("UPPERCASE"));
         assert!(!is_valid_slug("has spaces"));
     }
+
+    #[test]
+    fn test_pack_applies_bin_patches() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(dir.path()).unwrap();
+        let bin_path = root.join("content/base/test.wad.client/data/skin0.bin");
+        let patch_path = root.join("patches/base/test.wad.client/data/skin0.bin.json");
+        fs::create_dir_all(bin_path.parent().unwrap()).unwrap();
+        fs::create_dir_all(patch_path.parent().unwrap()).unwrap();
+        fs::write(&bin_path, test_bin()).unwrap();
+        fs::write(
+            &patch_path,
+            format!(
+                r#"{{"edits": [{{"object": "{}", "field": "skinScale", "value": 0.5}}]}}"#,
+                OBJECT_PATH
+            ),
+        )
+        .unwrap();
+
+        let project: ModProject = serde_json::from_str(
+            r#"{"name": "test", "display_name": "Test", "version": "1.0.0", "description": "", "authors": []}"#,
+        )
+        .unwrap();
+        let output_path = root.join("build/test.modpkg");
+        pack_from_project(root, &output_path, &project).unwrap();
+
+        let mut modpkg = Modpkg::mount_from_reader(File::open(&output_path).unwrap()).unwrap();
+        let data = modpkg
+            .load_chunk_decompressed_by_path("test.wad.client/data/skin0.bin", Some("base"))
+            .unwrap();
+        assert_eq!(skin_scale(&data), PropertyValueEnum::F32(F32Value(0.5)));
+        // The project file itself is left as it was
+        assert_eq!(fs::read(&bin_path).unwrap(), test_bin());
+    }
 }